@@ -0,0 +1,340 @@
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use url::Url;
+
+use crate::cdn;
+use crate::fetch;
+use crate::filenames;
+use crate::image_header;
+use crate::palette::PaletteColor;
+use crate::svg;
+
+/// Assets downloaded concurrently
+const DOWNLOAD_CONCURRENCY: usize = 6;
+/// Larger assets are skipped and reported as failed
+const MAX_ASSET_BYTES: usize = 50_000_000;
+
+/// One asset to include in a bulk download
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct AssetRequest {
+    /// "image", "icon", "svg" or "font"
+    pub(crate) kind: String,
+    /// Remote URL; empty for captured inline SVGs
+    #[serde(default)]
+    pub(crate) url: String,
+    /// Inline SVG markup (captured `<svg>` elements have no URL of their own)
+    #[serde(default)]
+    pub(crate) markup: String,
+    /// Preferred file name without directory
+    #[serde(default)]
+    pub(crate) name: String,
+}
+
+/// `manifest.json` entry for one written file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ManifestEntry {
+    /// Path inside the ZIP or folder, e.g. "images/hero.jpg"
+    pub(crate) path: String,
+    pub(crate) kind: String,
+    /// URL the asset was requested from ("inline" for captured SVG markup)
+    pub(crate) source_url: String,
+    /// URL actually downloaded, when a CDN original replaced the requested rendition
+    pub(crate) resolved_url: Option<String>,
+    pub(crate) page_url: String,
+    pub(crate) sha256: String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) bytes: u64,
+    /// RFC 3339, UTC
+    pub(crate) downloaded_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct FailedAsset {
+    pub(crate) source_url: String,
+    pub(crate) error: String,
+}
+
+/// `manifest.json` at the root of every bulk download
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct DownloadManifest {
+    pub(crate) page_url: String,
+    pub(crate) created_at: String,
+    pub(crate) files: Vec<ManifestEntry>,
+    pub(crate) failed: Vec<FailedAsset>,
+    /// The page's brand palette with print values (CMYK, nearest CSS name, nearest spot color)
+    #[serde(default)]
+    pub(crate) palette: Vec<PaletteColor>,
+}
+
+/// A fetched asset waiting to be written
+struct Fetched {
+    request: AssetRequest,
+    resolved_url: Option<String>,
+    content_type: String,
+    bytes: Vec<u8>,
+    downloaded_at: String,
+}
+
+/// Where a bundle's files go as they arrive: an open ZIP or a folder
+enum BundleWriter {
+    Zip(Box<zip::ZipWriter<std::fs::File>>),
+    Folder(PathBuf),
+}
+
+impl BundleWriter {
+    fn add(&mut self, entry_path: &str, bytes: &[u8]) -> Result<(), String> {
+        check_entry_path(entry_path)?;
+        match self {
+            BundleWriter::Zip(zip) => {
+                let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+                zip.start_file(entry_path, options).map_err(|e| format!("Failed to add {}: {}", entry_path, e))?;
+                zip.write_all(bytes).map_err(|e| format!("Failed to write {}: {}", entry_path, e))
+            }
+            BundleWriter::Folder(root) => {
+                let path = root.join(entry_path);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+                }
+                std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+            }
+        }
+    }
+
+    fn finish(mut self, manifest_json: &str) -> Result<(), String> {
+        self.add("manifest.json", manifest_json.as_bytes())?;
+        if let BundleWriter::Zip(zip) = self {
+            zip.finish().map_err(|e| format!("Failed to finish ZIP: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Run blocking file I/O on the writer off the async workers, handing it back afterwards
+async fn with_writer(
+    writer: BundleWriter,
+    work: impl FnOnce(&mut BundleWriter) -> Result<(), String> + Send + 'static,
+) -> Result<BundleWriter, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut writer = writer;
+        work(&mut writer).map(|_| writer)
+    })
+    .await
+    .map_err(|e| format!("Write task failed: {}", e))?
+}
+
+fn now_rfc3339() -> String {
+    time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default()
+}
+
+fn subdirectory(kind: &str) -> &'static str {
+    match kind {
+        "icon" => "icons",
+        "svg" => "svg",
+        "font" => "fonts",
+        _ => "images",
+    }
+}
+
+/// File name for an asset: the requested name, else the URL's last path segment,
+/// with an extension detected from the content when it has none. Both come from outside
+/// (the frontend, the server), so they are sanitized to a single path component.
+fn file_name(asset: &Fetched) -> String {
+    let request = &asset.request;
+    let url = asset.resolved_url.as_deref().unwrap_or(&request.url);
+    let requested = filenames::sanitize(&request.name);
+    let name = if requested.is_empty() {
+        filenames::name_from_url(url, &asset.content_type, &asset.bytes, &request.kind)
+    } else {
+        requested
+    };
+    if request.kind == "svg" && !name.to_lowercase().ends_with(".svg") {
+        format!("{}.svg", name)
+    } else {
+        name
+    }
+}
+
+async fn fetch_asset(client: &reqwest::Client, request: &AssetRequest) -> Result<(Option<String>, String, Vec<u8>), String> {
+    if !request.markup.is_empty() {
+        let markup = svg::sanitize_svg_markup(&request.markup).ok_or("Markup contains no <svg> element")?;
+        return Ok((None, "image/svg+xml".to_string(), markup.into_bytes()));
+    }
+    let url = if request.kind == "image" { cdn::resolve_original(client, &request.url).await } else { request.url.clone() };
+    let mut response = client
+        .get(&url)
+        .header("User-Agent", fetch::USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Server returned {}", response.status()));
+    }
+    if response.content_length().is_some_and(|len| len as usize > MAX_ASSET_BYTES) {
+        return Err(format!("Larger than {} bytes", MAX_ASSET_BYTES));
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_lowercase();
+    // Chunked responses carry no Content-Length; count what actually arrives
+    let (bytes, truncated) = fetch::read_capped(&mut response, MAX_ASSET_BYTES).await?;
+    if truncated {
+        return Err(format!("Larger than {} bytes", MAX_ASSET_BYTES));
+    }
+    let resolved = (url != request.url).then_some(url);
+    Ok((resolved, content_type, bytes))
+}
+
+/// Describe a fetched asset written at `path` inside the bundle
+fn manifest_entry(asset: &Fetched, path: String, page_url: &str) -> ManifestEntry {
+    let header = image_header::read_header(&asset.bytes);
+    ManifestEntry {
+        path,
+        kind: asset.request.kind.clone(),
+        source_url: if asset.request.url.is_empty() { "inline".to_string() } else { asset.request.url.clone() },
+        resolved_url: asset.resolved_url.clone(),
+        page_url: page_url.to_string(),
+        sha256: format!("{:x}", Sha256::digest(&asset.bytes)),
+        width: header.map(|h| h.width).unwrap_or(0),
+        height: header.map(|h| h.height).unwrap_or(0),
+        bytes: asset.bytes.len() as u64,
+        downloaded_at: asset.downloaded_at.clone(),
+    }
+}
+
+/// Fetch every asset with a bounded number of requests in flight and write each one
+/// (`images/`, `icons/`, `svg/`, `fonts/`) as soon as it arrives, so at most
+/// `DOWNLOAD_CONCURRENCY` bodies are in memory. Manifest entries come back in request order.
+async fn fetch_and_write(assets: Vec<AssetRequest>, page_url: &str, mut writer: BundleWriter) -> Result<(BundleWriter, Vec<ManifestEntry>, Vec<FailedAsset>), String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(DOWNLOAD_CONCURRENCY));
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, request) in assets.into_iter().enumerate() {
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            // Held until the body is written, so finished downloads can't pile up in memory
+            let permit = semaphore.acquire_owned().await;
+            let result = fetch_asset(&client, &request).await;
+            (index, request, result, now_rfc3339(), permit)
+        });
+    }
+
+    let mut used: HashMap<&str, HashSet<String>> = HashMap::new();
+    let mut entries: Vec<(usize, ManifestEntry)> = Vec::new();
+    let mut failed = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((index, request, result, downloaded_at, _permit)) = joined else { continue };
+        let (resolved_url, content_type, bytes) = match result {
+            Ok(fetched) => fetched,
+            Err(error) => {
+                println!("[assets] Failed {}: {}", request.url, error);
+                let source_url = if request.url.is_empty() { "inline".to_string() } else { request.url };
+                failed.push(FailedAsset { source_url, error });
+                continue;
+            }
+        };
+        let asset = Fetched { request, resolved_url, content_type, bytes, downloaded_at };
+        let dir = subdirectory(&asset.request.kind);
+        let name = filenames::unique_name(used.entry(dir).or_default(), &file_name(&asset));
+        let entry = manifest_entry(&asset, format!("{}/{}", dir, name), page_url);
+        let path = entry.path.clone();
+        writer = with_writer(writer, move |writer| writer.add(&path, &asset.bytes)).await?;
+        entries.push((index, entry));
+    }
+    entries.sort_by_key(|(index, _)| *index);
+    Ok((writer, entries.into_iter().map(|(_, entry)| entry).collect(), failed))
+}
+
+/// Entry paths are built from sanitized names; refuse anything that could still leave the bundle
+/// (`..`, absolute paths, drive prefixes) rather than trust that when writing. Checked segment by
+/// segment instead of with `Path::components` so `C:\x` is refused on every platform.
+fn check_entry_path(path: &str) -> Result<(), String> {
+    let contained = !path.contains(['\\', ':']) && path.split('/').all(|segment| !matches!(segment, "" | "." | ".."));
+    if contained {
+        Ok(())
+    } else {
+        Err(format!("Refusing to write outside the bundle: {}", path))
+    }
+}
+
+/// Download `assets` and write them, with `manifest.json`, to `<dest_dir>/<bundle name>.zip`
+/// (or a folder of that name when `as_zip` is false), recording `palette` in the manifest. Returns the written path.
+pub(crate) async fn download_bundle(assets: Vec<AssetRequest>, page_url: String, palette: Vec<PaletteColor>, as_zip: bool, dest_dir: PathBuf) -> Result<String, String> {
+    println!("[assets] Downloading {} assets from {}", assets.len(), page_url);
+    let host = Url::parse(&page_url).ok().and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string())).unwrap_or_else(|| "site".to_string());
+    let stamp = time::format_description::parse_borrowed::<2>("[year][month][day]-[hour][minute][second]")
+        .ok()
+        .and_then(|format| time::OffsetDateTime::now_utc().format(&format).ok())
+        .unwrap_or_default();
+    let bundle = format!("brandsnap-{}-{}", host, stamp);
+
+    // File I/O is blocking; keep it off the async workers
+    let (path, writer) = tokio::task::spawn_blocking(move || -> Result<(PathBuf, BundleWriter), String> {
+        if as_zip {
+            let (path, file) = filenames::create_unique_file(&dest_dir, &format!("{}.zip", bundle))?;
+            Ok((path, BundleWriter::Zip(Box::new(zip::ZipWriter::new(file)))))
+        } else {
+            let path = filenames::create_unique_dir(&dest_dir, &bundle)?;
+            Ok((path.clone(), BundleWriter::Folder(path)))
+        }
+    })
+    .await
+    .map_err(|e| format!("Write task failed: {}", e))??;
+
+    let result = write_bundle(assets, &page_url, palette, writer).await;
+    if result.is_err() {
+        // The ZIP or folder was created for this bundle alone; don't leave a partial one behind
+        let _ = if as_zip { std::fs::remove_file(&path) } else { std::fs::remove_dir_all(&path) };
+    }
+    let written = result?;
+    println!("[assets] Wrote {} files to {:?}", written, path);
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Fetch and write the assets, then the manifest. Returns the number of files written.
+async fn write_bundle(assets: Vec<AssetRequest>, page_url: &str, palette: Vec<PaletteColor>, writer: BundleWriter) -> Result<usize, String> {
+    let (writer, files, failed) = fetch_and_write(assets, page_url, writer).await?;
+    if files.is_empty() {
+        return Err(format!("No assets could be downloaded ({} failed)", failed.len()));
+    }
+    let count = files.len();
+    let manifest = DownloadManifest { page_url: page_url.to_string(), created_at: now_rfc3339(), files, failed, palette };
+    let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    tokio::task::spawn_blocking(move || writer.finish(&manifest_json))
+        .await
+        .map_err(|e| format!("Write task failed: {}", e))??;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_relative_entry_paths() {
+        assert!(check_entry_path("images/logo.png").is_ok());
+        assert!(check_entry_path("manifest.json").is_ok());
+    }
+
+    #[test]
+    fn refuses_entry_paths_that_leave_the_bundle() {
+        for path in ["../x", "images/../../x", "/abs", "C:\\x", "C:/x", "a\\..\\b", "a/./b", "a//b", ""] {
+            assert!(check_entry_path(path).is_err(), "{:?} should be refused", path);
+        }
+    }
+}
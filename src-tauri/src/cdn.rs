@@ -0,0 +1,302 @@
+use regex_lite::Regex;
+use std::sync::OnceLock;
+use url::Url;
+
+use crate::fetch;
+
+/// Cloudinary transformation parameters (`w_300,h_200,c_fill`) — a path segment made only of these is dropped
+const CLOUDINARY_PARAMS: &[&str] = &[
+    "a", "ar", "b", "bo", "c", "co", "dl", "dn", "dpr", "e", "f", "fl", "fn", "fps", "g", "h", "ki", "l", "o", "p",
+    "pg", "q", "r", "so", "sp", "t", "u", "vc", "vs", "w", "x", "y", "z", "$",
+];
+
+/// Shopify size suffixes on file names: `logo_300x.png`, `hero_1024x1024_crop_center@2x.jpg`
+fn shopify_size_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"_(?:\d+x\d*|\d*x\d+|pico|icon|thumb|small|compact|medium|large|grande|original|master)(?:_crop_[a-z]+)?(?:@\dx)?$").ok()
+    })
+    .as_ref()
+}
+
+/// WordPress thumbnail suffixes: `hero-300x200.jpg`
+fn wordpress_size_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"-\d+x\d+$").ok()).as_ref()
+}
+
+/// The copy WordPress makes of very large uploads: `hero-scaled.jpg`
+fn wordpress_scaled_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"-scaled$").ok()).as_ref()
+}
+
+/// Image CDNs that resize through plain query parameters (`?w=800&q=75`) and serve the stored
+/// original without them. Elsewhere those parameters may select a different file entirely.
+const RESIZE_QUERY_HOSTS: &[&str] = &[
+    "images.unsplash.com", "plus.unsplash.com", "images.pexels.com", "images.prismic.io", "cdn.builder.io",
+    "www.datocms-assets.com",
+];
+
+/// HEAD probes for original renditions give up after this long
+const HEAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(4);
+
+/// Query parameters that only resize or re-encode
+const RESIZE_PARAMS: &[&str] = &[
+    "w", "h", "width", "height", "fit", "crop", "q", "quality", "auto", "dpr", "fm", "format", "rect", "resize", "fl",
+];
+
+fn strip_query(url: &Url) -> Url {
+    let mut stripped = url.clone();
+    stripped.set_query(None);
+    stripped
+}
+
+fn strip_resize_params(url: &Url) -> Option<Url> {
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !RESIZE_PARAMS.contains(&k.to_lowercase().as_str()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if url.query().is_none() || kept.len() == url.query_pairs().count() {
+        return None;
+    }
+    let mut stripped = url.clone();
+    if kept.is_empty() {
+        stripped.set_query(None);
+    } else {
+        stripped.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Some(stripped)
+}
+
+/// Replace the file name's stem (the part before the extension)
+fn replace_stem(url: &Url, re: Option<&Regex>) -> Option<Url> {
+    let re = re?;
+    let path = url.path();
+    let (dir, file) = path.rsplit_once('/')?;
+    let (stem, ext) = file.rsplit_once('.').unwrap_or((file, ""));
+    let cleaned = re.replace(stem, "");
+    if cleaned == stem || cleaned.is_empty() {
+        return None;
+    }
+    let mut rewritten = url.clone();
+    let file = if ext.is_empty() { cleaned.to_string() } else { format!("{}.{}", cleaned, ext) };
+    rewritten.set_path(&format!("{}/{}", dir, file));
+    Some(rewritten)
+}
+
+/// Next.js `/_next/image?url=`, Vercel `/_vercel/image?url=` and Nuxt `/_ipx/<modifiers>/<path>`
+fn image_proxy(url: &Url) -> Option<Url> {
+    if url.path().starts_with("/_next/image") || url.path().starts_with("/_vercel/image") {
+        let inner = url.query_pairs().find(|(k, _)| k == "url")?.1.into_owned();
+        return url.join(&inner).ok();
+    }
+    let rest = url.path().strip_prefix("/_ipx/")?;
+    let (_, original) = rest.split_once('/')?;
+    if original.starts_with("http") {
+        // Remote sources may arrive with the `//` collapsed (`https:/cdn.com/a.png`)
+        let fixed = if original.contains("://") { original.to_string() } else { original.replacen(":/", "://", 1) };
+        return Url::parse(&fixed).ok();
+    }
+    let mut rewritten = url.clone();
+    rewritten.set_path(&format!("/{}", original));
+    Some(rewritten)
+}
+
+/// `/image/upload/w_300,c_fill/v123/brand/logo.png` → `/image/upload/v123/brand/logo.png`
+fn cloudinary(url: &Url) -> Option<Url> {
+    let path = url.path();
+    let marker = ["/image/upload/", "/image/fetch/", "/image/private/"].iter().find(|m| path.contains(*m))?;
+    let (prefix, rest) = path.split_once(marker)?;
+    let is_transform = |segment: &str| {
+        !segment.is_empty()
+            && segment.split(',').all(|part| {
+                part.split_once('_').is_some_and(|(key, _)| CLOUDINARY_PARAMS.contains(&key))
+            })
+    };
+    let segments: Vec<&str> = rest.split('/').collect();
+    let kept: Vec<&str> = segments.iter().copied().skip_while(|s| is_transform(s)).collect();
+    if kept.len() == segments.len() {
+        return None;
+    }
+    let mut rewritten = url.clone();
+    rewritten.set_path(&format!("{}{}{}", prefix, marker, kept.join("/")));
+    Some(rewritten)
+}
+
+/// Wix: `/media/<id>/v1/fill/w_300,h_200/<name>` → `/media/<id>`
+fn wix(url: &Url) -> Option<Url> {
+    let rest = url.path().strip_prefix("/media/")?;
+    let (id, transform) = rest.split_once('/')?;
+    if !transform.starts_with("v1/") {
+        return None;
+    }
+    let mut rewritten = strip_query(url);
+    rewritten.set_path(&format!("/media/{}", id));
+    Some(rewritten)
+}
+
+/// Rewrites for one URL, most likely to be the original first
+fn rewrites(url: &Url) -> Vec<Url> {
+    let host = url.host_str().unwrap_or("").to_lowercase();
+    let path = url.path().to_lowercase();
+    let mut out: Vec<Url> = Vec::new();
+
+    if let Some(inner) = image_proxy(url) {
+        out.push(inner);
+    }
+    if host == "res.cloudinary.com" || path.contains("/image/upload/") {
+        out.extend(cloudinary(url));
+    }
+    if host.ends_with(".imgix.net") || host == "images.ctfassets.net" || host == "cdn.sanity.io" || host.ends_with(".wp.com") {
+        // imgix, Contentful, Sanity and Jetpack serve the stored original when every parameter is dropped
+        if url.query().is_some() {
+            out.push(strip_query(url));
+        }
+    }
+    if host == "cdn.shopify.com" || path.contains("/cdn/shop/") || path.contains("/s/files/") {
+        let base = strip_resize_params(url).unwrap_or_else(|| url.clone());
+        match replace_stem(&base, shopify_size_re()) {
+            Some(without_size) => out.push(without_size),
+            None if base != *url => out.push(base),
+            None => {}
+        }
+    }
+    if host == "images.squarespace-cdn.com" {
+        let mut original = strip_query(url);
+        original.set_query(Some("format=original"));
+        out.push(original);
+    }
+    if host == "static.wixstatic.com" {
+        out.extend(wix(url));
+    }
+    if path.contains("/wp-content/uploads/") {
+        // `-300x200` thumbnails, then the `-scaled` copy WordPress makes of very large uploads
+        if let Some(without_size) = replace_stem(url, wordpress_size_re()) {
+            out.extend(replace_stem(&without_size, wordpress_scaled_re()));
+            out.push(without_size);
+        }
+        out.extend(replace_stem(url, wordpress_scaled_re()));
+    }
+    if RESIZE_QUERY_HOSTS.iter().any(|known| host == *known || host.ends_with(&format!(".{}", known))) {
+        out.extend(strip_resize_params(url));
+    }
+    out
+}
+
+/// Candidate URLs for the original rendition of `src`, best first. Proxies are unwrapped first,
+/// so `/_next/image?url=<cloudinary URL>` also yields the untransformed Cloudinary asset.
+pub(crate) fn original_candidates(src: &str) -> Vec<String> {
+    let Ok(url) = Url::parse(src) else { return Vec::new() };
+    let mut candidates: Vec<Url> = Vec::new();
+    for first in rewrites(&url) {
+        for second in rewrites(&first) {
+            if !candidates.contains(&second) {
+                candidates.push(second);
+            }
+        }
+        if !candidates.contains(&first) {
+            candidates.push(first);
+        }
+    }
+    candidates.retain(|candidate| *candidate != url);
+    candidates.into_iter().map(|u| u.to_string()).collect()
+}
+
+/// Whether a HEAD request finds an image at `url`
+async fn head_is_image(client: &reqwest::Client, url: &str) -> bool {
+    let response = client
+        .head(url)
+        .header("User-Agent", fetch::USER_AGENT)
+        .timeout(HEAD_TIMEOUT)
+        .send()
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_none_or(|content_type| content_type.starts_with("image/") || content_type.starts_with("application/octet-stream")),
+        _ => false,
+    }
+}
+
+/// The best original-resolution candidate that a HEAD request confirms, or `src` unchanged.
+/// Candidates are probed concurrently, so a slow host costs at most one `HEAD_TIMEOUT`.
+pub(crate) async fn resolve_original(client: &reqwest::Client, src: &str) -> String {
+    let candidates = original_candidates(src);
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        let client = client.clone();
+        let candidate = candidate.clone();
+        tasks.spawn(async move { (index, head_is_image(&client, &candidate).await) });
+    }
+    let mut best: Option<usize> = None;
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, true)) = joined {
+            best = Some(best.map_or(index, |best| best.min(index)));
+        }
+    }
+    match best {
+        Some(index) => {
+            println!("[cdn] Using original {} for {}", candidates[index], src);
+            candidates[index].clone()
+        }
+        None => src.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_cloudinary_transformations() {
+        assert_eq!(
+            original_candidates("https://res.cloudinary.com/acme/image/upload/w_300,h_200,c_fill/q_auto/v1712/brand/logo.png"),
+            ["https://res.cloudinary.com/acme/image/upload/v1712/brand/logo.png"],
+        );
+        assert!(original_candidates("https://res.cloudinary.com/acme/image/upload/v1712/brand/logo.png").is_empty());
+    }
+
+    #[test]
+    fn unwraps_image_proxies() {
+        assert_eq!(
+            original_candidates("https://example.com/_next/image?url=%2Fimages%2Fhero.jpg&w=1080&q=75"),
+            ["https://example.com/images/hero.jpg"],
+        );
+        assert_eq!(
+            original_candidates("https://example.com/_ipx/w_200&f_webp/https:/cdn.example.net/a.png"),
+            ["https://cdn.example.net/a.png"],
+        );
+        // A proxied Cloudinary asset yields the untransformed original first
+        assert_eq!(
+            original_candidates("https://example.com/_next/image?url=https%3A%2F%2Fres.cloudinary.com%2Facme%2Fimage%2Fupload%2Fw_400%2Flogo.png&w=64"),
+            ["https://res.cloudinary.com/acme/image/upload/logo.png", "https://res.cloudinary.com/acme/image/upload/w_400/logo.png"],
+        );
+    }
+
+    #[test]
+    fn strips_shopify_and_wordpress_sizes() {
+        assert_eq!(
+            original_candidates("https://cdn.shopify.com/s/files/1/0001/files/hero_1024x1024_crop_center@2x.jpg?v=12&width=600"),
+            ["https://cdn.shopify.com/s/files/1/0001/files/hero.jpg?v=12"],
+        );
+        assert_eq!(
+            original_candidates("https://example.com/wp-content/uploads/2024/05/team-scaled-300x200.jpg"),
+            ["https://example.com/wp-content/uploads/2024/05/team.jpg", "https://example.com/wp-content/uploads/2024/05/team-scaled.jpg"],
+        );
+    }
+
+    #[test]
+    fn drops_resize_queries_only_on_known_hosts() {
+        assert_eq!(
+            original_candidates("https://images.unsplash.com/photo-1?ixid=abc&w=800&q=75&auto=format"),
+            ["https://images.unsplash.com/photo-1?ixid=abc"],
+        );
+        assert_eq!(original_candidates("https://acme.imgix.net/logo.png?w=64"), ["https://acme.imgix.net/logo.png"]);
+        assert!(original_candidates("https://example.com/thumb.php?w=800&id=3").is_empty());
+        assert!(original_candidates("not a url").is_empty());
+    }
+}
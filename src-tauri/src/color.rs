@@ -0,0 +1,600 @@
+use serde::{Serialize, Deserialize};
+
+/// An opaque sRGB color
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Rgb {
+    pub(crate) r: u8,
+    pub(crate) g: u8,
+    pub(crate) b: u8,
+}
+
+impl Rgb {
+    pub(crate) fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb { r, g, b }
+    }
+
+    /// Lowercase `#rrggbb`
+    pub(crate) fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// Naive (ICC-free) conversion from CMYK percentages
+    pub(crate) fn from_cmyk(c: f32, m: f32, y: f32, k: f32) -> Self {
+        let channel = |v: f32| (255.0 * (1.0 - v.clamp(0.0, 100.0) / 100.0) * (1.0 - k.clamp(0.0, 100.0) / 100.0)).round() as u8;
+        Rgb::new(channel(c), channel(m), channel(y))
+    }
+
+    /// Naive (ICC-free) CMYK percentages. Print shops should still proof against their own profile.
+    pub(crate) fn to_cmyk(self) -> [u8; 4] {
+        let (r, g, b) = (self.r as f32 / 255.0, self.g as f32 / 255.0, self.b as f32 / 255.0);
+        let k = 1.0 - r.max(g).max(b);
+        if k >= 1.0 {
+            return [0, 0, 0, 100];
+        }
+        let part = |v: f32| ((1.0 - v - k) / (1.0 - k) * 100.0).round() as u8;
+        [part(r), part(g), part(b), (k * 100.0).round() as u8]
+    }
+
+    fn to_linear(self) -> [f32; 3] {
+        let linear = |v: u8| {
+            let v = v as f32 / 255.0;
+            if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+        };
+        [linear(self.r), linear(self.g), linear(self.b)]
+    }
+
+    /// `None` when a channel falls outside sRGB
+    fn from_linear([r, g, b]: [f32; 3]) -> Option<Self> {
+        const TOLERANCE: f32 = 0.0005;
+        let encode = |v: f32| {
+            if !(-TOLERANCE..=1.0 + TOLERANCE).contains(&v) {
+                return None;
+            }
+            let v = v.clamp(0.0, 1.0);
+            let v = if v <= 0.0031308 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 };
+            Some((v * 255.0).round().clamp(0.0, 255.0) as u8)
+        };
+        Some(Rgb::new(encode(r)?, encode(g)?, encode(b)?))
+    }
+
+    /// WCAG relative luminance
+    pub(crate) fn luminance(self) -> f32 {
+        let [r, g, b] = self.to_linear();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// OKLCH: lightness 0–1, chroma (0 to about 0.37 in sRGB), hue in degrees
+    pub(crate) fn to_oklch(self) -> [f32; 3] {
+        let [r, g, b] = self.to_linear().map(f64::from);
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        let lightness = 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s;
+        let a = 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s;
+        let b = 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s;
+        [lightness as f32, a.hypot(b) as f32, b.atan2(a).to_degrees().rem_euclid(360.0) as f32]
+    }
+
+    /// From OKLCH, lowering chroma until the color fits in sRGB (hue and lightness are kept)
+    pub(crate) fn from_oklch(lightness: f32, chroma: f32, hue: f32) -> Self {
+        let lightness = lightness.clamp(0.0, 1.0);
+        let (lightness, hue) = (lightness as f64, hue as f64);
+        let convert = |chroma: f32| {
+            let chroma = chroma as f64;
+            let (a, b) = (chroma * hue.to_radians().cos(), chroma * hue.to_radians().sin());
+            let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+            let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+            let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+            Rgb::from_linear([
+                4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+                -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+                -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+            ]
+            .map(|v| v as f32))
+        };
+        if let Some(rgb) = convert(chroma) {
+            return rgb;
+        }
+        // Binary search for the largest in-gamut chroma; chroma 0 (gray) always fits
+        let (mut low, mut high) = (0.0, chroma.max(0.0));
+        for _ in 0..20 {
+            let mid = (low + high) / 2.0;
+            if convert(mid).is_some() { low = mid } else { high = mid }
+        }
+        convert(low).unwrap_or_else(|| {
+            let gray = (lightness.powi(3) * 255.0).round() as u8;
+            Rgb::new(gray, gray, gray)
+        })
+    }
+
+    /// CIELAB (D65)
+    pub(crate) fn to_lab(self) -> [f32; 3] {
+        let [r, g, b] = self.to_linear();
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+        let f = |t: f32| if t > 216.0 / 24389.0 { t.cbrt() } else { (24389.0 / 27.0 * t + 16.0) / 116.0 };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+}
+
+/// WCAG 2 contrast ratio, 1–21
+pub(crate) fn contrast_ratio(a: Rgb, b: Rgb) -> f32 {
+    let (la, lb) = (a.luminance(), b.luminance());
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// CIEDE2000 color difference: under 1 is imperceptible, 2–3 is a close match
+pub(crate) fn delta_e(a: Rgb, b: Rgb) -> f32 {
+    let ([l1, a1, b1], [l2, a2, b2]) = (a.to_lab(), b.to_lab());
+    let (l1, a1, b1, l2, a2, b2) = (l1 as f64, a1 as f64, b1 as f64, l2 as f64, a2 as f64, b2 as f64);
+    let c_bar = ((a1.hypot(b1)) + (a2.hypot(b2))) / 2.0;
+    let g = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + 25f64.powi(7))).sqrt());
+    let (a1p, a2p) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1p, c2p) = (a1p.hypot(b1), a2p.hypot(b2));
+    let hue = |b: f64, a: f64| if a == 0.0 && b == 0.0 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    let (h1p, h2p) = (hue(b1, a1p), hue(b2, a2p));
+
+    let dl = l2 - l1;
+    let dc = c2p - c1p;
+    let dh = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p <= h1p {
+        h2p - h1p + 360.0
+    } else {
+        h2p - h1p - 360.0
+    };
+    let dh_big = 2.0 * (c1p * c2p).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar_p = (c1p + c2p) / 2.0;
+    let h_bar = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos() + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let sl = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_bar_p;
+    let sh = 1.0 + 0.015 * c_bar_p * t;
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let rc = 2.0 * (c_bar_p.powi(7) / (c_bar_p.powi(7) + 25f64.powi(7))).sqrt();
+    let rt = -rc * (2.0 * d_theta).to_radians().sin();
+    let (l, c, h) = (dl / sl, dc / sc, dh_big / sh);
+    (l * l + c * c + h * h + rt * c * h).sqrt() as f32
+}
+
+// ── Color vision deficiency ──
+
+/// Conditions simulated for accessibility checks
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+    Achromatopsia,
+}
+
+impl Deficiency {
+    pub(crate) const ALL: [Deficiency; 4] =
+        [Deficiency::Protanopia, Deficiency::Deuteranopia, Deficiency::Tritanopia, Deficiency::Achromatopsia];
+
+    /// Machado, Oliveira & Fernandes (2009) matrices at full severity, applied to linear RGB
+    fn matrix(self) -> Option<[[f32; 3]; 3]> {
+        match self {
+            Deficiency::Protanopia => Some([
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ]),
+            Deficiency::Deuteranopia => Some([
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ]),
+            Deficiency::Tritanopia => Some([
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ]),
+            Deficiency::Achromatopsia => None,
+        }
+    }
+}
+
+/// How `rgb` appears with the given deficiency. Achromatopsia keeps only relative luminance.
+pub(crate) fn simulate(rgb: Rgb, deficiency: Deficiency) -> Rgb {
+    let linear = rgb.to_linear();
+    let simulated = match deficiency.matrix() {
+        Some(matrix) => matrix.map(|row| row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]),
+        None => [rgb.luminance(); 3],
+    };
+    Rgb::from_linear(simulated.map(|v| v.clamp(0.0, 1.0))).unwrap_or(rgb)
+}
+
+/// The closest entry of a named-color table
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ColorMatch {
+    pub(crate) name: String,
+    pub(crate) hex: String,
+    /// CIEDE2000 distance from the palette color
+    pub(crate) delta_e: f32,
+}
+
+/// One entry of a user-supplied spot-color reference table
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct SpotColor {
+    pub(crate) name: String,
+    pub(crate) rgb: Rgb,
+}
+
+fn nearest<'a>(rgb: Rgb, candidates: impl Iterator<Item = (&'a str, Rgb)>) -> Option<ColorMatch> {
+    candidates
+        .map(|(name, candidate)| (name, candidate, delta_e(rgb, candidate)))
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(name, candidate, delta_e)| ColorMatch { name: name.to_string(), hex: candidate.to_hex(), delta_e })
+}
+
+/// Closest CSS named color
+pub(crate) fn nearest_named(rgb: Rgb) -> Option<ColorMatch> {
+    nearest(rgb, NAMED_COLORS.iter().map(|(name, value)| (*name, from_u32(*value))))
+}
+
+/// Closest entry of a spot-color table
+pub(crate) fn nearest_spot(rgb: Rgb, table: &[SpotColor]) -> Option<ColorMatch> {
+    nearest(rgb, table.iter().map(|spot| (spot.name.as_str(), spot.rgb)))
+}
+
+/// Split a CSV line on `,`, `;` or tabs, honoring double quotes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' | ';' | '\t' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+/// Header names of a CSV column holding colors
+const COLOR_HEADERS: &[&str] = &["hex", "hex code", "hex value", "color", "colour", "value"];
+
+/// A hex code with `#`, or `rgb()` / `hsl()`; a bare `0A2540` only when `bare_hex` says the field
+/// is known to hold colors, since names like "Facade" are valid hex too. Names are not colors
+/// here either: a spot called "Orange" must not be read as CSS `orange`.
+fn parse_table_color(value: &str, bare_hex: bool) -> Option<Rgb> {
+    let value = value.trim();
+    if value.len() == 6 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return if bare_hex { parse_css_color(&format!("#{}", value)) } else { None };
+    }
+    if value.starts_with('#') || value.contains('(') {
+        return parse_css_color(value);
+    }
+    None
+}
+
+/// One row: a name plus either a color field (`#0a2540`, `rgb(...)`, or `0A2540` in the
+/// `hex_column`) or three 0–255 numbers
+fn parse_spot_row(fields: &[String], hex_column: Option<usize>) -> Option<SpotColor> {
+    let numbers: Vec<u8> = fields.iter().filter_map(|f| f.parse::<u8>().ok()).collect();
+    let is_color = |(index, field): (usize, &String)| parse_table_color(field, Some(index) == hex_column);
+    let color = fields.iter().enumerate().position(|field| is_color(field).is_some());
+    let (rgb, name) = match color {
+        Some(index) => (is_color((index, &fields[index]))?, fields.iter().enumerate().find(|(i, f)| *i != index && !f.is_empty() && f.parse::<u8>().is_err())),
+        None if numbers.len() >= 3 => (Rgb::new(numbers[0], numbers[1], numbers[2]), fields.iter().enumerate().find(|(_, f)| !f.is_empty() && f.parse::<u8>().is_err())),
+        None => return None,
+    };
+    Some(SpotColor { name: name?.1.clone(), rgb })
+}
+
+/// Parse a spot-color reference table. JSON: an array of `{"name", "hex"}` (or `"r"`, `"g"`, `"b"`)
+/// objects, or an object mapping names to colors. CSV: `name,hex` or `name,r,g,b` rows, or any
+/// column order under a header row naming the color column; rows without a color are skipped.
+pub(crate) fn parse_spot_table(text: &str) -> Result<Vec<SpotColor>, String> {
+    let trimmed = text.trim_start_matches('\u{feff}').trim();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(trimmed).map_err(|e| format!("Invalid JSON: {}", e))?;
+        let entry = |name: &str, value: &serde_json::Value| -> Option<SpotColor> {
+            let rgb = match value {
+                serde_json::Value::String(color) => parse_table_color(color, true)?,
+                serde_json::Value::Object(fields) => match fields.get("hex").or(fields.get("color")).and_then(|v| v.as_str()) {
+                    Some(color) => parse_table_color(color, true)?,
+                    None => {
+                        let channel = |key: &str| fields.get(key)?.as_u64().map(|v| v.min(255) as u8);
+                        Rgb::new(channel("r")?, channel("g")?, channel("b")?)
+                    }
+                },
+                _ => return None,
+            };
+            Some(SpotColor { name: name.to_string(), rgb })
+        };
+        let table: Vec<SpotColor> = match &value {
+            serde_json::Value::Array(items) => items
+                .iter()
+                .filter_map(|item| entry(item.get("name")?.as_str()?, item))
+                .collect(),
+            serde_json::Value::Object(map) => map.iter().filter_map(|(name, value)| entry(name, value)).collect(),
+            _ => Vec::new(),
+        };
+        return if table.is_empty() { Err("No colors found in JSON table".to_string()) } else { Ok(table) };
+    }
+
+    let mut rows = trimmed.lines().map(split_csv_line).peekable();
+    // A first row without any color or numbers is a header: it names the hex column. Without
+    // one, bare hex is only read from the second column (`name,hex`).
+    let header = rows.peek().filter(|fields| {
+        fields.iter().all(|f| f.parse::<u8>().is_err() && parse_table_color(f, false).is_none())
+            && fields.get(1).is_none_or(|f| parse_table_color(f, true).is_none())
+    });
+    let hex_column = match header {
+        Some(fields) => fields.iter().position(|f| COLOR_HEADERS.contains(&f.to_lowercase().as_str())),
+        None => Some(1),
+    };
+    let table: Vec<SpotColor> = rows.filter_map(|fields| parse_spot_row(&fields, hex_column)).collect();
+    if table.is_empty() {
+        Err("No colors found in CSV table".to_string())
+    } else {
+        Ok(table)
+    }
+}
+
+/// CSS named colors (CSS Color Module Level 4)
+pub(crate) const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff), ("antiquewhite", 0xfaebd7), ("aqua", 0x00ffff), ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff), ("beige", 0xf5f5dc), ("bisque", 0xffe4c4), ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd), ("blue", 0x0000ff), ("blueviolet", 0x8a2be2), ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887), ("cadetblue", 0x5f9ea0), ("chartreuse", 0x7fff00), ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50), ("cornflowerblue", 0x6495ed), ("cornsilk", 0xfff8dc), ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff), ("darkblue", 0x00008b), ("darkcyan", 0x008b8b), ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9), ("darkgreen", 0x006400), ("darkgrey", 0xa9a9a9), ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b), ("darkolivegreen", 0x556b2f), ("darkorange", 0xff8c00), ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000), ("darksalmon", 0xe9967a), ("darkseagreen", 0x8fbc8f), ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f), ("darkslategrey", 0x2f4f4f), ("darkturquoise", 0x00ced1), ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493), ("deepskyblue", 0x00bfff), ("dimgray", 0x696969), ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff), ("firebrick", 0xb22222), ("floralwhite", 0xfffaf0), ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff), ("gainsboro", 0xdcdcdc), ("ghostwhite", 0xf8f8ff), ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520), ("gray", 0x808080), ("green", 0x008000), ("greenyellow", 0xadff2f),
+    ("grey", 0x808080), ("honeydew", 0xf0fff0), ("hotpink", 0xff69b4), ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082), ("ivory", 0xfffff0), ("khaki", 0xf0e68c), ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5), ("lawngreen", 0x7cfc00), ("lemonchiffon", 0xfffacd), ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080), ("lightcyan", 0xe0ffff), ("lightgoldenrodyellow", 0xfafad2), ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90), ("lightgrey", 0xd3d3d3), ("lightpink", 0xffb6c1), ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa), ("lightskyblue", 0x87cefa), ("lightslategray", 0x778899), ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de), ("lightyellow", 0xffffe0), ("lime", 0x00ff00), ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6), ("magenta", 0xff00ff), ("maroon", 0x800000), ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd), ("mediumorchid", 0xba55d3), ("mediumpurple", 0x9370db), ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee), ("mediumspringgreen", 0x00fa9a), ("mediumturquoise", 0x48d1cc), ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970), ("mintcream", 0xf5fffa), ("mistyrose", 0xffe4e1), ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead), ("navy", 0x000080), ("oldlace", 0xfdf5e6), ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23), ("orange", 0xffa500), ("orangered", 0xff4500), ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa), ("palegreen", 0x98fb98), ("paleturquoise", 0xafeeee), ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5), ("peachpuff", 0xffdab9), ("peru", 0xcd853f), ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd), ("powderblue", 0xb0e0e6), ("purple", 0x800080), ("rebeccapurple", 0x663399),
+    ("red", 0xff0000), ("rosybrown", 0xbc8f8f), ("royalblue", 0x4169e1), ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072), ("sandybrown", 0xf4a460), ("seagreen", 0x2e8b57), ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d), ("silver", 0xc0c0c0), ("skyblue", 0x87ceeb), ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090), ("slategrey", 0x708090), ("snow", 0xfffafa), ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4), ("tan", 0xd2b48c), ("teal", 0x008080), ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347), ("turquoise", 0x40e0d0), ("violet", 0xee82ee), ("wheat", 0xf5deb3),
+    ("white", 0xffffff), ("whitesmoke", 0xf5f5f5), ("yellow", 0xffff00), ("yellowgreen", 0x9acd32),
+];
+
+fn from_u32(value: u32) -> Rgb {
+    Rgb::new((value >> 16) as u8, (value >> 8) as u8, value as u8)
+}
+
+/// Parse a CSS color value (`#abc`, `#aabbcc`, `#aabbccdd`, `rgb()`/`rgba()`, `hsl()`/`hsla()`, named).
+/// Fully transparent colors and keywords like `currentColor` / `inherit` return `None`.
+pub(crate) fn parse_css_color(value: &str) -> Option<Rgb> {
+    let value = value.trim().trim_end_matches("!important").trim().to_lowercase();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let expand = |c: char| c.to_digit(16).map(|d| (d * 17) as u8);
+        let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return match hex.len() {
+            3 | 4 => {
+                let chars: Vec<char> = hex.chars().collect();
+                if hex.len() == 4 && chars[3] == '0' {
+                    return None;
+                }
+                Some(Rgb::new(expand(chars[0])?, expand(chars[1])?, expand(chars[2])?))
+            }
+            6 | 8 => {
+                if hex.len() == 8 && &hex[6..8] == "00" {
+                    return None;
+                }
+                Some(Rgb::new(pair(0)?, pair(2)?, pair(4)?))
+            }
+            _ => None,
+        };
+    }
+
+    if let Some(open) = value.find('(') {
+        let func = value[..open].trim();
+        let args: Vec<&str> = value[open + 1..]
+            .trim_end_matches(')')
+            .split([',', ' ', '/'])
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+            .collect();
+        if args.len() < 3 {
+            return None;
+        }
+        if let Some(alpha) = args.get(3) {
+            let alpha = match alpha.strip_suffix('%') {
+                Some(pct) => pct.parse::<f32>().ok()? / 100.0,
+                None => alpha.parse::<f32>().ok()?,
+            };
+            if alpha <= 0.0 {
+                return None;
+            }
+        }
+        return match func {
+            "rgb" | "rgba" => {
+                let channel = |a: &str| -> Option<u8> {
+                    let v = match a.strip_suffix('%') {
+                        Some(pct) => pct.parse::<f32>().ok()? * 2.55,
+                        None => a.parse::<f32>().ok()?,
+                    };
+                    Some(v.round().clamp(0.0, 255.0) as u8)
+                };
+                Some(Rgb::new(channel(args[0])?, channel(args[1])?, channel(args[2])?))
+            }
+            "hsl" | "hsla" => {
+                let h = args[0].trim_end_matches("deg").parse::<f32>().ok()?;
+                let s = args[1].trim_end_matches('%').parse::<f32>().ok()? / 100.0;
+                let l = args[2].trim_end_matches('%').parse::<f32>().ok()? / 100.0;
+                Some(hsl_to_rgb(h, s, l))
+            }
+            _ => None,
+        };
+    }
+
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, rgb)| from_u32(*rgb))
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> Rgb {
+    let h = h.rem_euclid(360.0) / 360.0;
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let hue = |mut t: f32| {
+        t = t.rem_euclid(1.0);
+        if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        }
+    };
+    let to_u8 = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb::new(to_u8(hue(h + 1.0 / 3.0)), to_u8(hue(h)), to_u8(hue(h - 1.0 / 3.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(parse_css_color("#ff8000"), Some(Rgb::new(255, 128, 0)));
+        assert_eq!(parse_css_color("#F80"), Some(Rgb::new(255, 136, 0)));
+        assert_eq!(parse_css_color("#ff800080"), Some(Rgb::new(255, 128, 0)));
+        assert_eq!(parse_css_color(" #000 !important"), Some(Rgb::new(0, 0, 0)));
+        assert_eq!(parse_css_color("#ff800000"), None);
+        assert_eq!(parse_css_color("#f800"), None);
+        assert_eq!(parse_css_color("#ggg"), None);
+        assert_eq!(parse_css_color("#12345"), None);
+    }
+
+    #[test]
+    fn parses_functional_colors() {
+        assert_eq!(parse_css_color("rgb(255, 128, 0)"), Some(Rgb::new(255, 128, 0)));
+        assert_eq!(parse_css_color("rgb(255 128 0 / 50%)"), Some(Rgb::new(255, 128, 0)));
+        assert_eq!(parse_css_color("rgba(100%, 0%, 0%, 0.5)"), Some(Rgb::new(255, 0, 0)));
+        assert_eq!(parse_css_color("rgba(0, 0, 0, 0)"), None);
+        assert_eq!(parse_css_color("hsl(120deg, 100%, 50%)"), Some(Rgb::new(0, 255, 0)));
+        assert_eq!(parse_css_color("hsla(240, 100%, 50%, 1)"), Some(Rgb::new(0, 0, 255)));
+        assert_eq!(parse_css_color("rgb(1, 2)"), None);
+        assert_eq!(parse_css_color("lab(50 20 30)"), None);
+    }
+
+    #[test]
+    fn parses_named_colors_and_rejects_keywords() {
+        assert_eq!(parse_css_color("RebeccaPurple"), Some(Rgb::new(102, 51, 153)));
+        assert_eq!(parse_css_color("white"), Some(Rgb::new(255, 255, 255)));
+        assert_eq!(parse_css_color("transparent"), None);
+        assert_eq!(parse_css_color("currentColor"), None);
+        assert_eq!(parse_css_color("inherit"), None);
+    }
+
+    #[test]
+    fn delta_e_matches_reference_values() {
+        let (white, black) = (Rgb::new(255, 255, 255), Rgb::new(0, 0, 0));
+        assert_eq!(delta_e(white, white), 0.0);
+        assert!((delta_e(white, black) - 100.0).abs() < 0.1, "{}", delta_e(white, black));
+        let (red, blue) = (Rgb::new(255, 0, 0), Rgb::new(0, 0, 255));
+        assert!((delta_e(red, blue) - 52.88).abs() < 0.1, "{}", delta_e(red, blue));
+        assert_eq!(delta_e(red, blue), delta_e(blue, red));
+        assert!(delta_e(Rgb::new(10, 37, 64), Rgb::new(11, 37, 64)) < 1.0);
+        assert!((contrast_ratio(white, black) - 21.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn matches_nearest_named_and_spot_colors() {
+        let named = nearest_named(Rgb::new(250, 128, 115)).unwrap();
+        assert_eq!((named.name.as_str(), named.hex.as_str()), ("salmon", "#fa8072"));
+        let table = parse_spot_table("Name,Hex\nNavy Ink,0A2540\nSignal,#FF5A1F").unwrap();
+        let spot = nearest_spot(Rgb::new(12, 38, 66), &table).unwrap();
+        assert_eq!(spot.name, "Navy Ink");
+        assert!(spot.delta_e < 2.0);
+        assert!(nearest_spot(Rgb::new(0, 0, 0), &[]).is_none());
+    }
+
+    #[test]
+    fn parses_spot_tables() {
+        let names = |table: Vec<SpotColor>| table.into_iter().map(|s| (s.name, s.rgb.to_hex())).collect::<Vec<_>>();
+        let expected = vec![("Navy".to_string(), "#0a2540".to_string()), ("Orange".to_string(), "#ff5a1f".to_string())];
+        assert_eq!(names(parse_spot_table("Navy,0A2540\nOrange,\"rgb(255, 90, 31)\"").unwrap()), expected);
+        assert_eq!(names(parse_spot_table("\u{feff}Name;Pantone;Hex Code\nNavy;PMS 2767 C;0a2540\nOrange;PMS 1655 C;FF5A1F").unwrap()), expected);
+        assert_eq!(names(parse_spot_table("Navy\t10\t37\t64\nOrange\t255\t90\t31").unwrap()), expected);
+        assert_eq!(names(parse_spot_table(r#"[{"name": "Navy", "hex": "0A2540"}, {"name": "Orange", "r": 255, "g": 90, "b": 31}]"#).unwrap()), expected);
+        assert_eq!(names(parse_spot_table(r##"{"Navy": "#0a2540", "Orange": "rgb(255 90 31)"}"##).unwrap()), expected);
+        // "Facade" is valid hex and "Orange" a CSS name; neither is a color in a name column
+        assert!(parse_spot_table("Facade,Orange").is_err());
+        assert!(parse_spot_table("[1, 2]").is_err());
+        assert!(parse_spot_table("{not json").is_err());
+    }
+
+    #[test]
+    fn simulation_keeps_neutrals() {
+        for deficiency in Deficiency::ALL {
+            for gray in [Rgb::new(0, 0, 0), Rgb::new(128, 128, 128), Rgb::new(255, 255, 255)] {
+                let simulated = simulate(gray, deficiency);
+                assert!(delta_e(gray, simulated) < 1.0, "{:?} {:?} -> {:?}", deficiency, gray, simulated);
+            }
+        }
+    }
+
+    #[test]
+    fn simulation_confuses_the_expected_pairs() {
+        let (red, green, blue) = (Rgb::new(220, 50, 47), Rgb::new(80, 160, 40), Rgb::new(38, 139, 210));
+        let seen = |a: Rgb, b: Rgb, deficiency| delta_e(simulate(a, deficiency), simulate(b, deficiency));
+        // Red and green collapse for both red-green deficiencies, but stay apart with tritanopia
+        assert!(seen(red, green, Deficiency::Protanopia) < delta_e(red, green) / 2.0);
+        assert!(seen(red, green, Deficiency::Deuteranopia) < delta_e(red, green) / 2.0);
+        assert!(seen(red, green, Deficiency::Tritanopia) > delta_e(red, green) / 2.0);
+
+        let gray = simulate(blue, Deficiency::Achromatopsia);
+        assert!(gray.r == gray.g && gray.g == gray.b, "{:?}", gray);
+        assert!((gray.luminance() - blue.luminance()).abs() < 0.01);
+    }
+
+    #[test]
+    fn deficiencies_serialize_lowercase() {
+        assert_eq!(serde_json::to_string(&Deficiency::ALL).unwrap(), r#"["protanopia","deuteranopia","tritanopia","achromatopsia"]"#);
+    }
+}
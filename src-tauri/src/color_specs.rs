@@ -0,0 +1,274 @@
+use regex_lite::Regex;
+use serde::{Serialize, Deserialize};
+use std::sync::OnceLock;
+
+use crate::color::{self, Rgb};
+use crate::palette::PaletteColor;
+use crate::TextBlock;
+
+/// Words that label a notation rather than name a color ("HEX", "Pantone", "Print")
+const LABEL_WORDS: &[&str] = &[
+    "hex", "hexadecimal", "html", "web", "rgb", "cmyk", "pms", "pantone", "print", "screen", "digital", "code", "color",
+    "colour", "value", "values", "r", "g", "b", "c", "m", "y", "k",
+];
+/// Longest text accepted as a color name
+const MAX_NAME_CHARS: usize = 32;
+const MAX_NAME_WORDS: usize = 4;
+
+/// A brand color spelled out in page text, typically on a brand-guidelines page
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ColorSpec {
+    /// Nearby heading or label ("Midnight Blue"); empty when none was found
+    pub(crate) name: String,
+    pub(crate) hex: String,
+    /// From the hex code, else the RGB triple, else a naive CMYK conversion
+    pub(crate) rgb: Rgb,
+    /// CMYK percentages as published
+    pub(crate) cmyk: Option<[u8; 4]>,
+    /// Pantone reference as published, e.g. "PMS 2955 C"
+    pub(crate) pantone: Option<String>,
+    /// Every notation as written on the page
+    pub(crate) notations: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Hex,
+    Rgb,
+    Cmyk,
+    Pantone,
+}
+
+/// One notation found in a text block
+struct Found {
+    kind: Kind,
+    start: usize,
+    end: usize,
+    text: String,
+    values: Vec<u8>,
+}
+
+fn patterns() -> &'static [(Kind, Regex)] {
+    static PATTERNS: OnceLock<Vec<(Kind, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (Kind::Hex, r"#([0-9a-fA-F]{6}|[0-9a-fA-F]{3})\b"),
+            (Kind::Rgb, r"(?i)\brgba?\b[\s:]*\(?\s*(\d{1,3})\s*[,/\s]\s*(\d{1,3})\s*[,/\s]\s*(\d{1,3})\b"),
+            (Kind::Rgb, r"(?i)\bR[\s:]*(\d{1,3})\s*[,/]?\s*G[\s:]*(\d{1,3})\s*[,/]?\s*B[\s:]*(\d{1,3})\b"),
+            (Kind::Cmyk, r"(?i)\bcmyk\b[\s:]*\(?\s*(\d{1,3})%?\s*[,/\s]\s*(\d{1,3})%?\s*[,/\s]\s*(\d{1,3})%?\s*[,/\s]\s*(\d{1,3})%?"),
+            (Kind::Cmyk, r"(?i)\bC[\s:]*(\d{1,3})%?\s*[,/]?\s*M[\s:]*(\d{1,3})%?\s*[,/]?\s*Y[\s:]*(\d{1,3})%?\s*[,/]?\s*K[\s:]*(\d{1,3})%?"),
+            (Kind::Pantone, r"(?i)\b(?:pms|pantone)\b[®\s:]*(\d{3,5}(?:\s?(?:c|u|cp|up|tcx|tpx|xgc))?\b|(?:[a-z]+\s){1,2}[cu]\b)"),
+        ]
+        .into_iter()
+        .filter_map(|(kind, pattern)| Regex::new(pattern).ok().map(|re| (kind, re)))
+        .collect()
+    })
+}
+
+/// Every notation in `text`, in order, without overlaps
+fn find_notations(text: &str) -> Vec<Found> {
+    let mut found: Vec<Found> = Vec::new();
+    for (kind, re) in patterns() {
+        for caps in re.captures_iter(text) {
+            let Some(whole) = caps.get(0) else { continue };
+            let values: Vec<u8> = if *kind == Kind::Hex {
+                let hex = &caps[1];
+                // "#123" in prose is an issue or list number far more often than a color
+                if hex.len() == 3 && hex.chars().all(|c| c.is_ascii_digit()) {
+                    continue;
+                }
+                Vec::new()
+            } else if *kind == Kind::Pantone {
+                Vec::new()
+            } else {
+                let Some(values) = caps.iter().skip(1).map(|m| m?.as_str().parse::<u8>().ok()).collect::<Option<Vec<u8>>>() else { continue };
+                if *kind == Kind::Cmyk && values.iter().any(|v| *v > 100) {
+                    continue;
+                }
+                values
+            };
+            if found.iter().any(|f| whole.start() < f.end && f.start < whole.end()) {
+                continue;
+            }
+            found.push(Found { kind: *kind, start: whole.start(), end: whole.end(), text: whole.as_str().trim().to_string(), values });
+        }
+    }
+    found.sort_by_key(|f| f.start);
+    found
+}
+
+/// Treat short label-like text ("Midnight Blue", "Primary — Ocean:") as a color name
+fn as_name(text: &str) -> Option<String> {
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || matches!(c, ':' | '|' | '•' | '·' | '—' | '–' | '-' | '/' | '(' | ')' | ','))
+        .filter(|w| !w.is_empty() && !LABEL_WORDS.contains(&w.to_lowercase().as_str()))
+        .collect();
+    let name = words.join(" ");
+    let usable = !words.is_empty()
+        && words.len() <= MAX_NAME_WORDS
+        && name.chars().count() <= MAX_NAME_CHARS
+        && words[0].chars().any(char::is_alphabetic);
+    usable.then_some(name)
+}
+
+/// Notations collected for one color
+#[derive(Default)]
+struct Group {
+    name: String,
+    hex: Option<Rgb>,
+    rgb: Option<Rgb>,
+    cmyk: Option<[u8; 4]>,
+    pantone: Option<String>,
+    notations: Vec<String>,
+}
+
+impl Group {
+    fn has(&self, kind: Kind) -> bool {
+        match kind {
+            Kind::Hex => self.hex.is_some(),
+            Kind::Rgb => self.rgb.is_some(),
+            Kind::Cmyk => self.cmyk.is_some(),
+            Kind::Pantone => self.pantone.is_some(),
+        }
+    }
+
+    fn add(&mut self, found: Found) {
+        match found.kind {
+            Kind::Hex => self.hex = color::parse_css_color(&found.text),
+            Kind::Rgb => self.rgb = Some(Rgb::new(found.values[0], found.values[1], found.values[2])),
+            Kind::Cmyk => self.cmyk = Some([found.values[0], found.values[1], found.values[2], found.values[3]]),
+            Kind::Pantone => self.pantone = Some(found.text.clone()),
+        }
+        self.notations.push(found.text);
+    }
+
+    /// A lone unnamed notation is more likely an example in prose than a brand spec; a Pantone
+    /// reference alone can't be converted without the (licensed) Pantone tables
+    fn into_spec(self) -> Option<ColorSpec> {
+        if self.name.is_empty() && self.notations.len() < 2 {
+            return None;
+        }
+        let cmyk = self.cmyk.map(|[c, m, y, k]| Rgb::from_cmyk(c as f32, m as f32, y as f32, k as f32));
+        let rgb = self.hex.or(self.rgb).or(cmyk)?;
+        Some(ColorSpec { name: self.name, hex: rgb.to_hex(), rgb, cmyk: self.cmyk, pantone: self.pantone, notations: self.notations })
+    }
+}
+
+/// Finish a group: keep it when it resolves to a color, merging repeats of the same color
+fn push_group(group: Option<Group>, specs: &mut Vec<ColorSpec>) {
+    let Some(spec) = group.and_then(Group::into_spec) else { return };
+    match specs.iter_mut().find(|s| s.rgb == spec.rgb) {
+        Some(existing) => {
+            for notation in spec.notations {
+                if !existing.notations.contains(&notation) {
+                    existing.notations.push(notation);
+                }
+            }
+        }
+        None => specs.push(spec),
+    }
+}
+
+/// Mine color specs from page text. Notations in the same or consecutive blocks belong to one
+/// color until a notation of the same kind repeats or a new name appears; a short block or the
+/// text just before a notation names the color.
+pub(crate) fn extract_color_specs(blocks: &[TextBlock]) -> Vec<ColorSpec> {
+    let mut specs: Vec<ColorSpec> = Vec::new();
+    let mut current: Option<Group> = None;
+    let mut pending_name: Option<String> = None;
+
+    for block in blocks {
+        let found = find_notations(&block.text);
+        if found.is_empty() {
+            // A heading-sized block names the next color; anything longer ends the current one
+            push_group(current.take(), &mut specs);
+            pending_name = as_name(&block.text);
+            continue;
+        }
+        let mut cursor = 0;
+        for notation in found {
+            if let Some(name) = block.text.get(cursor..notation.start).and_then(as_name) {
+                push_group(current.take(), &mut specs);
+                pending_name = Some(name);
+            }
+            if current.as_ref().is_some_and(|group| group.has(notation.kind)) {
+                push_group(current.take(), &mut specs);
+            }
+            cursor = notation.end;
+            current
+                .get_or_insert_with(|| Group { name: pending_name.take().unwrap_or_default(), ..Default::default() })
+                .add(notation);
+        }
+    }
+    push_group(current.take(), &mut specs);
+    specs
+}
+
+/// Declared palette entries for the mined specs, tagged "text"
+pub(crate) fn palette_colors(specs: &[ColorSpec]) -> Vec<PaletteColor> {
+    specs.iter().map(|spec| PaletteColor::new(spec.rgb, "text", true).with_name(&spec.name)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(texts: &[&str]) -> Vec<TextBlock> {
+        texts.iter().map(|text| TextBlock { tag: "p".into(), text: text.to_string() }).collect()
+    }
+
+    #[test]
+    fn groups_notations_under_a_heading() {
+        let specs = extract_color_specs(&blocks(&[
+            "Midnight Blue",
+            "HEX #0A2342",
+            "RGB 10 / 35 / 66 · CMYK 100, 80, 30, 50",
+            "Pantone 2767 C",
+            "Coral",
+            "#FF6F59 | R255 G111 B89",
+        ]));
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].name, "Midnight Blue");
+        assert_eq!(specs[0].hex, "#0a2342");
+        assert_eq!(specs[0].cmyk, Some([100, 80, 30, 50]));
+        assert_eq!(specs[0].pantone.as_deref(), Some("Pantone 2767 C"));
+        assert_eq!(specs[0].notations.len(), 4);
+        assert_eq!(specs[1].name, "Coral");
+        assert_eq!(specs[1].rgb, Rgb::new(255, 111, 89));
+    }
+
+    #[test]
+    fn names_colors_from_inline_labels() {
+        let specs = extract_color_specs(&blocks(&["Primary: Ocean #0077B6, Accent: Sand #F4E1C1"]));
+        let named: Vec<(&str, &str)> = specs.iter().map(|s| (s.name.as_str(), s.hex.as_str())).collect();
+        assert_eq!(named, [("Primary Ocean", "#0077b6"), ("Accent Sand", "#f4e1c1")]);
+    }
+
+    #[test]
+    fn falls_back_to_rgb_then_cmyk() {
+        let specs = extract_color_specs(&blocks(&["Forest", "RGB(34, 139, 34)", "Ink", "C 0 M 0 Y 0 K 100"]));
+        assert_eq!(specs[0].rgb, Rgb::new(34, 139, 34));
+        assert_eq!(specs[1].name, "Ink");
+        assert_eq!(specs[1].rgb, Rgb::new(0, 0, 0));
+    }
+
+    #[test]
+    fn ignores_prose_and_lone_notations() {
+        let specs = extract_color_specs(&blocks(&[
+            "We fixed issue #123 and #456 in this release, thanks to everyone who reported them.",
+            "If you want links to match the old theme, set them to #3366ff in your stylesheet.",
+            "Everything else on this page is unchanged from the previous version of the guide.",
+            "Pantone 186 C",
+            "CMYK 120, 0, 0, 0",
+        ]));
+        assert!(specs.is_empty(), "{:?}", specs);
+    }
+
+    #[test]
+    fn merges_repeats_of_the_same_color() {
+        let specs = extract_color_specs(&blocks(&["Red", "#E63946", "Brand red", "#e63946 / RGB 230 57 70"]));
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].name, "Red");
+        assert_eq!(specs[0].notations, ["#E63946", "#e63946", "RGB 230 57 70"]);
+    }
+}
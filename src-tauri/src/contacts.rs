@@ -0,0 +1,482 @@
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Serialize, Deserialize};
+use regex_lite::Regex;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use url::Url;
+
+use crate::filenames;
+use crate::jsonld;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct EmailContact {
+    pub(crate) address: String,
+    /// Element the address was found in, e.g. `a.footer-link[href^=mailto:]`
+    pub(crate) source: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct PhoneContact {
+    /// The number as written on the page
+    pub(crate) display: String,
+    /// E.164 form (`+14155550123`) when the country code is known or inferable
+    pub(crate) e164: Option<String>,
+    pub(crate) source: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct PostalAddress {
+    pub(crate) street: String,
+    pub(crate) locality: String,
+    pub(crate) region: String,
+    pub(crate) postal_code: String,
+    pub(crate) country: String,
+    /// Single-line form, used for display and deduplication
+    pub(crate) formatted: String,
+    pub(crate) source: String,
+}
+
+/// Contact details found on the page, deduplicated across the browser and server documents
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct ContactDetails {
+    pub(crate) emails: Vec<EmailContact>,
+    pub(crate) phones: Vec<PhoneContact>,
+    pub(crate) addresses: Vec<PostalAddress>,
+}
+
+impl ContactDetails {
+    /// Append entries from `other` that aren't already present
+    pub(crate) fn merge(&mut self, other: ContactDetails) {
+        let mut emails: HashSet<String> = self.emails.iter().map(|e| e.address.clone()).collect();
+        let mut phones: HashSet<String> = self.phones.iter().map(phone_key).collect();
+        let mut addresses: HashSet<String> = self.addresses.iter().map(address_key).collect();
+        for email in other.emails {
+            if emails.insert(email.address.clone()) {
+                self.emails.push(email);
+            }
+        }
+        for phone in other.phones {
+            if phones.insert(phone_key(&phone)) {
+                self.phones.push(phone);
+            }
+        }
+        for address in other.addresses {
+            if addresses.insert(address_key(&address)) {
+                self.addresses.push(address);
+            }
+        }
+    }
+}
+
+fn phone_key(phone: &PhoneContact) -> String {
+    phone.e164.clone().unwrap_or_else(|| phone.display.chars().filter(|c| c.is_ascii_digit()).collect())
+}
+
+fn address_key(address: &PostalAddress) -> String {
+    address.formatted.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// Short CSS-like description of an element (`footer#contact.site-footer`) for provenance
+fn describe(el: ElementRef) -> String {
+    let value = el.value();
+    let mut out = value.name().to_string();
+    if let Some(id) = value.attr("id") {
+        out.push('#');
+        out.push_str(id);
+    }
+    for class in value.classes().take(2) {
+        out.push('.');
+        out.push_str(class);
+    }
+    out
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Country calling codes for the regions we can infer from `<html lang>` or a ccTLD
+const CALLING_CODES: &[(&str, &str)] = &[
+    ("us", "1"), ("ca", "1"), ("gb", "44"), ("uk", "44"), ("ie", "353"), ("fr", "33"),
+    ("de", "49"), ("at", "43"), ("ch", "41"), ("nl", "31"), ("be", "32"), ("es", "34"),
+    ("it", "39"), ("pt", "351"), ("se", "46"), ("no", "47"), ("dk", "45"), ("fi", "358"),
+    ("pl", "48"), ("il", "972"), ("au", "61"), ("nz", "64"), ("in", "91"), ("jp", "81"),
+    ("br", "55"), ("mx", "52"), ("za", "27"), ("sg", "65"),
+];
+
+/// Infer the page's country calling code from `<html lang="en-GB">` or the domain's ccTLD
+fn infer_calling_code(document: &Html, base_url: &Url) -> Option<&'static str> {
+    let lang_region = Selector::parse("html[lang]").ok().and_then(|sel| {
+        document
+            .select(&sel)
+            .next()
+            .and_then(|el| el.value().attr("lang"))
+            .and_then(|lang| lang.split(['-', '_']).nth(1))
+            .map(|region| region.to_lowercase())
+    });
+    let tld = base_url
+        .host_str()
+        .and_then(|host| host.rsplit('.').next())
+        .map(|tld| tld.to_lowercase());
+    [lang_region, tld]
+        .into_iter()
+        .flatten()
+        .find_map(|region| CALLING_CODES.iter().find(|(r, _)| *r == region).map(|(_, code)| *code))
+}
+
+/// Normalize a phone number to E.164. National numbers need a known calling code;
+/// without one, ten-digit numbers are assumed to be North American.
+pub(crate) fn to_e164(raw: &str, calling_code: Option<&str>) -> Option<String> {
+    let trimmed = raw.trim();
+    let digits: String = trimmed.chars().filter(|c| c.is_ascii_digit()).collect();
+    let international = if trimmed.starts_with('+') {
+        digits
+    } else if let Some(rest) = digits.strip_prefix("00") {
+        rest.to_string()
+    } else {
+        match calling_code {
+            Some("1") | None if digits.len() == 10 => format!("1{}", digits),
+            Some("1") | None if digits.len() == 11 && digits.starts_with('1') => digits,
+            Some(code) if code != "1" => format!("{}{}", code, digits.trim_start_matches('0')),
+            _ => return None,
+        }
+    };
+    if (8..=15).contains(&international.len()) && !international.starts_with('0') {
+        Some(format!("+{}", international))
+    } else {
+        None
+    }
+}
+
+/// Decode Cloudflare's email obfuscation (`data-cfemail` / `/cdn-cgi/l/email-protection#...`):
+/// the first byte is an XOR key applied to every following byte.
+fn decode_cfemail(hex: &str) -> Option<String> {
+    let bytes: Vec<u8> = (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+        .collect::<Option<_>>()?;
+    let (key, rest) = bytes.split_first()?;
+    String::from_utf8(rest.iter().map(|b| b ^ key).collect()).ok()
+}
+
+/// `[at]` / `(dot)` style placeholders and the characters they stand for
+fn obfuscation_patterns() -> &'static [(Regex, &'static str)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (r"(?i)\s*[\[\(\{<]\s*at\s*[\]\)\}>]\s*", "@"),
+            (r"(?i)\s*[\[\(\{<]\s*dot\s*[\]\)\}>]\s*", "."),
+        ]
+        .into_iter()
+        .filter_map(|(pattern, replacement)| Regex::new(pattern).ok().map(|re| (re, replacement)))
+        .collect()
+    })
+}
+
+/// Plain email addresses
+fn email_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").ok()).as_ref()
+}
+
+/// International numbers, `(415) 555-0123` and `415-555-0123`
+fn phone_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:\+|00)\d[\d\s().-]{6,18}\d|\(\d{3}\)\s?\d{3}[\s.-]\d{4}|\b\d{3}[.-]\d{3}[.-]\d{4}\b").ok()).as_ref()
+}
+
+/// Turn `jane [at] brand [dot] com` / `jane(at)brand(dot)com` style text back into an address
+fn deobfuscate(text: &str) -> String {
+    let mut out = text.to_string();
+    for (re, replacement) in obfuscation_patterns() {
+        out = re.replace_all(&out, *replacement).to_string();
+    }
+    out
+}
+
+fn is_plausible_email(address: &str) -> bool {
+    let lower = address.to_lowercase();
+    let asset_suffixes = [".png", ".jpg", ".jpeg", ".gif", ".webp", ".svg", ".avif", ".js", ".css"];
+    lower.contains('@')
+        && !asset_suffixes.iter().any(|ext| lower.ends_with(ext))
+        && !lower.ends_with("@example.com")
+        && !lower.contains("sentry")
+}
+
+/// Contact details being extracted, with the keys already taken so each insert is one lookup
+struct Collector {
+    details: ContactDetails,
+    calling_code: Option<&'static str>,
+    emails: HashSet<String>,
+    phones: HashSet<String>,
+    addresses: HashSet<String>,
+}
+
+impl Collector {
+    fn push_email(&mut self, raw: &str, source: String) {
+        let address = raw.trim().trim_end_matches('.').to_lowercase();
+        if is_plausible_email(&address) && self.emails.insert(address.clone()) {
+            self.details.emails.push(EmailContact { address, source });
+        }
+    }
+
+    fn push_phone(&mut self, raw: &str, source: String, require_e164: bool) {
+        let display = collapse_whitespace(raw);
+        let e164 = to_e164(&display, self.calling_code);
+        let digit_count = display.chars().filter(|c| c.is_ascii_digit()).count();
+        if digit_count < 7 || (require_e164 && e164.is_none()) {
+            return;
+        }
+        let phone = PhoneContact { display, e164, source };
+        if self.phones.insert(phone_key(&phone)) {
+            self.details.phones.push(phone);
+        }
+    }
+
+    fn push_address(&mut self, address: PostalAddress) {
+        if !address.formatted.is_empty() && self.addresses.insert(address_key(&address)) {
+            self.details.addresses.push(address);
+        }
+    }
+}
+
+/// Extract emails, phone numbers and postal addresses from a parsed document
+pub(crate) fn extract_contacts(document: &Html, base_url: &Url) -> ContactDetails {
+    let mut found = Collector {
+        details: ContactDetails::default(),
+        calling_code: infer_calling_code(document, base_url),
+        emails: HashSet::new(),
+        phones: HashSet::new(),
+        addresses: HashSet::new(),
+    };
+
+    // ── mailto: / tel: links and Cloudflare-protected emails ──
+    if let Ok(selector) = Selector::parse("a[href], [data-cfemail]") {
+        for el in document.select(&selector) {
+            let href = el.value().attr("href").unwrap_or("");
+            // ASCII-only lowercasing keeps byte offsets, so positions found in `lower` index `href`
+            let lower = href.to_ascii_lowercase();
+            if let Some(hex) = el.value().attr("data-cfemail") {
+                if let Some(address) = decode_cfemail(hex) {
+                    found.push_email(&address, describe(el));
+                }
+            } else if let Some(pos) = lower.find("/cdn-cgi/l/email-protection#") {
+                let hex = &href[pos + "/cdn-cgi/l/email-protection#".len()..];
+                if let Some(address) = decode_cfemail(hex) {
+                    found.push_email(&address, describe(el));
+                }
+            } else if lower.starts_with("mailto:") {
+                let target = &href["mailto:".len()..];
+                let decoded = filenames::percent_decode(target.split('?').next().unwrap_or(""));
+                for address in decoded.split(',') {
+                    found.push_email(address, format!("{}[href^=mailto:]", describe(el)));
+                }
+            } else if lower.starts_with("tel:") || lower.starts_with("callto:") {
+                let target = href.split_once(':').map(|(_, t)| t).unwrap_or("");
+                found.push_phone(&filenames::percent_decode(target), format!("{}[href^=tel:]", describe(el)), false);
+            }
+        }
+    }
+
+    // ── Microdata: itemprop=email / itemprop=telephone ──
+    if let Ok(selector) = Selector::parse("[itemprop='email'], [itemprop='telephone']") {
+        for el in document.select(&selector) {
+            let value = el
+                .value()
+                .attr("content")
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| el.text().collect::<String>());
+            let value = value.trim().trim_start_matches("mailto:").trim_start_matches("tel:").to_string();
+            let source = format!("{}[itemprop]", describe(el));
+            if el.value().attr("itemprop") == Some("email") {
+                found.push_email(&value, source);
+            } else {
+                found.push_phone(&value, source, false);
+            }
+        }
+    }
+
+    // ── JSON-LD: email / telephone / PostalAddress on any object ──
+    for block in jsonld::blocks(document) {
+        jsonld::walk_objects(&block, &mut |obj| {
+            if let Some(email) = obj.get("email") {
+                for address in jsonld::strings(email) {
+                    found.push_email(address.trim_start_matches("mailto:"), "json-ld".to_string());
+                }
+            }
+            if let Some(phone) = obj.get("telephone") {
+                for number in jsonld::strings(phone) {
+                    found.push_phone(&number, "json-ld".to_string(), false);
+                }
+            }
+            if jsonld::has_type(obj, &["PostalAddress"]) {
+                let field = |key: &str| {
+                    obj.get(key)
+                        .map(|v| match v {
+                            serde_json::Value::Object(map) => map.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string(),
+                            other => jsonld::strings(other).join(", "),
+                        })
+                        .unwrap_or_default()
+                };
+                let address = postal_address(
+                    field("streetAddress"), field("addressLocality"), field("addressRegion"),
+                    field("postalCode"), field("addressCountry"), "json-ld".to_string(),
+                );
+                found.push_address(address);
+            }
+        });
+    }
+
+    // ── Microdata PostalAddress ──
+    if let Ok(selector) = Selector::parse("[itemtype*='schema.org/PostalAddress']") {
+        for el in document.select(&selector) {
+            let prop = |name: &str| {
+                Selector::parse(&format!("[itemprop='{}']", name))
+                    .ok()
+                    .and_then(|sel| el.select(&sel).next())
+                    .map(|p| p.value().attr("content").map(|c| c.to_string()).unwrap_or_else(|| collapse_whitespace(&p.text().collect::<String>())))
+                    .unwrap_or_default()
+            };
+            let address = postal_address(
+                prop("streetAddress"), prop("addressLocality"), prop("addressRegion"),
+                prop("postalCode"), prop("addressCountry"), format!("{}[itemtype=PostalAddress]", describe(el)),
+            );
+            found.push_address(address);
+        }
+    }
+
+    // ── <address> elements (free-form; links inside are picked up above) ──
+    if let Ok(selector) = Selector::parse("address") {
+        for el in document.select(&selector) {
+            let lines: Vec<String> = el
+                .text()
+                .map(collapse_whitespace)
+                .filter(|line| !line.is_empty() && !line.contains('@') && !looks_like_phone(line))
+                .collect();
+            let formatted = lines.join(", ");
+            if formatted.len() >= 10 && formatted.chars().any(|c| c.is_ascii_digit()) {
+                found.push_address(PostalAddress { formatted, source: describe(el), ..Default::default() });
+            }
+        }
+    }
+
+    // ── Plain and obfuscated emails / international phone numbers in visible text ──
+    for node in document.root_element().descendants() {
+        let Node::Text(text) = node.value() else { continue };
+        let Some(parent) = node.parent().and_then(ElementRef::wrap) else { continue };
+        if matches!(parent.value().name(), "script" | "style" | "noscript" | "template") {
+            continue;
+        }
+        let text = deobfuscate(text);
+        if let Some(re) = email_re() {
+            for m in re.find_iter(&text) {
+                found.push_email(m.as_str(), describe(parent));
+            }
+        }
+        if let Some(re) = phone_re() {
+            for m in re.find_iter(&text) {
+                found.push_phone(m.as_str(), describe(parent), true);
+            }
+        }
+    }
+
+    found.details
+}
+
+/// A line that is mostly digits and phone punctuation (`Tel: +44 20 7946 0958`)
+fn looks_like_phone(line: &str) -> bool {
+    let digits = line.chars().filter(|c| c.is_ascii_digit()).count();
+    let letters = line.chars().filter(|c| c.is_alphabetic()).count();
+    digits >= 7 && letters <= 5
+}
+
+fn postal_address(street: String, locality: String, region: String, postal_code: String, country: String, source: String) -> PostalAddress {
+    let locality_line = [locality.as_str(), region.as_str(), postal_code.as_str()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let formatted = [street.as_str(), locality_line.as_str(), country.as_str()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    PostalAddress { street, locality, region, postal_code, country, formatted, source }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(html: &str) -> ContactDetails {
+        extract_contacts(&Html::parse_document(html), &Url::parse("https://brand.example").unwrap())
+    }
+
+    #[test]
+    fn to_e164_keeps_international_numbers() {
+        assert_eq!(to_e164("+44 20 7946 0958", None).as_deref(), Some("+442079460958"));
+        assert_eq!(to_e164("0044 20 7946 0958", Some("1")).as_deref(), Some("+442079460958"));
+    }
+
+    #[test]
+    fn to_e164_applies_the_calling_code_to_national_numbers() {
+        assert_eq!(to_e164("020 7946 0958", Some("44")).as_deref(), Some("+442079460958"));
+        assert_eq!(to_e164("(415) 555-0123", None).as_deref(), Some("+14155550123"));
+        assert_eq!(to_e164("1-415-555-0123", Some("1")).as_deref(), Some("+14155550123"));
+    }
+
+    #[test]
+    fn to_e164_rejects_unknown_national_numbers() {
+        assert_eq!(to_e164("555 0123", None), None);
+        assert_eq!(to_e164("12345", Some("44")), None);
+    }
+
+    #[test]
+    fn decodes_cloudflare_emails() {
+        // Key 0x42 XORed over "a@b.co"
+        let encoded: String = std::iter::once(0x42u8)
+            .chain("a@b.co".bytes().map(|b| b ^ 0x42))
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(decode_cfemail(&encoded).as_deref(), Some("a@b.co"));
+        assert_eq!(decode_cfemail("zz"), None);
+    }
+
+    #[test]
+    fn deobfuscates_bracketed_placeholders() {
+        assert_eq!(deobfuscate("jane [at] brand [dot] com"), "jane@brand.com");
+        assert_eq!(deobfuscate("jane(AT)brand(dot)com"), "jane@brand.com");
+    }
+
+    #[test]
+    fn mailto_keeps_ampersands_and_plus_signs() {
+        let details = extract(r#"<a href="mailto:a%26b@brand.example?subject=hi">x</a><a href="mailto:j+news@brand.example">y</a>"#);
+        let emails: Vec<&str> = details.emails.iter().map(|e| e.address.as_str()).collect();
+        assert_eq!(emails, ["a&b@brand.example", "j+news@brand.example"]);
+    }
+
+    #[test]
+    fn dedupes_across_sources() {
+        let details = extract(
+            r#"<a href="mailto:Hello@Brand.example">mail</a><p>Write to hello@brand.example</p>
+               <a href="tel:+1-415-555-0123">call</a><p>Call (415) 555-0123</p>"#,
+        );
+        assert_eq!(details.emails.len(), 1);
+        assert_eq!(details.phones.len(), 1);
+        assert_eq!(details.phones[0].e164.as_deref(), Some("+14155550123"));
+    }
+
+    #[test]
+    fn non_ascii_hrefs_do_not_shift_the_email_protection_offset() {
+        let details = extract(r#"<a href="ȺȺ/cdn-cgi/l/email-protection#">x</a><a href="ȺȺ/cdn-cgi/l/email-protection#zz">y</a>"#);
+        assert!(details.emails.is_empty());
+    }
+
+    #[test]
+    fn skips_asset_names_that_look_like_emails() {
+        let details = extract("<p>icon@2x.png and logo@3x.webp</p>");
+        assert!(details.emails.is_empty());
+    }
+}
@@ -0,0 +1,579 @@
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ExtendedColorType, ImageEncoder, RgbaImage};
+use serde::{Serialize, Deserialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use resvg::{tiny_skia, usvg};
+
+use crate::filenames;
+use crate::image_header;
+
+/// PNG sizes written by the favicon preset (180 is the Apple touch icon, 192/512 the PWA icons)
+const FAVICON_SIZES: &[u32] = &[16, 32, 48, 180, 192, 512];
+/// Sizes packed into `favicon.ico`
+const ICO_SIZES: &[u32] = &[16, 32, 48];
+/// Square avatar size when none is given
+const AVATAR_SIZE: u32 = 512;
+/// Open Graph / Twitter card size
+const OG_SIZE: (u32, u32) = (1200, 630);
+/// Largest side we render or resize to
+const MAX_SIDE: u32 = 8192;
+
+/// Conversion applied to a download. Every field is optional; an empty value keeps the file as served.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct ConvertOptions {
+    /// "png", "jpeg" or "webp"; defaults to the source format (PNG for SVG and formats we can't encode)
+    #[serde(default)]
+    pub(crate) format: Option<String>,
+    /// "favicon", "og" or "avatar"
+    #[serde(default)]
+    pub(crate) preset: Option<String>,
+    /// Target size in pixels. With both set the image is cropped to fill; with one the other follows the aspect ratio.
+    #[serde(default)]
+    pub(crate) width: Option<u32>,
+    #[serde(default)]
+    pub(crate) height: Option<u32>,
+    /// JPEG quality, 1–100 (default 90)
+    #[serde(default)]
+    pub(crate) quality: Option<u8>,
+}
+
+impl ConvertOptions {
+    fn is_noop(&self) -> bool {
+        self.format.is_none() && self.preset.is_none() && self.width.is_none() && self.height.is_none()
+    }
+}
+
+/// How the decoded image is sized
+#[derive(Clone, Copy)]
+enum Fit {
+    Original,
+    Width(u32),
+    Height(u32),
+    /// Scale to cover, then center-crop
+    Fill(u32, u32),
+    /// Scale to fit inside a square, centered on transparency
+    Contain(u32),
+}
+
+impl Fit {
+    /// Scale factor from a `w`×`h` source
+    fn scale(self, w: f32, h: f32) -> f32 {
+        match self {
+            Fit::Original => 1.0,
+            Fit::Width(tw) => tw as f32 / w,
+            Fit::Height(th) => th as f32 / h,
+            Fit::Fill(tw, th) => (tw as f32 / w).max(th as f32 / h),
+            Fit::Contain(s) => s as f32 / w.max(h),
+        }
+    }
+
+    fn apply(self, image: DynamicImage) -> DynamicImage {
+        let (w, h) = (image.width().max(1), image.height().max(1));
+        match self {
+            Fit::Original => image,
+            Fit::Width(_) | Fit::Height(_) => {
+                let scale = self.scale(w as f32, h as f32);
+                let (nw, nh) = (((w as f32 * scale).round() as u32).clamp(1, MAX_SIDE), ((h as f32 * scale).round() as u32).clamp(1, MAX_SIDE));
+                if (nw, nh) == (w, h) { image } else { image.resize_exact(nw, nh, FilterType::Lanczos3) }
+            }
+            Fit::Fill(tw, th) => {
+                if (w, h) == (tw, th) { image } else { image.resize_to_fill(tw, th, FilterType::Lanczos3) }
+            }
+            Fit::Contain(s) => {
+                let fitted = if w.max(h) == s { image } else { image.resize(s, s, FilterType::Lanczos3) };
+                let mut canvas = RgbaImage::new(s, s);
+                let (x, y) = ((s - fitted.width().min(s)) / 2, (s - fitted.height().min(s)) / 2);
+                image::imageops::overlay(&mut canvas, &fitted.to_rgba8(), x as i64, y as i64);
+                DynamicImage::ImageRgba8(canvas)
+            }
+        }
+    }
+}
+
+/// A decoded source: SVGs stay vector so every output size is rendered sharp
+enum Source {
+    Raster(DynamicImage, &'static str),
+    Svg(Box<usvg::Tree>),
+}
+
+/// System fonts for `<text>` in SVGs, loaded once
+pub(crate) fn fontdb() -> Arc<usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut db = usvg::fontdb::Database::new();
+            db.load_system_fonts();
+            Arc::new(db)
+        })
+        .clone()
+}
+
+impl Source {
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let format = image_header::read_header(bytes).map(|h| h.format);
+        match format {
+            Some("svg") => {
+                let options = usvg::Options { fontdb: fontdb(), ..Default::default() };
+                let tree = usvg::Tree::from_data(bytes, &options).map_err(|e| format!("Failed to parse SVG: {}", e))?;
+                Ok(Source::Svg(Box::new(tree)))
+            }
+            Some("avif") => Err("AVIF images can't be decoded for conversion".to_string()),
+            _ => {
+                let image = image::load_from_memory(bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+                Ok(Source::Raster(image, format.unwrap_or("")))
+            }
+        }
+    }
+
+    /// Output format when none is requested
+    fn default_format(&self) -> &'static str {
+        match self {
+            Source::Raster(_, format @ ("jpeg" | "webp")) => format,
+            _ => "png",
+        }
+    }
+
+    fn render(&self, fit: Fit) -> Result<DynamicImage, String> {
+        match self {
+            Source::Raster(image, _) => Ok(fit.apply(image.clone())),
+            Source::Svg(tree) => {
+                let size = tree.size();
+                let (w, h) = (size.width().max(1.0), size.height().max(1.0));
+                let scale = fit.scale(w, h);
+                let pw = ((w * scale).ceil() as u32).clamp(1, MAX_SIDE);
+                let ph = ((h * scale).ceil() as u32).clamp(1, MAX_SIDE);
+                let mut pixmap = tiny_skia::Pixmap::new(pw, ph).ok_or("Failed to allocate SVG canvas")?;
+                resvg::render(tree, tiny_skia::Transform::from_scale(pw as f32 / w, ph as f32 / h), &mut pixmap.as_mut());
+                let mut rgba = RgbaImage::new(pw, ph);
+                for (out, pixel) in rgba.pixels_mut().zip(pixmap.pixels()) {
+                    let c = pixel.demultiply();
+                    *out = image::Rgba([c.red(), c.green(), c.blue(), c.alpha()]);
+                }
+                // Rendering already hit the target scale; this only crops or pads
+                Ok(fit.apply(DynamicImage::ImageRgba8(rgba)))
+            }
+        }
+    }
+}
+
+/// Decode any supported image into RGBA no larger than `max_side` on its longest side.
+/// SVGs are rendered to fit that square; the padding is transparent.
+pub(crate) fn decode_rgba(bytes: &[u8], max_side: u32) -> Result<RgbaImage, String> {
+    let image = match Source::decode(bytes)? {
+        Source::Raster(image, _) if image.width().max(image.height()) > max_side => image.thumbnail(max_side, max_side),
+        Source::Raster(image, _) => image,
+        svg => svg.render(Fit::Contain(max_side))?,
+    };
+    Ok(image.to_rgba8())
+}
+
+// ── Source URL metadata (XMP `dc:source`) ──
+
+fn xmp_packet(source_url: &str) -> String {
+    let escaped = source_url.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+    format!(
+        "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\
+         <x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\
+         <rdf:Description rdf:about=\"\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><dc:source>{}</dc:source></rdf:Description>\
+         </rdf:RDF></x:xmpmeta><?xpacket end=\"r\"?>",
+        escaped
+    )
+}
+
+/// Insert an `iTXt` XMP chunk right after `IHDR`
+fn png_with_xmp(png: Vec<u8>, xmp: &str) -> Vec<u8> {
+    const IHDR_END: usize = 8 + 25;
+    if png.len() < IHDR_END {
+        return png;
+    }
+    let mut data = b"XML:com.adobe.xmp\0\0\0\0\0".to_vec();
+    data.extend_from_slice(xmp.as_bytes());
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(b"iTXt");
+    chunk.extend_from_slice(&data);
+    chunk.extend_from_slice(&crc32fast::hash(&chunk[4..]).to_be_bytes());
+
+    let mut out = Vec::with_capacity(png.len() + chunk.len());
+    out.extend_from_slice(&png[..IHDR_END]);
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&png[IHDR_END..]);
+    out
+}
+
+/// Insert an XMP `APP1` segment after `SOI` (and after the JFIF `APP0`, which must come first)
+fn jpeg_with_xmp(jpeg: Vec<u8>, xmp: &str) -> Vec<u8> {
+    const NAMESPACE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+    let length = 2 + NAMESPACE.len() + xmp.len();
+    if jpeg.len() < 4 || length > u16::MAX as usize {
+        return jpeg;
+    }
+    let mut at = 2;
+    if jpeg[2..4] == [0xFF, 0xE0] && jpeg.len() >= 6 {
+        at = (4 + u16::from_be_bytes([jpeg[4], jpeg[5]]) as usize).min(jpeg.len());
+    }
+    let mut out = Vec::with_capacity(jpeg.len() + length + 2);
+    out.extend_from_slice(&jpeg[..at]);
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&(length as u16).to_be_bytes());
+    out.extend_from_slice(NAMESPACE);
+    out.extend_from_slice(xmp.as_bytes());
+    out.extend_from_slice(&jpeg[at..]);
+    out
+}
+
+/// Append an `XMP ` chunk; simple (`VP8L`) files are upgraded to the extended `VP8X` layout it needs
+fn webp_with_xmp(webp: Vec<u8>, xmp: &str, width: u32, height: u32) -> Vec<u8> {
+    if webp.len() < 20 || &webp[..4] != b"RIFF" || &webp[8..12] != b"WEBP" {
+        return webp;
+    }
+    let mut out = b"RIFF\0\0\0\0WEBP".to_vec();
+    if &webp[12..16] == b"VP8X" {
+        out.extend_from_slice(&webp[12..]);
+        out[20] |= 0x04;
+    } else {
+        let mut header = [0u8; 10];
+        header[0] = 0x04 | 0x10; // XMP, alpha
+        header[4..7].copy_from_slice(&(width - 1).to_le_bytes()[..3]);
+        header[7..10].copy_from_slice(&(height - 1).to_le_bytes()[..3]);
+        out.extend_from_slice(b"VP8X");
+        out.extend_from_slice(&10u32.to_le_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&webp[12..]);
+    }
+    out.extend_from_slice(b"XMP ");
+    out.extend_from_slice(&(xmp.len() as u32).to_le_bytes());
+    out.extend_from_slice(xmp.as_bytes());
+    if xmp.len() % 2 == 1 {
+        out.push(0);
+    }
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    out
+}
+
+// ── Encoding ──
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    let rgba = image.to_rgba8();
+    let mut out = Vec::new();
+    PngEncoder::new(&mut out)
+        .write_image(&rgba, rgba.width(), rgba.height(), ExtendedColorType::Rgba8)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(out)
+}
+
+/// Encode as `format` with the source URL embedded (inline SVGs have none)
+fn encode(image: &DynamicImage, format: &str, quality: u8, source_url: &str) -> Result<Vec<u8>, String> {
+    let encoded = match format {
+        "png" => encode_png(image)?,
+        "jpeg" => {
+            // JPEG has no alpha: flatten onto white rather than the encoder's black
+            let mut flat = RgbaImage::from_pixel(image.width(), image.height(), image::Rgba([255, 255, 255, 255]));
+            image::imageops::overlay(&mut flat, &image.to_rgba8(), 0, 0);
+            let rgb = DynamicImage::ImageRgba8(flat).to_rgb8();
+            let mut out = Vec::new();
+            JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100))
+                .write_image(&rgb, rgb.width(), rgb.height(), ExtendedColorType::Rgb8)
+                .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+            out
+        }
+        "webp" => {
+            // The pure-Rust encoder is lossless only
+            let rgba = image.to_rgba8();
+            let mut out = Vec::new();
+            WebPEncoder::new_lossless(&mut out)
+                .write_image(&rgba, rgba.width(), rgba.height(), ExtendedColorType::Rgba8)
+                .map_err(|e| format!("Failed to encode WebP: {}", e))?;
+            out
+        }
+        other => return Err(format!("Unsupported output format: {}", other)),
+    };
+    if source_url.is_empty() {
+        return Ok(encoded);
+    }
+    let xmp = xmp_packet(source_url);
+    Ok(match format {
+        "png" => png_with_xmp(encoded, &xmp),
+        "jpeg" => jpeg_with_xmp(encoded, &xmp),
+        _ => webp_with_xmp(encoded, &xmp, image.width(), image.height()),
+    })
+}
+
+fn encode_ico(source: &Source) -> Result<Vec<u8>, String> {
+    let mut pngs = Vec::new();
+    for &size in ICO_SIZES {
+        pngs.push((size, encode_png(&source.render(Fit::Contain(size))?)?));
+    }
+    let frames = pngs
+        .iter()
+        .map(|(size, png)| IcoFrame::with_encoded(png.as_slice(), *size, *size, ExtendedColorType::Rgba8))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to build ICO: {}", e))?;
+    let mut out = Vec::new();
+    IcoEncoder::new(&mut out).encode_images(&frames).map_err(|e| format!("Failed to encode ICO: {}", e))?;
+    Ok(out)
+}
+
+fn extension(format: &str) -> &str {
+    if format == "jpeg" { "jpg" } else { format }
+}
+
+/// Convert `bytes` per `options`. Returns `(file name, bytes)` pairs: one file, or the whole
+/// set for the favicon preset (`favicon.ico` plus PNGs for browsers, Apple touch and PWAs).
+pub(crate) fn convert(bytes: &[u8], stem: &str, source_url: &str, options: &ConvertOptions) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let source = Source::decode(bytes)?;
+    let format = match options.format.as_deref().map(|f| f.trim().to_lowercase()) {
+        Some(f) if f == "jpg" => "jpeg".to_string(),
+        Some(f) => f,
+        None => source.default_format().to_string(),
+    };
+    let quality = options.quality.unwrap_or(90);
+
+    let fit = match (options.preset.as_deref(), options.width, options.height) {
+        (Some("favicon"), _, _) => {
+            let mut files = vec![("favicon.ico".to_string(), encode_ico(&source)?)];
+            for &size in FAVICON_SIZES {
+                let name = match size {
+                    180 => "apple-touch-icon.png".to_string(),
+                    _ => format!("icon-{}.png", size),
+                };
+                files.push((name, encode(&source.render(Fit::Contain(size))?, "png", quality, source_url)?));
+            }
+            return Ok(files);
+        }
+        (Some("og"), _, _) => Fit::Fill(OG_SIZE.0, OG_SIZE.1),
+        (Some("avatar"), w, h) => {
+            let size = w.or(h).unwrap_or(AVATAR_SIZE).clamp(1, MAX_SIDE);
+            Fit::Fill(size, size)
+        }
+        (Some(other), _, _) => return Err(format!("Unknown preset: {}", other)),
+        (None, Some(w), Some(h)) => Fit::Fill(w.clamp(1, MAX_SIDE), h.clamp(1, MAX_SIDE)),
+        (None, Some(w), None) => Fit::Width(w.clamp(1, MAX_SIDE)),
+        (None, None, Some(h)) => Fit::Height(h.clamp(1, MAX_SIDE)),
+        (None, None, None) => Fit::Original,
+    };
+    let image = source.render(fit)?;
+    let suffix = match options.preset.as_deref() {
+        Some(preset) => format!("-{}", preset),
+        None if matches!(fit, Fit::Original) => String::new(),
+        None => format!("-{}x{}", image.width(), image.height()),
+    };
+    let name = format!("{}{}.{}", stem, suffix, extension(&format));
+    Ok(vec![(name, encode(&image, &format, quality, source_url)?)])
+}
+
+/// Replace a downloaded file with its converted output, written next to it. A favicon set goes
+/// into a `<stem>-favicons` folder, whose path is returned. Leaves the file alone for empty options.
+pub(crate) fn convert_file(path: &Path, source_url: &str, options: &ConvertOptions) -> Result<PathBuf, String> {
+    if options.is_noop() {
+        return Ok(path.to_path_buf());
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "image".to_string());
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let files = convert(&bytes, &stem, source_url, options)?;
+
+    let written = if options.preset.as_deref() == Some("favicon") {
+        let folder = filenames::create_unique_dir(dir, &format!("{}-favicons", stem))?;
+        for (name, data) in &files {
+            std::fs::write(folder.join(name), data).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        }
+        folder
+    } else {
+        let (name, data) = files.into_iter().next().ok_or("Conversion produced no output")?;
+        if path.file_name().is_some_and(|f| f.to_string_lossy() == name) {
+            std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            path.to_path_buf()
+        } else {
+            let (out, mut file) = filenames::create_unique_file(dir, &name)?;
+            file.write_all(&data).map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+            out
+        }
+    };
+    if written != path {
+        let _ = std::fs::remove_file(path);
+    }
+    println!("[convert] {:?} → {:?}", path, written);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "https://brand.example/logo.png?a=1&b=2";
+
+    fn sample_png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_fn(width, height, |x, y| image::Rgba([(x * 5) as u8, (y * 7) as u8, 128, 255]));
+        encode_png(&DynamicImage::ImageRgba8(image)).unwrap()
+    }
+
+    fn convert_one(bytes: &[u8], options: ConvertOptions) -> (String, Vec<u8>) {
+        let mut files = convert(bytes, "logo", SOURCE, &options).unwrap();
+        assert_eq!(files.len(), 1);
+        files.remove(0)
+    }
+
+    fn with_format(format: &str) -> ConvertOptions {
+        ConvertOptions { format: Some(format.to_string()), ..Default::default() }
+    }
+
+    fn dimensions(bytes: &[u8]) -> (u32, u32) {
+        let image = image::load_from_memory(bytes).unwrap();
+        (image.width(), image.height())
+    }
+
+    fn position(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).position(|window| window == needle)
+    }
+
+    #[test]
+    fn png_gets_an_itxt_chunk_after_ihdr() {
+        let (name, png) = convert_one(&sample_png(40, 20), with_format("png"));
+        assert_eq!(name, "logo.png");
+        assert_eq!(dimensions(&png), (40, 20));
+
+        // Signature (8) + IHDR (25), then length, type, data and CRC
+        assert_eq!(&png[37..41], b"iTXt");
+        let length = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        let data = &png[41..41 + length];
+        assert!(data.starts_with(b"XML:com.adobe.xmp\0\0\0\0\0"));
+        let xmp = std::str::from_utf8(&data[22..]).unwrap();
+        assert!(xmp.contains("<dc:source>https://brand.example/logo.png?a=1&amp;b=2</dc:source>"));
+        let crc = u32::from_be_bytes(png[41 + length..45 + length].try_into().unwrap());
+        assert_eq!(crc, crc32fast::hash(&png[37..41 + length]));
+        assert_eq!(&png[49 + length..53 + length], b"IDAT");
+    }
+
+    #[test]
+    fn jpeg_gets_an_app1_segment_after_app0() {
+        let (name, jpeg) = convert_one(&sample_png(40, 20), with_format("jpg"));
+        assert_eq!(name, "logo.jpg");
+        assert_eq!(dimensions(&jpeg), (40, 20));
+
+        assert_eq!(&jpeg[..2], [0xFF, 0xD8]);
+        let at = if jpeg[2..4] == [0xFF, 0xE0] { 4 + u16::from_be_bytes([jpeg[4], jpeg[5]]) as usize } else { 2 };
+        assert_eq!(&jpeg[at..at + 2], [0xFF, 0xE1]);
+        let length = u16::from_be_bytes([jpeg[at + 2], jpeg[at + 3]]) as usize;
+        let segment = &jpeg[at + 4..at + 2 + length];
+        assert!(segment.starts_with(b"http://ns.adobe.com/xap/1.0/\0"));
+        assert!(segment.ends_with(b"<?xpacket end=\"r\"?>"));
+        assert_eq!(jpeg[at + 2 + length], 0xFF);
+    }
+
+    #[test]
+    fn jpeg_without_app0_gets_app1_after_soi() {
+        let bare = [0xFF, 0xD8, 0xFF, 0xDB, 0x00, 0x02, 0xFF, 0xD9].to_vec();
+        let out = jpeg_with_xmp(bare, "x");
+        assert_eq!(&out[2..4], [0xFF, 0xE1]);
+        assert_eq!(&out[out.len() - 6..], [0xFF, 0xDB, 0x00, 0x02, 0xFF, 0xD9]);
+    }
+
+    #[test]
+    fn webp_is_upgraded_to_vp8x_with_an_xmp_chunk() {
+        let (name, webp) = convert_one(&sample_png(40, 20), with_format("webp"));
+        assert_eq!(name, "logo.webp");
+        assert_eq!(dimensions(&webp), (40, 20));
+
+        assert_eq!(&webp[12..16], b"VP8X");
+        assert_eq!(webp[20] & 0x04, 0x04);
+        assert_eq!(u32::from_le_bytes(webp[24..28].try_into().unwrap()) & 0xFF_FFFF, 39);
+        assert_eq!(u32::from_le_bytes(webp[27..31].try_into().unwrap()) & 0xFF_FFFF, 19);
+        assert_eq!(u32::from_le_bytes(webp[4..8].try_into().unwrap()) as usize, webp.len() - 8);
+        let xmp_at = position(&webp, b"XMP ").unwrap();
+        let length = u32::from_le_bytes(webp[xmp_at + 4..xmp_at + 8].try_into().unwrap()) as usize;
+        assert_eq!(webp.len(), xmp_at + 8 + length + length % 2);
+    }
+
+    #[test]
+    fn webp_chunks_are_padded_to_even_sizes() {
+        let plain = {
+            let (_, webp) = convert_one(&sample_png(4, 4), with_format("webp"));
+            let xmp_at = position(&webp, b"XMP ").unwrap();
+            let mut simple = b"RIFF\0\0\0\0WEBP".to_vec();
+            simple.extend_from_slice(&webp[30..xmp_at]);
+            let size = (simple.len() - 8) as u32;
+            simple[4..8].copy_from_slice(&size.to_le_bytes());
+            simple
+        };
+        for xmp in ["odd", "even"] {
+            let out = webp_with_xmp(plain.clone(), xmp, 4, 4);
+            assert_eq!(out.len() % 2, 0);
+            assert_eq!(u32::from_le_bytes(out[4..8].try_into().unwrap()) as usize, out.len() - 8);
+            assert_eq!(dimensions(&out), (4, 4));
+        }
+    }
+
+    #[test]
+    fn webp_that_is_already_vp8x_only_gains_the_flag() {
+        let (_, webp) = convert_one(&sample_png(8, 8), with_format("webp"));
+        let again = webp_with_xmp(webp.clone(), "more", 8, 8);
+        assert_eq!(position(&again, b"VP8X"), Some(12));
+        assert_eq!(again.len(), webp.len() + 12);
+        assert_eq!(dimensions(&again), (8, 8));
+    }
+
+    #[test]
+    fn favicon_preset_writes_ico_and_pngs() {
+        let files = convert(&sample_png(300, 200), "logo", SOURCE, &ConvertOptions { preset: Some("favicon".to_string()), ..Default::default() }).unwrap();
+        let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["favicon.ico", "icon-16.png", "icon-32.png", "icon-48.png", "apple-touch-icon.png", "icon-192.png", "icon-512.png"]);
+        for ((_, png), size) in files[1..].iter().zip(FAVICON_SIZES) {
+            assert_eq!(dimensions(png), (*size, *size));
+        }
+
+        let ico = &files[0].1;
+        assert_eq!(&ico[..4], [0, 0, 1, 0]);
+        assert_eq!(u16::from_le_bytes([ico[4], ico[5]]) as usize, ICO_SIZES.len());
+        let sizes: Vec<u32> = (0..ICO_SIZES.len()).map(|i| ico[6 + i * 16] as u32).collect();
+        assert_eq!(sizes, ICO_SIZES);
+        assert_eq!(image::load_from_memory_with_format(ico, image::ImageFormat::Ico).unwrap().width(), 48);
+    }
+
+    #[test]
+    fn og_and_avatar_presets_fill_their_sizes() {
+        let source = sample_png(300, 200);
+        let preset = |name: &str, width| ConvertOptions { preset: Some(name.to_string()), width, ..Default::default() };
+        let (name, og) = convert_one(&source, preset("og", None));
+        assert_eq!((name.as_str(), dimensions(&og)), ("logo-og.png", (1200, 630)));
+        let (_, avatar) = convert_one(&source, preset("avatar", None));
+        assert_eq!(dimensions(&avatar), (AVATAR_SIZE, AVATAR_SIZE));
+        let (_, small) = convert_one(&source, preset("avatar", Some(64)));
+        assert_eq!(dimensions(&small), (64, 64));
+        assert!(convert(&source, "logo", SOURCE, &preset("poster", None)).is_err());
+    }
+
+    #[test]
+    fn resizing_keeps_the_aspect_ratio_and_names_the_size() {
+        let source = sample_png(40, 20);
+        let (name, wide) = convert_one(&source, ConvertOptions { width: Some(100), ..Default::default() });
+        assert_eq!((name.as_str(), dimensions(&wide)), ("logo-100x50.png", (100, 50)));
+        let (_, tall) = convert_one(&source, ConvertOptions { height: Some(10), ..Default::default() });
+        assert_eq!(dimensions(&tall), (20, 10));
+        let (_, filled) = convert_one(&source, ConvertOptions { width: Some(30), height: Some(30), ..Default::default() });
+        assert_eq!(dimensions(&filled), (30, 30));
+    }
+
+    #[test]
+    fn svg_renders_at_the_requested_size() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="5" viewBox="0 0 10 5"><rect width="10" height="5" fill="#ff0000"/></svg>"##;
+        let (name, png) = convert_one(svg, ConvertOptions { width: Some(64), ..Default::default() });
+        assert_eq!(name, "logo-64x32.png");
+        let image = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (64, 32));
+        assert_eq!(image.get_pixel(32, 16).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn empty_source_url_adds_no_metadata() {
+        let files = convert(&sample_png(4, 4), "logo", "", &with_format("png")).unwrap();
+        assert!(position(&files[0].1, b"iTXt").is_none());
+    }
+}
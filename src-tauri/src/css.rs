@@ -0,0 +1,272 @@
+/// A style rule, or a declaration-block at-rule like `@font-face`
+#[derive(Clone, Debug)]
+pub(crate) struct CssRule {
+    /// Selector list, or the at-rule prelude (`@font-face`) for declaration-block at-rules
+    pub(crate) selectors: String,
+    pub(crate) declarations: Vec<(String, String)>,
+    /// Preludes of the conditional group rules it sits in, outermost first (`@media (prefers-color-scheme: dark)`)
+    pub(crate) conditions: Vec<String>,
+}
+
+impl CssRule {
+    /// Last value declared for a property (later declarations win)
+    pub(crate) fn get(&self, property: &str) -> Option<&str> {
+        self.declarations
+            .iter()
+            .rev()
+            .find(|(name, _)| name == property)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        match rest[start + 2..].find("*/") {
+            Some(end) => rest = &rest[start + 2 + end + 2..],
+            None => return out,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Index of the first `target` at nesting depth 0, skipping strings and parentheses
+fn find_top_level(text: &str, targets: &[char]) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (_, '\\') => escaped = true,
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, _) if depth <= 0 && targets.contains(&c) => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Index of the `}` that closes a block whose `{` precedes `text`
+fn block_end(text: &str) -> Option<usize> {
+    let mut depth = 1i32;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (_, '\\') => escaped = true,
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a declaration block into lowercase property names and trimmed values
+pub(crate) fn parse_declarations(block: &str) -> Vec<(String, String)> {
+    let mut declarations = Vec::new();
+    let mut rest = block;
+    loop {
+        let end = find_top_level(rest, &[';']).unwrap_or(rest.len());
+        let declaration = &rest[..end];
+        if let Some((name, value)) = declaration.split_once(':') {
+            let name = name.trim();
+            // Skip nested rules (CSS nesting) that slipped into the block
+            if !name.is_empty() && !name.contains(['{', '}']) {
+                let value = value.trim().trim_end_matches("!important").trim();
+                let name = if name.starts_with("--") { name.to_string() } else { name.to_lowercase() };
+                declarations.push((name, value.to_string()));
+            }
+        }
+        if end >= rest.len() {
+            break;
+        }
+        rest = &rest[end + 1..];
+    }
+    declarations
+}
+
+fn parse_into(css: &str, conditions: &[String], rules: &mut Vec<CssRule>) {
+    let mut rest = css;
+    while !rest.trim().is_empty() {
+        let Some(open) = find_top_level(rest, &['{', ';']) else { break };
+        let prelude = rest[..open].trim();
+        if rest[open..].starts_with(';') {
+            // Statement at-rule (`@import`, `@charset`) — handled by `imports`
+            rest = &rest[open + 1..];
+            continue;
+        }
+        let after_open = &rest[open + 1..];
+        let (body, remainder) = match block_end(after_open) {
+            Some(close) => (&after_open[..close], &after_open[close + 1..]),
+            None => (after_open, ""),
+        };
+        rest = remainder;
+
+        let lower = prelude.to_lowercase();
+        if ["@media", "@supports", "@layer", "@container", "@document", "@scope"].iter().any(|at| lower.starts_with(at)) {
+            let mut nested = conditions.to_vec();
+            nested.push(prelude.split_whitespace().collect::<Vec<_>>().join(" "));
+            parse_into(body, &nested, rules);
+        } else if lower.starts_with("@keyframes") || lower.starts_with("@-webkit-keyframes") {
+            continue;
+        } else {
+            rules.push(CssRule {
+                selectors: prelude.split_whitespace().collect::<Vec<_>>().join(" "),
+                declarations: parse_declarations(body),
+                conditions: conditions.to_vec(),
+            });
+        }
+    }
+}
+
+/// Flatten a stylesheet into rules. The contents of conditional group rules (`@media`,
+/// `@supports`, ...) are included and tagged with their conditions; keyframes are dropped.
+pub(crate) fn parse_rules(css: &str) -> Vec<CssRule> {
+    let mut rules = Vec::new();
+    parse_into(&strip_comments(css), &[], &mut rules);
+    rules
+}
+
+/// Targets of `@import url(...)` / `@import "..."` statements
+pub(crate) fn imports(css: &str) -> Vec<String> {
+    strip_comments(css)
+        .split("@import")
+        .skip(1)
+        .filter_map(|part| {
+            let statement = part.split(';').next()?.trim();
+            let target = statement.strip_prefix("url(").map(|s| s.split(')').next().unwrap_or("")).unwrap_or(statement);
+            let target = target.split_whitespace().next().unwrap_or("").trim_matches(['"', '\'']);
+            (!target.is_empty()).then(|| target.to_string())
+        })
+        .collect()
+}
+
+/// Targets of the `url(...)` references in a value, e.g. an `@font-face` `src` list
+pub(crate) fn urls(value: &str) -> Vec<String> {
+    value
+        .split("url(")
+        .skip(1)
+        .filter_map(|part| {
+            let target = part.split(')').next()?.trim().trim_matches(['"', '\'']).trim();
+            (!target.is_empty() && !target.starts_with("data:")).then(|| target.to_string())
+        })
+        .collect()
+}
+
+/// Decode a CSS string value (`"\f101"`, `'\e900'`) into its characters
+pub(crate) fn unescape_string(value: &str) -> String {
+    unescape(value.trim().trim_matches(['"', '\'']))
+}
+
+/// Decode CSS escapes (`\f101 `, `\72`, `\"`) anywhere in a piece of CSS
+pub(crate) fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 {
+            match chars.peek() {
+                Some(h) if h.is_ascii_hexdigit() => {
+                    hex.push(*h);
+                    chars.next();
+                }
+                _ => break,
+            }
+        }
+        if hex.is_empty() {
+            if let Some(next) = chars.next() {
+                out.push(next);
+            }
+        } else {
+            if chars.peek() == Some(&' ') {
+                chars.next();
+            }
+            if let Some(decoded) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                out.push(decoded);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rules_and_declarations() {
+        let rules = parse_rules("/* brand */ :root { --Brand: #f80; COLOR: red !important; }\n.a,\n.b { background: url(\"x;y.png\"); content: '}' }");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].selectors, ":root");
+        assert_eq!(rules[0].get("--Brand"), Some("#f80"));
+        assert_eq!(rules[0].get("color"), Some("red"));
+        assert_eq!(rules[1].selectors, ".a, .b");
+        assert_eq!(rules[1].get("background"), Some("url(\"x;y.png\")"));
+        assert_eq!(rules[1].get("content"), Some("'}'"));
+    }
+
+    #[test]
+    fn later_declarations_win() {
+        let rules = parse_rules(".a { color: red; color: blue }");
+        assert_eq!(rules[0].get("color"), Some("blue"));
+        assert_eq!(rules[0].get("margin"), None);
+    }
+
+    #[test]
+    fn tags_conditional_rules_and_drops_keyframes() {
+        let rules = parse_rules(concat!(
+            "@import url(base.css); @charset \"utf-8\";",
+            "@media (prefers-color-scheme:   dark) { @supports (display: grid) { body { color: white } } }",
+            "@keyframes spin { from { opacity: 0 } to { opacity: 1 } }",
+            "@font-face { font-family: Brand; src: url(brand.woff2) }",
+        ));
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].selectors, "body");
+        assert_eq!(rules[0].conditions, ["@media (prefers-color-scheme: dark)", "@supports (display: grid)"]);
+        assert_eq!(rules[1].selectors, "@font-face");
+        assert!(rules[1].conditions.is_empty());
+    }
+
+    #[test]
+    fn keeps_rules_of_an_unclosed_block() {
+        let rules = parse_rules(".a { color: red } .b { color: blue");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].get("color"), Some("blue"));
+    }
+
+    #[test]
+    fn reads_imports_urls_and_escapes() {
+        assert_eq!(imports("@import url('a.css'); @import \"b.css\" screen;"), ["a.css", "b.css"]);
+        assert_eq!(urls("url(a.woff2) format('woff2'), url(data:font/woff;base64,AA), url( \"b.woff\" )"), ["a.woff2", "b.woff"]);
+        assert_eq!(unescape_string("\"\\f101\""), "\u{f101}");
+        assert_eq!(unescape("\\75 rl(\\\"x\\\")"), "url(\"x\")");
+    }
+}
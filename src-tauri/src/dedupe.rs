@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use crate::fetch::{self, FetchError, UrlCache};
+use crate::srcset;
+use crate::ImageInfo;
+
+/// Images larger than this are not downloaded for hashing
+const MAX_HASH_BYTES: u64 = 1_000_000;
+/// Images hashed per analysis; the rest keep their URL-pattern grouping
+pub(crate) const MAX_HASHES: usize = 60;
+/// Images hashed concurrently
+const HASH_CONCURRENCY: usize = 6;
+/// Hashes differing in at most this many of 64 bits are treated as the same picture
+const MAX_DISTANCE: u32 = 4;
+
+/// Perceptual hashes shared across analyses. `None` records an image that answered 4xx or could not be decoded.
+pub(crate) type HashCache = UrlCache<Option<u64>>;
+
+/// dHash: shrink to 9×8 grayscale and record whether each pixel is brighter than its right neighbour.
+/// Survives re-encoding, resizing and small color shifts, which is how CDNs serve the same photo.
+fn dhash(bytes: &[u8]) -> Option<u64> {
+    dhash_image(&image::load_from_memory(bytes).ok()?)
+}
+
+fn dhash_image(image: &image::DynamicImage) -> Option<u64> {
+    let small = image.resize_exact(9, 8, image::imageops::FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    // Flat images (spacers, solid placeholders) all hash to 0 and say nothing about content
+    (hash != 0).then_some(hash)
+}
+
+/// Two hashes show the same picture when at most `MAX_DISTANCE` of their bits differ
+fn same_picture(a: u64, b: u64) -> bool {
+    (a ^ b).count_ones() <= MAX_DISTANCE
+}
+
+async fn hash_url(client: reqwest::Client, url: String) -> Result<u64, FetchError> {
+    let bytes = fetch::fetch_capped(&client, &url, MAX_HASH_BYTES as usize, None)
+        .await
+        .inspect_err(|e| println!("[dedupe] Not hashing {}: {}", url, e))?;
+    // Decoding is CPU-bound; keep it off the async workers
+    tokio::task::spawn_blocking(move || dhash(&bytes))
+        .await
+        .map_err(|e| FetchError::transient(format!("Task failed: {}", e)))?
+        .ok_or_else(|| FetchError::permanent(format!("{} could not be decoded or is flat", url)))
+}
+
+fn is_http(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// URL to hash for an image: its narrowest `w`-described srcset candidate, since a thumbnail is
+/// plenty for a 9×8 hash, else the image itself. `None` for SVGs and for an image that would be
+/// fetched whole while its probed size is over `MAX_HASH_BYTES`; a thumbnail's size is unknown
+/// until it is fetched, and `fetch_capped` enforces the cap there.
+fn hash_source(image: &ImageInfo) -> Option<String> {
+    if !is_http(&image.src) || image.format == "svg" {
+        return None;
+    }
+    let thumbnail = image
+        .variants
+        .iter()
+        .filter(|variant| variant.width > 0 && is_http(&variant.src) && variant.src != image.src)
+        .filter(|variant| !variant.mime_type.contains("svg") && !variant.mime_type.contains("avif"))
+        .min_by_key(|variant| variant.width);
+    match thumbnail {
+        Some(variant) => Some(variant.src.clone()),
+        None if image.file_size.is_some_and(|size| size > MAX_HASH_BYTES) => None,
+        None => Some(image.src.clone()),
+    }
+}
+
+/// Near-identical images collected for one kept image
+struct Group {
+    hash: Option<u64>,
+    pattern: String,
+    images: Vec<ImageInfo>,
+}
+
+/// Image indices in hashing order: images whose URL pattern is shared come first, since only a
+/// hash can confirm those matches
+fn hash_order(patterns: &[String]) -> Vec<usize> {
+    let mut pattern_counts: HashMap<&str, usize> = HashMap::new();
+    for pattern in patterns {
+        *pattern_counts.entry(pattern).or_default() += 1;
+    }
+    let mut order: Vec<usize> = (0..patterns.len()).collect();
+    order.sort_by_key(|&i| pattern_counts[patterns[i].as_str()] < 2);
+    order
+}
+
+/// Hashed images join the first group with a close hash; unhashed ones join a group with their
+/// URL pattern. Anything else starts a group of its own. Groups keep first-appearance order.
+fn group_images(images: Vec<ImageInfo>, patterns: Vec<String>, hashes: Vec<Option<u64>>) -> Vec<Vec<ImageInfo>> {
+    let mut groups: Vec<Group> = Vec::new();
+    for ((image, hash), pattern) in images.into_iter().zip(hashes).zip(patterns) {
+        let existing = match hash {
+            Some(hash) => groups.iter().position(|g| g.hash.is_some_and(|g| same_picture(g, hash))),
+            None => groups.iter().position(|g| g.pattern == pattern),
+        };
+        match existing {
+            Some(group) => groups[group].images.push(image),
+            None => groups.push(Group { hash, pattern, images: vec![image] }),
+        }
+    }
+    groups.into_iter().map(|group| group.images).collect()
+}
+
+/// The highest-resolution image of a group (ties keep the earlier one), with the dropped copies'
+/// srcset candidates merged in and the first image's alt text when it has none
+fn keep_largest(group: Vec<ImageInfo>) -> Option<ImageInfo> {
+    let first_alt = group.first()?.alt.clone();
+    let rank = |image: &ImageInfo| (image.width as u64 * image.height as u64, image.file_size.unwrap_or(0));
+    let best_index = (0..group.len()).fold(0, |best, i| if rank(&group[i]) > rank(&group[best]) { i } else { best });
+    let mut variants = Vec::new();
+    let mut best = None;
+    for (i, image) in group.into_iter().enumerate() {
+        if i == best_index {
+            best = Some(image);
+        } else {
+            variants.extend(image.variants);
+        }
+    }
+    let mut best = best?;
+    // The dropped copies' srcset candidates are candidates for the kept image too; the
+    // largest of them becomes the default download again
+    srcset::merge_variants(&mut best.variants, variants);
+    srcset::promote_largest(&mut best);
+    if best.alt.is_empty() {
+        best.alt = first_alt;
+    }
+    Some(best)
+}
+
+/// Group near-identical pictures by perceptual hash and keep the highest-resolution member of
+/// each group, in the position of the group's first image. `patterns` holds each image's
+/// URL-pattern key (`hero-small.jpg` and `hero.jpg` share one); a shared key only proposes a
+/// match, which the hashes must confirm, so unrelated files that differ by a size suffix stay
+/// apart. Images sharing a key are hashed first. Images that can't be hashed (SVGs, oversized
+/// or undecodable files, past `MAX_HASHES`) fall back to their URL-pattern grouping.
+pub(crate) async fn dedupe_images(images: Vec<ImageInfo>, patterns: Vec<String>, cache: &HashCache) -> Vec<ImageInfo> {
+    let mut sources: Vec<Option<String>> = vec![None; images.len()];
+    for (i, source) in hash_order(&patterns).into_iter().filter_map(|i| hash_source(&images[i]).map(|source| (i, source))).take(MAX_HASHES) {
+        sources[i] = Some(source);
+    }
+    let urls: Vec<String> = sources.iter().flatten().cloned().collect();
+    println!("[dedupe] Hashing {} images", urls.len());
+    let hashes = fetch::fetch_each(urls, cache, HASH_CONCURRENCY, hash_url).await;
+    let image_hashes = sources.into_iter().map(|source| source.and_then(|source| hashes.get(&source).copied().flatten())).collect();
+
+    let before = images.len();
+    let kept: Vec<ImageInfo> = group_images(images, patterns, image_hashes).into_iter().filter_map(keep_largest).collect();
+    println!("[dedupe] {} images → {} after content dedupe", before, kept.len());
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srcset::ImageVariant;
+    use image::{DynamicImage, RgbImage};
+
+    fn gradient(width: u32, height: u32, flip: bool) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let x = if flip { width - 1 - x } else { x };
+            let value = ((x * 255 / width) as u8).wrapping_add(((y * 40 / height) as u8).wrapping_mul(3));
+            image::Rgb([value, value / 2, 255 - value])
+        }))
+    }
+
+    fn image(src: &str, width: u32, height: u32) -> ImageInfo {
+        ImageInfo::new(src.to_string(), String::new(), width, height)
+    }
+
+    fn patterns(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    fn srcs(images: &[ImageInfo]) -> Vec<&str> {
+        images.iter().map(|image| image.src.as_str()).collect()
+    }
+
+    #[test]
+    fn resized_copies_hash_alike() {
+        let original = dhash_image(&gradient(400, 300, false)).unwrap();
+        let thumbnail = dhash_image(&gradient(120, 90, false)).unwrap();
+        let mirrored = dhash_image(&gradient(400, 300, true)).unwrap();
+        assert!(same_picture(original, thumbnail));
+        assert!(!same_picture(original, mirrored));
+        assert_eq!(dhash_image(&DynamicImage::ImageRgb8(RgbImage::from_pixel(50, 50, image::Rgb([9, 9, 9])))), None);
+    }
+
+    #[test]
+    fn distance_threshold() {
+        assert!(same_picture(0, 0b1111));
+        assert!(!same_picture(0, 0b11111));
+    }
+
+    #[test]
+    fn shared_patterns_are_hashed_first() {
+        assert_eq!(hash_order(&patterns(&["a", "hero", "b", "hero"])), [1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn hashes_confirm_or_reject_pattern_matches() {
+        let images = vec![image("/hero-small.jpg", 100, 50), image("/hero.jpg", 800, 400), image("/team-small.jpg", 100, 50), image("/team.jpg", 800, 400)];
+        let groups = group_images(images, patterns(&["/hero.jpg", "/hero.jpg", "/team.jpg", "/team.jpg"]), vec![Some(0xF0), Some(0xF1), Some(0xF0F0_F0F0), Some(0x0F0F_0F0F)]);
+        let grouped: Vec<Vec<&str>> = groups.iter().map(|group| srcs(group)).collect();
+        assert_eq!(grouped, [vec!["/hero-small.jpg", "/hero.jpg"], vec!["/team-small.jpg"], vec!["/team.jpg"]]);
+    }
+
+    #[test]
+    fn hashes_match_across_unrelated_urls() {
+        let images = vec![image("https://cdn.a.example/x1.jpg", 100, 50), image("https://cdn.b.example/photo.jpg", 800, 400)];
+        let groups = group_images(images, patterns(&["/x1.jpg", "/photo.jpg"]), vec![Some(0xFF00), Some(0xFF01)]);
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn unhashed_images_fall_back_to_patterns() {
+        let images = vec![image("/logo.svg", 0, 0), image("/logo@2x.svg", 0, 0), image("/mark.svg", 0, 0)];
+        let groups = group_images(images, patterns(&["/logo.svg", "/logo.svg", "/mark.svg"]), vec![None, None, None]);
+        let grouped: Vec<Vec<&str>> = groups.iter().map(|group| srcs(group)).collect();
+        assert_eq!(grouped, [vec!["/logo.svg", "/logo@2x.svg"], vec!["/mark.svg"]]);
+    }
+
+    #[test]
+    fn keeps_the_largest_copy() {
+        let mut first = image("/hero-small.jpg", 100, 50);
+        first.alt = "Our team".to_string();
+        first.variants = vec![ImageVariant { src: "/hero-1600.jpg".to_string(), width: 1600, ..Default::default() }];
+        let group = vec![first, image("/hero.jpg", 800, 400), image("/hero-copy.jpg", 800, 400)];
+        let kept = keep_largest(group).unwrap();
+        // The merged srcset candidate outranks the kept copy's own URL
+        assert_eq!(kept.src, "/hero-1600.jpg");
+        assert_eq!(kept.alt, "Our team");
+        assert!(kept.variants.iter().any(|variant| variant.src == "/hero.jpg"));
+        assert!(!kept.variants.iter().any(|variant| variant.src == "/hero-copy.jpg"));
+    }
+
+    #[test]
+    fn ties_keep_the_earlier_image() {
+        let mut second = image("/b.jpg", 10, 10);
+        second.alt = "B".to_string();
+        let kept = keep_largest(vec![image("/a.jpg", 10, 10), second]).unwrap();
+        assert_eq!((kept.src.as_str(), kept.alt.as_str()), ("/a.jpg", ""));
+        assert!(keep_largest(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn hashes_the_narrowest_candidate() {
+        let mut hero = image("https://brand.example/hero.jpg", 1600, 800);
+        hero.variants = vec![
+            ImageVariant { src: "https://brand.example/hero-400.avif".to_string(), width: 400, mime_type: "image/avif".to_string(), ..Default::default() },
+            ImageVariant { src: "https://brand.example/hero-800.jpg".to_string(), width: 800, ..Default::default() },
+            ImageVariant { src: "https://brand.example/hero.jpg".to_string(), width: 1600, ..Default::default() },
+        ];
+        assert_eq!(hash_source(&hero).as_deref(), Some("https://brand.example/hero-800.jpg"));
+
+        let mut big = image("https://brand.example/big.png", 4000, 4000);
+        big.file_size = Some(MAX_HASH_BYTES + 1);
+        assert_eq!(hash_source(&big), None);
+        let mut svg = image("https://brand.example/logo.svg", 0, 0);
+        svg.format = "svg".to_string();
+        assert_eq!(hash_source(&svg), None);
+        assert_eq!(hash_source(&image("data:image/png;base64,AAAA", 1, 1)), None);
+    }
+}
@@ -0,0 +1,193 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use crate::fetch;
+use crate::filenames;
+
+/// Default cap on a single download
+pub(crate) const DEFAULT_MAX_BYTES: u64 = 500_000_000;
+/// Times an interrupted transfer is resumed before giving up
+const MAX_RESUMES: usize = 3;
+/// Bytes between progress events
+const PROGRESS_STEP: u64 = 256 * 1024;
+/// Leading bytes kept in memory to detect the file type
+const HEAD_BYTES: usize = 4096;
+/// Time allowed to connect, and to wait for each read once connected. A stalled transfer fails
+/// after `READ_TIMEOUT` and is resumed; a slow but moving one is never cut off.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Progress of one download, emitted as the `download-progress` event
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct DownloadProgress {
+    pub(crate) url: String,
+    pub(crate) received: u64,
+    /// From `Content-Length`, when the server sends it
+    pub(crate) total: Option<u64>,
+    pub(crate) done: bool,
+}
+
+/// Client for user-requested downloads: no overall deadline, since large files take as long as
+/// they take, but connects and individual reads time out
+pub(crate) fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// A temp file that is deleted unless the download completes
+struct PartialFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Start offset of a `Content-Range: bytes 1000-1999/2000` header
+fn range_start(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Stream `url` into `dest_dir` without buffering it in memory. The body goes to a hidden
+/// `.part` file that is renamed into place only once the transfer is complete and matches
+/// `Content-Length`; interrupted transfers resume with a `Range` request.
+pub(crate) async fn download_to_dir(
+    client: &reqwest::Client,
+    url: &str,
+    dest_dir: &Path,
+    fallback_stem: &str,
+    max_bytes: u64,
+    mut on_progress: impl FnMut(DownloadProgress),
+) -> Result<PathBuf, String> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut partial = PartialFile { path: dest_dir.join(format!(".brandsnap-{}.part", nanos)), keep: false };
+    let mut file = tokio::fs::File::create(&partial.path)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", partial.path.display(), e))?;
+
+    let mut received: u64 = 0;
+    let mut total: Option<u64> = None;
+    let mut content_type = String::new();
+    let mut head: Vec<u8> = Vec::new();
+    let mut last_reported: u64 = 0;
+    let mut resumes = 0;
+
+    loop {
+        let mut request = client
+            .get(url)
+            .header("User-Agent", fetch::USER_AGENT);
+        if received > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", received));
+        }
+        let mut response = match request.send().await {
+            Ok(response) => response,
+            Err(e) if received > 0 && resumes < MAX_RESUMES => {
+                resumes += 1;
+                println!("[download] Resume request failed ({}), retrying", e);
+                continue;
+            }
+            Err(e) => return Err(format!("Download failed: {}", e)),
+        };
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Server returned {}", status));
+        }
+
+        // A server that ignores `Range` (or answers from another offset) sends the whole file again
+        if received > 0 && (status != reqwest::StatusCode::PARTIAL_CONTENT || range_start(&response) != Some(received)) {
+            println!("[download] Server can't resume {}, starting over", url);
+            file.set_len(0).await.map_err(|e| format!("Failed to reset file: {}", e))?;
+            file.seek(std::io::SeekFrom::Start(0)).await.map_err(|e| format!("Failed to reset file: {}", e))?;
+            received = 0;
+            last_reported = 0;
+            head.clear();
+        }
+        if received == 0 {
+            content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_lowercase();
+            total = response.content_length();
+            if let Some(len) = total.filter(|len| *len > max_bytes) {
+                return Err(format!("File is {} bytes, over the {} byte limit", len, max_bytes));
+            }
+        }
+
+        let interrupted = loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    received += chunk.len() as u64;
+                    if received > max_bytes {
+                        return Err(format!("Download exceeded the {} byte limit", max_bytes));
+                    }
+                    if head.len() < HEAD_BYTES {
+                        let take = (HEAD_BYTES - head.len()).min(chunk.len());
+                        head.extend_from_slice(&chunk[..take]);
+                    }
+                    file.write_all(&chunk).await.map_err(|e| format!("Failed to write file: {}", e))?;
+                    if received - last_reported >= PROGRESS_STEP {
+                        last_reported = received;
+                        on_progress(DownloadProgress { url: url.to_string(), received, total, done: false });
+                    }
+                }
+                Ok(None) => break false,
+                Err(e) => {
+                    println!("[download] Transfer of {} interrupted at {} bytes: {}", url, received, e);
+                    break true;
+                }
+            }
+        };
+        if !interrupted {
+            break;
+        }
+        resumes += 1;
+        if resumes > MAX_RESUMES {
+            return Err(format!("Download interrupted {} times, giving up", resumes));
+        }
+    }
+
+    file.flush().await.map_err(|e| format!("Failed to write file: {}", e))?;
+    drop(file);
+    if let Some(expected) = total {
+        if received != expected {
+            return Err(format!("Incomplete download: got {} of {} bytes", received, expected));
+        }
+    }
+
+    let name = filenames::name_from_url(url, &content_type, &head, fallback_stem);
+    // Claim the name first so a concurrent download can't take it, then move the data over it
+    let (path, _) = filenames::create_unique_file(dest_dir, &name)?;
+    if let Err(e) = tokio::fs::rename(&partial.path, &path).await {
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(format!("Failed to save file: {}", e));
+    }
+    partial.keep = true;
+    on_progress(DownloadProgress { url: url.to_string(), received, total, done: true });
+    Ok(path)
+}
@@ -0,0 +1,224 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Recently downloaded bodies kept in memory, in total bytes
+const RECENT_BYTES: usize = 32_000_000;
+
+/// Entries a `UrlCache` keeps before dropping the oldest
+const CACHE_CAPACITY: usize = 2_000;
+/// Desktop Chrome's User-Agent; some sites and CDNs refuse or downgrade unknown clients
+pub(crate) const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36";
+
+/// Why a download or decode failed
+#[derive(Debug)]
+pub(crate) struct FetchError {
+    pub(crate) message: String,
+    /// Retrying can't help (a 4xx response, an oversized file, content that doesn't parse), so the
+    /// failure may be cached. Network errors, timeouts and 5xx responses are worth another try.
+    pub(crate) permanent: bool,
+}
+
+impl FetchError {
+    pub(crate) fn permanent(message: String) -> Self {
+        FetchError { message, permanent: true }
+    }
+
+    pub(crate) fn transient(message: String) -> Self {
+        FetchError { message, permanent: false }
+    }
+
+    /// An unsuccessful response; client errors are permanent except timeouts and rate limits
+    pub(crate) fn status(url: &str, status: reqwest::StatusCode) -> Self {
+        let permanent = status.is_client_error() && !matches!(status.as_u16(), 408 | 429);
+        FetchError { message: format!("{} returned {}", url, status), permanent }
+    }
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Cached values with their insertion order, for evicting the oldest
+struct CacheEntries<V> {
+    values: HashMap<String, V>,
+    order: VecDeque<String>,
+}
+
+/// Per-URL results of a download-and-decode step (image probes, hashes, colors, font metadata),
+/// shared across analyses and bounded to `CACHE_CAPACITY` entries
+pub(crate) struct UrlCache<V>(Arc<Mutex<CacheEntries<V>>>);
+
+impl<V> Clone for UrlCache<V> {
+    fn clone(&self) -> Self {
+        UrlCache(self.0.clone())
+    }
+}
+
+impl<V> Default for UrlCache<V> {
+    fn default() -> Self {
+        UrlCache(Arc::new(Mutex::new(CacheEntries { values: HashMap::new(), order: VecDeque::new() })))
+    }
+}
+
+impl<V: Clone> UrlCache<V> {
+    pub(crate) fn get(&self, url: &str) -> Option<V> {
+        self.0.lock().unwrap().values.get(url).cloned()
+    }
+
+    pub(crate) fn insert(&self, url: String, value: V) {
+        let mut entries = self.0.lock().unwrap();
+        if entries.values.insert(url.clone(), value).is_none() {
+            entries.order.push_back(url);
+        }
+        while entries.order.len() > CACHE_CAPACITY {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.values.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Client shared by background downloads, so connections to a site's CDN are reused across steps
+pub(crate) fn client() -> Result<reqwest::Client, String> {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client.clone());
+    }
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .connect_timeout(std::time::Duration::from_secs(10))
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    Ok(CLIENT.get_or_init(|| client).clone())
+}
+
+/// Downloaded bodies by URL, oldest first
+type RecentBodies = VecDeque<(String, Vec<u8>)>;
+
+fn recent() -> &'static Mutex<RecentBodies> {
+    static RECENT: OnceLock<Mutex<RecentBodies>> = OnceLock::new();
+    RECENT.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+fn remember(url: &str, bytes: &[u8]) {
+    if bytes.len() > RECENT_BYTES / 4 {
+        return;
+    }
+    let mut recent = recent().lock().unwrap();
+    recent.retain(|(existing, _)| existing != url);
+    recent.push_back((url.to_string(), bytes.to_vec()));
+    while recent.iter().map(|(_, body)| body.len()).sum::<usize>() > RECENT_BYTES {
+        recent.pop_front();
+    }
+}
+
+/// Read a response body chunk by chunk, stopping once `max_bytes` have arrived so an oversized
+/// or endless body is never buffered whole. Returns the bytes read and whether the body went on
+/// past the cap.
+pub(crate) async fn read_capped(response: &mut reqwest::Response, max_bytes: usize) -> Result<(Vec<u8>, bool), String> {
+    let mut bytes = Vec::new();
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                if bytes.len() + chunk.len() > max_bytes {
+                    bytes.extend_from_slice(&chunk[..max_bytes - bytes.len()]);
+                    return Ok((bytes, true));
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            Ok(None) => return Ok((bytes, false)),
+            Err(e) => return Err(format!("Failed to read {}: {}", response.url(), e)),
+        }
+    }
+}
+
+/// Download a whole file of at most `max_bytes`, failing on anything larger. Recent bodies are
+/// served from memory, so steps reading the same file (a logo hashed for dedupe, then quantized
+/// for colors) download it once.
+pub(crate) async fn fetch_capped(client: &reqwest::Client, url: &str, max_bytes: usize, referer: Option<&str>) -> Result<Vec<u8>, FetchError> {
+    let too_large = || FetchError::permanent(format!("{} is larger than {} bytes", url, max_bytes));
+    let cached = recent().lock().unwrap().iter().find(|(existing, _)| existing == url).map(|(_, body)| body.clone());
+    if let Some(bytes) = cached {
+        return if bytes.len() <= max_bytes { Ok(bytes) } else { Err(too_large()) };
+    }
+
+    let mut request = client
+        .get(url)
+        .header("User-Agent", USER_AGENT);
+    if let Some(referer) = referer {
+        request = request.header("Referer", referer);
+    }
+    let mut response = request.send().await.map_err(|e| FetchError::transient(format!("Request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(FetchError::status(url, response.status()));
+    }
+    if response.content_length().is_some_and(|len| len > max_bytes as u64) {
+        return Err(too_large());
+    }
+    let (bytes, truncated) = read_capped(&mut response, max_bytes).await.map_err(FetchError::transient)?;
+    if truncated {
+        return Err(too_large());
+    }
+    remember(url, &bytes);
+    Ok(bytes)
+}
+
+/// Run `work` for every URL not already in `cache`, `concurrency` at a time on the shared client.
+/// Successes and permanent failures (`None`) are cached; URLs that failed transiently are left
+/// out of both the cache and the results so the next analysis tries them again.
+pub(crate) async fn fetch_each<V, F, Fut>(urls: Vec<String>, cache: &UrlCache<Option<V>>, concurrency: usize, work: F) -> HashMap<String, Option<V>>
+where
+    V: Clone + Send + 'static,
+    F: Fn(reqwest::Client, String) -> Fut,
+    Fut: Future<Output = Result<V, FetchError>> + Send + 'static,
+{
+    let mut results: HashMap<String, Option<V>> = HashMap::new();
+    let mut pending: Vec<String> = Vec::new();
+    for url in urls {
+        if results.contains_key(&url) || pending.contains(&url) {
+            continue;
+        }
+        match cache.get(&url) {
+            Some(cached) => {
+                results.insert(url, cached);
+            }
+            None => pending.push(url),
+        }
+    }
+    if pending.is_empty() {
+        return results;
+    }
+    let client = match client() {
+        Ok(client) => client,
+        Err(e) => {
+            println!("[fetch] {}", e);
+            return results;
+        }
+    };
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+    let mut tasks = tokio::task::JoinSet::new();
+    for url in pending {
+        let semaphore = semaphore.clone();
+        let task = work(client.clone(), url.clone());
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire().await;
+            (url, task.await)
+        });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((url, result)) = joined else { continue };
+        let value = match result {
+            Ok(value) => Some(value),
+            Err(e) if e.permanent => None,
+            Err(_) => continue,
+        };
+        cache.insert(url.clone(), value.clone());
+        results.insert(url, value);
+    }
+    results
+}
@@ -0,0 +1,229 @@
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+use crate::image_header;
+
+/// Longest file name we write, in characters (extension included)
+const MAX_NAME_CHARS: usize = 150;
+
+/// Names Windows refuses regardless of extension
+const RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9",
+    "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Numbered suffixes tried before giving up on a name
+const MAX_SUFFIX: usize = 10_000;
+
+/// Server-side script extensions that say nothing about the file actually served
+const SCRIPT_EXTENSIONS: &[&str] = &["php", "asp", "aspx", "jsp", "cgi", "ashx", "axd"];
+
+/// Decode `%20`-style escapes; invalid escapes are kept literally
+pub(crate) fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = value.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() && ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()) => (stem, ext),
+        _ => (name, ""),
+    }
+}
+
+/// Make a name safe on every desktop OS: no separators, control or reserved characters,
+/// no leading/trailing dots or spaces, no reserved device names, bounded length.
+/// Returns an empty string when nothing usable is left.
+pub(crate) fn sanitize(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*') { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let cleaned = cleaned.trim_matches(|c: char| c == '.' || c == ' ' || c == '_');
+
+    let (stem, ext) = split_extension(cleaned);
+    let stem = if RESERVED_NAMES.contains(&stem.to_lowercase().as_str()) { format!("_{}", stem) } else { stem.to_string() };
+    let ext_len = if ext.is_empty() { 0 } else { ext.chars().count() + 1 };
+    let stem: String = stem.chars().take(MAX_NAME_CHARS.saturating_sub(ext_len)).collect();
+    let stem = stem.trim_end_matches(['.', ' ']);
+    if stem.is_empty() {
+        String::new()
+    } else if ext.is_empty() {
+        stem.to_string()
+    } else {
+        format!("{}.{}", stem, ext.to_lowercase())
+    }
+}
+
+/// File extension for downloaded bytes: magic bytes first, then the `Content-Type`
+pub(crate) fn extension_for(content_type: &str, bytes: &[u8]) -> Option<&'static str> {
+    if let Some(header) = image_header::read_header(bytes) {
+        return Some(if header.format == "jpeg" { "jpg" } else { header.format });
+    }
+    let extension = match bytes {
+        [b'w', b'O', b'F', b'2', ..] => "woff2",
+        [b'w', b'O', b'F', b'F', ..] => "woff",
+        [0, 1, 0, 0, ..] | [b't', b'r', b'u', b'e', ..] => "ttf",
+        [b'O', b'T', b'T', b'O', ..] => "otf",
+        [b'%', b'P', b'D', b'F', ..] => "pdf",
+        [b'B', b'M', ..] => "bmp",
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] if content_type.starts_with("video/") => "mp4",
+        _ => match content_type.split(';').next().unwrap_or("").trim() {
+            "image/png" => "png",
+            "image/jpeg" | "image/jpg" | "image/pjpeg" => "jpg",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            "image/avif" => "avif",
+            "image/svg+xml" => "svg",
+            "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+            "image/bmp" => "bmp",
+            "image/tiff" => "tiff",
+            "font/woff2" => "woff2",
+            "font/woff" | "application/font-woff" => "woff",
+            "font/ttf" | "application/x-font-ttf" => "ttf",
+            "font/otf" => "otf",
+            "video/mp4" => "mp4",
+            "video/webm" => "webm",
+            "application/pdf" => "pdf",
+            _ => return None,
+        },
+    };
+    Some(extension)
+}
+
+/// Local file name for a download: the decoded last path segment, sanitized, with an extension
+/// from the content when the URL has none (`/image`, `/photo?id=3`, `/render.php`)
+pub(crate) fn name_from_url(url: &str, content_type: &str, bytes: &[u8], fallback_stem: &str) -> String {
+    let segment = Url::parse(url)
+        .ok()
+        .and_then(|u| u.path_segments().and_then(|mut segs| segs.next_back().map(percent_decode)))
+        .unwrap_or_default();
+    let mut name = sanitize(&segment);
+    if name.is_empty() {
+        name = fallback_stem.to_string();
+    }
+    let (stem, ext) = split_extension(&name);
+    if ext.is_empty() || SCRIPT_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
+        if let Some(detected) = extension_for(content_type, bytes) {
+            name = format!("{}.{}", stem, detected);
+        }
+    }
+    name
+}
+
+/// `logo.png` → `logo (2).png`
+fn with_suffix(name: &str, n: usize) -> String {
+    match split_extension(name) {
+        (stem, "") => format!("{} ({})", stem, n),
+        (stem, ext) => format!("{} ({}).{}", stem, n, ext),
+    }
+}
+
+/// Create `name` in `dir`, falling back to `name (1)`, `name (2)`, ... when it is taken. `create`
+/// must fail with `AlreadyExists` rather than replace an existing entry, so two writers racing
+/// for the same name never overwrite each other.
+fn create_unique<T>(dir: &Path, name: &str, create: impl Fn(&Path) -> std::io::Result<T>) -> Result<(PathBuf, T), String> {
+    let mut path = dir.join(name);
+    let mut n = 1;
+    loop {
+        match create(&path) {
+            Ok(created) => return Ok((path, created)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && n <= MAX_SUFFIX => {
+                path = dir.join(with_suffix(name, n));
+                n += 1;
+            }
+            Err(e) => return Err(format!("Failed to create {}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// A new, empty file in `dir` named `name` or `name (n)`, opened for writing
+pub(crate) fn create_unique_file(dir: &Path, name: &str) -> Result<(PathBuf, File), String> {
+    create_unique(dir, name, |path| OpenOptions::new().write(true).create_new(true).open(path))
+}
+
+/// A new, empty folder in `dir` named `name` or `name (n)`
+pub(crate) fn create_unique_dir(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    create_unique(dir, name, |path| std::fs::create_dir(path)).map(|(path, _)| path)
+}
+
+/// Like `create_unique_file`, for names that only have to be unique among each other (archive entries).
+/// Comparison ignores case so the result also extracts cleanly on macOS and Windows.
+pub(crate) fn unique_name(used: &mut HashSet<String>, name: &str) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 1;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = with_suffix(name, n);
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_names() {
+        assert_eq!(sanitize("brand/logo:dark?.PNG"), "brand_logo_dark_.png");
+        assert_eq!(sanitize("  ..hero\timage  .jpg.. "), "hero_image.jpg");
+        assert_eq!(sanitize("brand   guide  .pdf"), "brand guide.pdf");
+        assert_eq!(sanitize("CON.txt"), "_CON.txt");
+        assert_eq!(sanitize("nul"), "_nul");
+        assert_eq!(sanitize("..."), "");
+        assert_eq!(sanitize("a\u{0}b"), "a_b");
+        let long = sanitize(&format!("{}.woff2", "x".repeat(400)));
+        assert_eq!(long.chars().count(), MAX_NAME_CHARS);
+        assert!(long.ends_with("x.woff2"));
+    }
+
+    #[test]
+    fn names_downloads_from_urls() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x01\0\0\0\x01";
+        assert_eq!(name_from_url("https://example.com/img/Brand%20Logo.svg?v=2", "", b"", "image"), "Brand Logo.svg");
+        assert_eq!(name_from_url("https://example.com/render.php?id=3", "image/jpeg", png, "image"), "render.png");
+        assert_eq!(name_from_url("https://example.com/photo", "image/webp", b"", "image"), "photo.webp");
+        assert_eq!(name_from_url("https://example.com/", "image/gif", b"", "image-3"), "image-3.gif");
+        assert_eq!(name_from_url("https://example.com/%2e%2e%2fetc%2fpasswd", "", b"", "image"), "etc_passwd");
+        assert_eq!(name_from_url("https://example.com/font", "application/octet-stream", b"wOF2\0\0", "font"), "font.woff2");
+    }
+
+    #[test]
+    fn suffixes_taken_names() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_name(&mut used, "logo.png"), "logo.png");
+        assert_eq!(unique_name(&mut used, "Logo.PNG"), "Logo (1).PNG");
+        assert_eq!(unique_name(&mut used, "logo.png"), "logo (2).png");
+        assert_eq!(unique_name(&mut used, "README"), "README");
+        assert_eq!(unique_name(&mut used, "README"), "README (1)");
+    }
+
+    #[test]
+    fn creates_files_without_overwriting() {
+        let dir = std::env::temp_dir().join(format!("filenames-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (first, _) = create_unique_file(&dir, "a.txt").unwrap();
+        let (second, _) = create_unique_file(&dir, "a.txt").unwrap();
+        let folder = create_unique_dir(&dir, "a.txt").unwrap();
+        assert_eq!(first, dir.join("a.txt"));
+        assert_eq!(second, dir.join("a (1).txt"));
+        assert_eq!(folder, dir.join("a (2).txt"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
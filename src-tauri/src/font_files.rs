@@ -0,0 +1,566 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::io::Read;
+use ttf_parser::{name_id, PlatformId};
+
+use crate::fetch::{self, FetchError, UrlCache};
+use crate::fonts::FontFamily;
+
+/// Larger font files are skipped
+const MAX_FONT_BYTES: usize = 10_000_000;
+/// Files read per family (one per `@font-face` weight/style)
+const MAX_FILES_PER_FAMILY: usize = 4;
+/// Files read per page
+const MAX_FILES: usize = 24;
+/// Files fetched concurrently
+const CONCURRENCY: usize = 4;
+/// Decompressed tables larger than this are treated as corrupt
+const MAX_TABLE_BYTES: usize = 4_000_000;
+/// WOFF2 streams are decompressed up to the last wanted table, at most this far
+const MAX_STREAM_BYTES: usize = 64_000_000;
+/// Windows platform, US English
+const ENGLISH_US: u16 = 0x0409;
+
+/// Tags WOFF2 encodes as an index into this list instead of spelling out
+const WOFF2_KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm", b"glyf", b"loca", b"prep",
+    b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE",
+    b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt",
+    b"avar", b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty", b"just", b"lcar",
+    b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill",
+];
+/// The tables metadata is read from
+const WANTED: [&[u8; 4]; 3] = [b"name", b"OS/2", b"fvar"];
+
+/// One variation axis of a variable font
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct VariationAxis {
+    /// e.g. "wght", "wdth", "opsz"
+    pub(crate) tag: String,
+    pub(crate) name: String,
+    pub(crate) min: f32,
+    pub(crate) default: f32,
+    pub(crate) max: f32,
+}
+
+/// What a font file says about itself in its `name`, `OS/2` and `fvar` tables
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct FontFileInfo {
+    pub(crate) url: String,
+    /// "woff2", "woff", "ttf" or "otf"
+    pub(crate) format: String,
+    /// Typographic family (name ID 16), else the legacy family (ID 1)
+    pub(crate) family: String,
+    pub(crate) subfamily: String,
+    pub(crate) full_name: String,
+    pub(crate) version: String,
+    pub(crate) designer: String,
+    /// Manufacturer (name ID 8), usually the foundry
+    pub(crate) foundry: String,
+    /// Four-letter foundry code registered with Microsoft (`OS/2` achVendID)
+    pub(crate) vendor_id: String,
+    pub(crate) copyright: String,
+    pub(crate) trademark: String,
+    pub(crate) license: String,
+    pub(crate) license_url: String,
+    pub(crate) vendor_url: String,
+    /// `OS/2` usWeightClass, 1–1000
+    pub(crate) weight_class: Option<u16>,
+    /// Empty for static fonts
+    pub(crate) axes: Vec<VariationAxis>,
+}
+
+/// Metadata shared across analyses. `None` records a file that answered 4xx or could not be read.
+pub(crate) type FontCache = UrlCache<Option<FontFileInfo>>;
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn tag_at(data: &[u8], offset: usize) -> Option<[u8; 4]> {
+    data.get(offset..offset + 4)?.try_into().ok()
+}
+
+/// Wanted tables of a plain TrueType/OpenType file; collections read their first font
+fn sfnt_tables(data: &[u8]) -> Result<HashMap<[u8; 4], Vec<u8>>, String> {
+    let start = if data.starts_with(b"ttcf") { u32_at(data, 12).ok_or("Truncated collection header")? as usize } else { 0 };
+    let count = u16_at(data, start + 4).ok_or("Truncated font header")? as usize;
+    let mut tables = HashMap::new();
+    for index in 0..count {
+        let record = start + 12 + index * 16;
+        let tag = tag_at(data, record).ok_or("Truncated table directory")?;
+        if !WANTED.contains(&&tag) {
+            continue;
+        }
+        let offset = u32_at(data, record + 8).ok_or("Truncated table directory")? as usize;
+        let length = u32_at(data, record + 12).ok_or("Truncated table directory")? as usize;
+        let table = data.get(offset..offset.saturating_add(length)).ok_or("Table runs past the end of the file")?;
+        tables.insert(tag, table.to_vec());
+    }
+    Ok(tables)
+}
+
+/// WOFF 1: an sfnt whose tables are individually zlib-compressed
+fn woff_tables(data: &[u8]) -> Result<HashMap<[u8; 4], Vec<u8>>, String> {
+    let count = u16_at(data, 12).ok_or("Truncated WOFF header")? as usize;
+    let mut tables = HashMap::new();
+    for index in 0..count {
+        let record = 44 + index * 20;
+        let tag = tag_at(data, record).ok_or("Truncated WOFF directory")?;
+        if !WANTED.contains(&&tag) {
+            continue;
+        }
+        let offset = u32_at(data, record + 4).ok_or("Truncated WOFF directory")? as usize;
+        let compressed = u32_at(data, record + 8).ok_or("Truncated WOFF directory")? as usize;
+        let original = u32_at(data, record + 12).ok_or("Truncated WOFF directory")? as usize;
+        if original > MAX_TABLE_BYTES {
+            return Err(format!("{} table is too large", String::from_utf8_lossy(&tag)));
+        }
+        let raw = data.get(offset..offset.saturating_add(compressed)).ok_or("WOFF table runs past the end of the file")?;
+        let table = if compressed < original {
+            // Sizes come from the file; let the buffer grow with what actually inflates
+            let mut out = Vec::new();
+            flate2::read::ZlibDecoder::new(raw)
+                .take(original as u64)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Failed to inflate {}: {}", String::from_utf8_lossy(&tag), e))?;
+            out
+        } else {
+            raw.to_vec()
+        };
+        tables.insert(tag, table);
+    }
+    Ok(tables)
+}
+
+/// WOFF2 UIntBase128
+fn read_base128(data: &[u8], pos: &mut usize) -> Option<u32> {
+    let mut value: u32 = 0;
+    for i in 0..5 {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        if i == 0 && byte == 0x80 {
+            return None;
+        }
+        value = value.checked_mul(128)? | (byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// WOFF2 255UInt16
+fn read_255_u16(data: &[u8], pos: &mut usize) -> Option<u16> {
+    let code = *data.get(*pos)?;
+    *pos += 1;
+    match code {
+        253 => {
+            let value = u16_at(data, *pos)?;
+            *pos += 2;
+            Some(value)
+        }
+        254 => {
+            let next = *data.get(*pos)?;
+            *pos += 1;
+            Some(next as u16 + 253 * 2)
+        }
+        255 => {
+            let next = *data.get(*pos)?;
+            *pos += 1;
+            Some(next as u16 + 253)
+        }
+        _ => Some(code as u16),
+    }
+}
+
+/// WOFF2: one brotli stream holding every table back to back. Only `glyf`, `loca` and `hmtx` are
+/// ever transformed, so the metadata tables come out of the stream as-is.
+fn woff2_tables(data: &[u8]) -> Result<HashMap<[u8; 4], Vec<u8>>, String> {
+    let flavor = tag_at(data, 4).ok_or("Truncated WOFF2 header")?;
+    let count = u16_at(data, 12).ok_or("Truncated WOFF2 header")? as usize;
+    let compressed_size = u32_at(data, 20).ok_or("Truncated WOFF2 header")? as usize;
+
+    // Directory: (tag, offset into the decompressed stream, length)
+    let mut pos = 48;
+    let mut stream_offset = 0usize;
+    let mut directory = Vec::new();
+    for _ in 0..count {
+        let flags = *data.get(pos).ok_or("Truncated WOFF2 directory")?;
+        pos += 1;
+        let tag = match flags & 0x3f {
+            63 => {
+                let tag = tag_at(data, pos).ok_or("Truncated WOFF2 directory")?;
+                pos += 4;
+                tag
+            }
+            index => *WOFF2_KNOWN_TAGS[index as usize],
+        };
+        let version = flags >> 6;
+        let original = read_base128(data, &mut pos).ok_or("Bad WOFF2 table length")? as usize;
+        let transformed = if &tag == b"glyf" || &tag == b"loca" { version != 3 } else { version != 0 };
+        let length = if transformed { read_base128(data, &mut pos).ok_or("Bad WOFF2 transform length")? as usize } else { original };
+        if WANTED.contains(&&tag) && length > MAX_TABLE_BYTES {
+            return Err(format!("{} table is too large", String::from_utf8_lossy(&tag)));
+        }
+        directory.push((tag, stream_offset, length));
+        stream_offset = stream_offset.checked_add(length).ok_or("WOFF2 directory overflows")?;
+    }
+    if &flavor == b"ttcf" {
+        // Collection directory: skip it, the table stream follows
+        pos += 4;
+        let fonts = read_255_u16(data, &mut pos).ok_or("Truncated WOFF2 collection")?;
+        for _ in 0..fonts {
+            let tables = read_255_u16(data, &mut pos).ok_or("Truncated WOFF2 collection")?;
+            pos += 4;
+            for _ in 0..tables {
+                read_255_u16(data, &mut pos).ok_or("Truncated WOFF2 collection")?;
+            }
+        }
+    }
+
+    // Tables we need sit wherever the directory puts them; stop decompressing past the last one
+    let needed = directory
+        .iter()
+        .filter(|(tag, _, _)| WANTED.contains(&tag))
+        .map(|(_, offset, length)| offset.saturating_add(*length))
+        .max()
+        .unwrap_or(0);
+    if needed > MAX_STREAM_BYTES {
+        return Err("WOFF2 stream is too large".to_string());
+    }
+    let compressed = data.get(pos..pos.saturating_add(compressed_size)).ok_or("WOFF2 data runs past the end of the file")?;
+    let mut stream = Vec::new();
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .take(needed as u64)
+        .read_to_end(&mut stream)
+        .map_err(|e| format!("Failed to decompress WOFF2: {}", e))?;
+
+    let mut tables = HashMap::new();
+    for (tag, offset, length) in directory {
+        if WANTED.contains(&&tag) {
+            let table = stream.get(offset..offset.saturating_add(length)).ok_or("WOFF2 stream is truncated")?;
+            tables.insert(tag, table.to_vec());
+        }
+    }
+    Ok(tables)
+}
+
+/// Best string for a name ID: Windows US English, then any Unicode record, then Mac Roman ASCII
+fn name_string(table: &ttf_parser::name::Table, id: u16) -> String {
+    let records = || table.names.into_iter().filter(move |name| name.name_id == id);
+    let english = records().find(|name| name.platform_id == PlatformId::Windows && name.language_id == ENGLISH_US);
+    english
+        .and_then(|name| name.to_string())
+        .or_else(|| records().find_map(|name| name.to_string()))
+        .or_else(|| {
+            records()
+                .find(|name| name.platform_id == PlatformId::Macintosh && name.encoding_id == 0 && name.name.is_ascii())
+                .map(|name| String::from_utf8_lossy(name.name).to_string())
+        })
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Read metadata from a font file (WOFF2, WOFF, TrueType, OpenType or a collection)
+pub(crate) fn read_font_info(data: &[u8], url: &str) -> Result<FontFileInfo, String> {
+    let (format, tables) = match data.get(..4) {
+        Some(b"wOF2") => ("woff2", woff2_tables(data)?),
+        Some(b"wOFF") => ("woff", woff_tables(data)?),
+        Some(b"OTTO") => ("otf", sfnt_tables(data)?),
+        Some([0, 1, 0, 0]) | Some(b"true") | Some(b"ttcf") => ("ttf", sfnt_tables(data)?),
+        _ => return Err("Not a font file".to_string()),
+    };
+    let name_data = tables.get(b"name").ok_or("No name table")?;
+    let names = ttf_parser::name::Table::parse(name_data).ok_or("Malformed name table")?;
+    let get = |id: u16| name_string(&names, id);
+
+    let os2 = tables.get(b"OS/2");
+    let weight_class = os2.and_then(|t| u16_at(t, 4)).filter(|w| (1..=1000).contains(w));
+    let vendor_id = os2
+        .and_then(|t| t.get(58..62))
+        .map(|v| String::from_utf8_lossy(v).trim_matches(['\0', ' ']).to_string())
+        // Unregistered fonts often carry a placeholder like "????"
+        .filter(|v| v.chars().any(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_default();
+    let axes = tables
+        .get(b"fvar")
+        .and_then(|t| ttf_parser::fvar::Table::parse(t))
+        .map(|fvar| {
+            fvar.axes
+                .into_iter()
+                .filter(|axis| !axis.hidden)
+                .map(|axis| VariationAxis {
+                    tag: String::from_utf8_lossy(&axis.tag.to_bytes()).to_string(),
+                    name: get(axis.name_id),
+                    min: axis.min_value,
+                    default: axis.def_value,
+                    max: axis.max_value,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let typographic = get(name_id::TYPOGRAPHIC_FAMILY);
+    Ok(FontFileInfo {
+        url: url.to_string(),
+        format: format.to_string(),
+        family: if typographic.is_empty() { get(name_id::FAMILY) } else { typographic },
+        subfamily: get(name_id::SUBFAMILY),
+        full_name: get(name_id::FULL_NAME),
+        version: get(name_id::VERSION),
+        designer: get(name_id::DESIGNER),
+        foundry: get(name_id::MANUFACTURER),
+        vendor_id,
+        copyright: get(name_id::COPYRIGHT_NOTICE),
+        trademark: get(name_id::TRADEMARK),
+        license: get(name_id::LICENSE),
+        license_url: get(name_id::LICENSE_URL),
+        vendor_url: get(name_id::VENDOR_URL),
+        weight_class,
+        axes,
+    })
+}
+
+/// Download a font file and read its metadata
+async fn font_info(client: reqwest::Client, url: String, referer: String) -> Result<FontFileInfo, FetchError> {
+    // Adobe Fonts and some CDNs only serve files to the domains a kit is registered for
+    let result = match fetch::fetch_capped(&client, &url, MAX_FONT_BYTES, Some(&referer)).await {
+        // Decompressing is CPU-bound; keep it off the async workers
+        Ok(bytes) => {
+            let url = url.clone();
+            tokio::task::spawn_blocking(move || read_font_info(&bytes, &url).map_err(FetchError::permanent))
+                .await
+                .unwrap_or_else(|e| Err(FetchError::transient(format!("Task failed: {}", e))))
+        }
+        Err(e) => Err(e),
+    };
+    result.inspect_err(|e| println!("[font_files] No metadata for {}: {}", url, e))
+}
+
+/// Files to read for a family: one per `@font-face` (the first `src`, usually WOFF2), skipping
+/// other formats of the same file (`brand.woff2` / `brand.woff`)
+fn pick_files(family: &FontFamily) -> Vec<String> {
+    let mut stems: Vec<&str> = Vec::new();
+    let mut picked = Vec::new();
+    for file in &family.files {
+        let path = file.split(['?', '#']).next().unwrap_or(file);
+        let stem = path.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(path);
+        if !stems.contains(&stem) {
+            stems.push(stem);
+            picked.push(file.clone());
+        }
+    }
+    picked.truncate(MAX_FILES_PER_FAMILY);
+    picked
+}
+
+/// Download the web font files of each family and fill `file_info`
+pub(crate) async fn extract_font_info(families: &mut [FontFamily], page_url: &str, cache: &FontCache) {
+    let targets: Vec<(usize, String)> = families
+        .iter()
+        .enumerate()
+        .flat_map(|(index, family)| pick_files(family).into_iter().map(move |url| (index, url)))
+        .take(MAX_FILES)
+        .collect();
+
+    let urls = targets.iter().map(|(_, url)| url.clone()).collect();
+    let referer = page_url.to_string();
+    let infos = fetch::fetch_each(urls, cache, CONCURRENCY, |client, url| font_info(client, url, referer.clone())).await;
+    for (index, url) in targets {
+        if let Some(Some(info)) = infos.get(&url) {
+            families[index].file_info.push(info.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Table = ([u8; 4], Vec<u8>);
+
+    fn name_table(records: &[(u16, &str)]) -> Vec<u8> {
+        let strings: Vec<Vec<u8>> = records.iter().map(|(_, s)| s.encode_utf16().flat_map(u16::to_be_bytes).collect()).collect();
+        let mut table = [0u16, records.len() as u16, 6 + 12 * records.len() as u16].map(u16::to_be_bytes).concat();
+        let mut offset = 0;
+        for ((id, _), string) in records.iter().zip(&strings) {
+            for field in [3, 1, ENGLISH_US, *id, string.len() as u16, offset] {
+                table.extend(field.to_be_bytes());
+            }
+            offset += string.len() as u16;
+        }
+        table.extend(strings.concat());
+        table
+    }
+
+    fn os2_table(weight: u16, vendor: &[u8; 4]) -> Vec<u8> {
+        let mut table = vec![0; 78];
+        table[4..6].copy_from_slice(&weight.to_be_bytes());
+        table[58..62].copy_from_slice(vendor);
+        table
+    }
+
+    fn fvar_table() -> Vec<u8> {
+        let mut table = [1u16, 0, 16, 2, 1, 20, 0, 8].map(u16::to_be_bytes).concat();
+        table.extend(b"wght");
+        for value in [100i32, 400, 900] {
+            table.extend((value << 16).to_be_bytes());
+        }
+        table.extend([0u16, 256].map(u16::to_be_bytes).concat());
+        table
+    }
+
+    fn tables() -> Vec<Table> {
+        vec![
+            (*b"head", vec![0; 54]),
+            (*b"name", name_table(&[(1, "Brand Sans Bold"), (2, "Regular"), (8, "Acme Type"), (16, "Brand Sans"), (256, "Weight")])),
+            (*b"OS/2", os2_table(700, b"ACME")),
+            (*b"fvar", fvar_table()),
+        ]
+    }
+
+    fn sfnt(tables: &[Table]) -> Vec<u8> {
+        let mut font = [1u16, 0].map(u16::to_be_bytes).concat();
+        font.extend([tables.len() as u16, 0, 0, 0].map(u16::to_be_bytes).concat());
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, data) in tables {
+            font.extend(tag);
+            font.extend([0, offset as u32, data.len() as u32].map(u32::to_be_bytes).concat());
+            offset += data.len();
+        }
+        font.extend(tables.iter().flat_map(|(_, data)| data.clone()));
+        font
+    }
+
+    fn woff(tables: &[Table]) -> Vec<u8> {
+        let mut font = b"wOFF\0\x01\0\0\0\0\0\0".to_vec();
+        font.extend((tables.len() as u16).to_be_bytes());
+        font.resize(44, 0);
+        let mut offset = 44 + 20 * tables.len();
+        for (tag, data) in tables {
+            font.extend(tag);
+            font.extend([offset as u32, data.len() as u32, data.len() as u32, 0].map(u32::to_be_bytes).concat());
+            offset += data.len();
+        }
+        font.extend(tables.iter().flat_map(|(_, data)| data.clone()));
+        font
+    }
+
+    fn base128(value: u32) -> Vec<u8> {
+        let mut bytes = vec![(value & 0x7f) as u8];
+        let mut rest = value >> 7;
+        while rest > 0 {
+            bytes.insert(0, (rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        bytes
+    }
+
+    /// A brotli stream holding `data` in one uncompressed meta-block
+    fn brotli_stored(data: &[u8]) -> Vec<u8> {
+        let header = ((data.len() as u32 - 1) << 4) | (1 << 20);
+        let mut stream = header.to_le_bytes()[..3].to_vec();
+        stream.extend(data);
+        stream.push(0x03);
+        stream
+    }
+
+    /// WOFF2 with a transformed `glyf` table ahead of the wanted ones
+    fn woff2(tables: &[Table]) -> Vec<u8> {
+        let glyf = vec![7; 300];
+        let mut directory = vec![10];
+        directory.extend(base128(1000));
+        directory.extend(base128(glyf.len() as u32));
+        let mut stream = glyf;
+        for (tag, data) in tables {
+            match WOFF2_KNOWN_TAGS.iter().position(|known| *known == tag) {
+                Some(index) => directory.push(index as u8),
+                None => {
+                    directory.push(63);
+                    directory.extend(tag);
+                }
+            }
+            directory.extend(base128(data.len() as u32));
+            stream.extend(data);
+        }
+        let compressed = brotli_stored(&stream);
+        let mut font = b"wOF2\0\x01\0\0\0\0\0\0".to_vec();
+        font.extend((tables.len() as u16 + 1).to_be_bytes());
+        font.extend([0, 0]);
+        font.extend([0, compressed.len() as u32].map(u32::to_be_bytes).concat());
+        font.resize(48, 0);
+        font.extend(directory);
+        font.extend(compressed);
+        font
+    }
+
+    fn assert_brand_sans(info: &FontFileInfo) {
+        assert_eq!(info.family, "Brand Sans");
+        assert_eq!(info.subfamily, "Regular");
+        assert_eq!(info.foundry, "Acme Type");
+        assert_eq!(info.vendor_id, "ACME");
+        assert_eq!(info.weight_class, Some(700));
+        assert_eq!(info.axes.len(), 1);
+        let axis = &info.axes[0];
+        assert_eq!((axis.tag.as_str(), axis.name.as_str(), axis.min, axis.default, axis.max), ("wght", "Weight", 100.0, 400.0, 900.0));
+    }
+
+    #[test]
+    fn reads_woff2_tables_after_transformed_glyf() {
+        let font = woff2(&tables());
+        let read = woff2_tables(&font).unwrap();
+        assert_eq!(read.len(), 3);
+        assert_eq!(read[b"OS/2"], os2_table(700, b"ACME"));
+        let info = read_font_info(&font, "https://example.com/brand.woff2").unwrap();
+        assert_eq!(info.format, "woff2");
+        assert_brand_sans(&info);
+    }
+
+    #[test]
+    fn reads_woff_and_sfnt() {
+        let info = read_font_info(&woff(&tables()), "a.woff").unwrap();
+        assert_eq!(info.format, "woff");
+        assert_brand_sans(&info);
+        let info = read_font_info(&sfnt(&tables()), "a.ttf").unwrap();
+        assert_eq!(info.format, "ttf");
+        assert_brand_sans(&info);
+    }
+
+    #[test]
+    fn falls_back_to_legacy_family_and_skips_placeholder_vendors() {
+        let tables = vec![(*b"name", name_table(&[(1, "Legacy")])), (*b"OS/2", os2_table(0, b"????"))];
+        let info = read_font_info(&sfnt(&tables), "a.ttf").unwrap();
+        assert_eq!(info.family, "Legacy");
+        assert_eq!(info.vendor_id, "");
+        assert_eq!(info.weight_class, None);
+        assert!(info.axes.is_empty());
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        let font = woff2(&tables());
+        assert!(woff2_tables(&font[..60]).is_err());
+        assert!(woff2_tables(&font[..font.len() - 40]).is_err());
+        assert!(read_font_info(&sfnt(&[(*b"OS/2", os2_table(400, b"ACME"))]), "a.ttf").is_err());
+        assert!(read_font_info(b"<html>", "a.woff2").is_err());
+    }
+
+    #[test]
+    fn reads_woff2_integers() {
+        let read = |bytes: &[u8]| read_base128(bytes, &mut 0);
+        assert_eq!(read(&base128(63)), Some(63));
+        assert_eq!(read(&base128(1_000_000)), Some(1_000_000));
+        assert_eq!(read(&[0x80, 0x01]), None);
+        assert_eq!(read(&[0xFF; 5]), None);
+        let read = |bytes: &[u8]| read_255_u16(bytes, &mut 0);
+        assert_eq!(read(&[100]), Some(100));
+        assert_eq!(read(&[255, 10]), Some(263));
+        assert_eq!(read(&[254, 10]), Some(516));
+        assert_eq!(read(&[253, 0x12, 0x34]), Some(0x1234));
+    }
+}
@@ -0,0 +1,305 @@
+use serde::{Serialize, Deserialize};
+use url::Url;
+
+use crate::css::{self, CssRule};
+use crate::font_files::FontFileInfo;
+use crate::stylesheets::ParsedSheets;
+use crate::themes;
+use crate::typography::{self, TextStyle};
+
+/// Font services, by the hosts their stylesheets and files are served from
+const PROVIDERS: &[(&str, &str)] = &[
+    ("fonts.googleapis.com", "google"),
+    ("fonts.gstatic.com", "google"),
+    ("use.typekit.net", "adobe"),
+    ("p.typekit.net", "adobe"),
+    ("use.typekit.com", "adobe"),
+    ("fonts.bunny.net", "bunny"),
+    ("api.fontshare.com", "fontshare"),
+    ("cdn.fontshare.com", "fontshare"),
+];
+/// Families preinstalled on Windows, macOS, iOS, Android or common Linux desktops
+const SYSTEM_FONTS: &[&str] = &[
+    "arial", "arial black", "helvetica", "helvetica neue", "segoe ui", "segoe ui emoji", "segoe ui symbol", "tahoma",
+    "verdana", "trebuchet ms", "georgia", "times", "times new roman", "courier", "courier new", "lucida grande",
+    "lucida console", "lucida sans unicode", "impact", "palatino", "palatino linotype", "garamond", "calibri", "cambria",
+    "consolas", "menlo", "monaco", "sf pro", "sf pro text", "sf pro display", "sf mono", "san francisco", ".sfnstext",
+    "apple color emoji", "apple sd gothic neo", "avenir", "avenir next", "optima", "futura", "gill sans", "noto sans",
+    "noto color emoji", "noto serif", "droid sans", "roboto", "ubuntu", "cantarell", "oxygen", "oxygen-sans",
+    "fira sans", "dejavu sans", "liberation sans", "liberation mono", "microsoft yahei", "pingfang sc", "hiragino sans",
+];
+
+/// Where a family is loaded from: an `@font-face` rule or a provider stylesheet that requests it
+#[derive(Clone, Debug)]
+pub(crate) struct FontSource {
+    pub(crate) family: String,
+    /// "google", "adobe", "bunny", "fontshare" or "self-hosted"
+    pub(crate) provider: String,
+    /// Stylesheet declaring or requesting the family
+    pub(crate) stylesheet: String,
+    /// Font files from `@font-face` `src`, resolved against the stylesheet
+    pub(crate) files: Vec<String>,
+}
+
+/// A font stack as the browser computed it, with the number of elements using it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct FontStack {
+    pub(crate) stack: String,
+    #[serde(default)]
+    pub(crate) count: usize,
+}
+
+/// One family the page names, classified by its place in the stacks and linked to its source
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct FontFamily {
+    pub(crate) name: String,
+    /// "primary" (first typeface in a stack), "fallback" or "generic" (`sans-serif`, `system-ui`, ...)
+    pub(crate) role: String,
+    /// Preinstalled on common operating systems and not loaded as a web font
+    pub(crate) system: bool,
+    /// "google", "adobe", "bunny", "fontshare" or "self-hosted"; empty when not loaded as a web font
+    pub(crate) provider: String,
+    /// Stylesheets declaring or requesting the family
+    pub(crate) stylesheets: Vec<String>,
+    /// Font files from its `@font-face` rules
+    pub(crate) files: Vec<String>,
+    /// Elements whose stack includes the family
+    pub(crate) usage: usize,
+    /// What the downloaded font files say about themselves: real family, foundry, license, axes
+    #[serde(default)]
+    pub(crate) file_info: Vec<FontFileInfo>,
+}
+
+fn provider_for(url: &Url) -> Option<&'static str> {
+    let host = url.host_str()?.to_lowercase();
+    PROVIDERS.iter().find(|(provider_host, _)| host == *provider_host).map(|(_, provider)| *provider)
+}
+
+/// Families a provider stylesheet URL asks for: Google and Bunny `family=Inter:wght@400|Lato`
+/// (css and css2 APIs), Fontshare `f[]=satoshi@400`
+fn requested_families(url: &Url) -> Vec<String> {
+    url.query_pairs()
+        .filter(|(key, _)| key == "family" || key == "f[]")
+        .flat_map(|(_, value)| value.split('|').map(|family| family.split([':', '@']).next().unwrap_or("").trim().to_string()).collect::<Vec<_>>())
+        .filter(|family| !family.is_empty())
+        .collect()
+}
+
+/// Web font sources: every `@font-face` rule, plus the families provider stylesheet URLs request
+/// (their CSS may not have been fetched, and Typekit kits only name families inside)
+pub(crate) fn font_sources(parsed: &ParsedSheets) -> Vec<FontSource> {
+    let mut sources = Vec::new();
+    for (sheet, rules) in parsed.per_sheet() {
+        let Ok(sheet_url) = Url::parse(&sheet.url) else { continue };
+        let sheet_provider = if sheet.inline { None } else { provider_for(&sheet_url) };
+        if let Some(provider) = sheet_provider {
+            for family in requested_families(&sheet_url) {
+                sources.push(FontSource { family, provider: provider.to_string(), stylesheet: sheet.url.clone(), files: Vec::new() });
+            }
+        }
+        for rule in rules.iter().filter(|rule| rule.selectors.eq_ignore_ascii_case("@font-face")) {
+            let Some(family) = rule.get("font-family").map(|f| f.trim().trim_matches(['"', '\'']).trim().to_string()) else { continue };
+            let files: Vec<Url> = rule.get("src").map(css::urls).unwrap_or_default().iter().filter_map(|src| sheet_url.join(src).ok()).collect();
+            let provider = files.iter().find_map(provider_for).or(sheet_provider).unwrap_or("self-hosted");
+            sources.push(FontSource {
+                family,
+                provider: provider.to_string(),
+                stylesheet: sheet.url.clone(),
+                files: files.iter().map(|url| url.to_string()).collect(),
+            });
+        }
+    }
+    sources
+}
+
+/// `font-family` values declared anywhere in the stylesheets, for pages the browser couldn't render
+pub(crate) fn declared_stacks(rules: &[CssRule]) -> Vec<FontStack> {
+    let variables = themes::root_variables(rules);
+    let mut stacks: Vec<FontStack> = Vec::new();
+    for rule in rules.iter().filter(|rule| !rule.selectors.starts_with('@')) {
+        let Some(value) = rule.get("font-family") else { continue };
+        let stack = themes::resolve_vars(value, &variables);
+        if stack.is_empty() || stack.eq_ignore_ascii_case("inherit") || stacks.iter().any(|s| s.stack == stack) {
+            continue;
+        }
+        stacks.push(FontStack { stack, count: 0 });
+    }
+    stacks
+}
+
+/// Classify every family in the page's stacks (browser-computed first, then the typography roles and
+/// stylesheet declarations) and link it to its web font sources. Families loaded only through
+/// `@font-face` but never used in a stack are listed as primary too, since the site ships them.
+pub(crate) fn resolve_families(stacks: &[FontStack], styles: &[TextStyle], declared: &[FontStack], sources: &[FontSource]) -> Vec<FontFamily> {
+    let mut families: Vec<FontFamily> = Vec::new();
+    let role_stacks = styles.iter().map(|style| (style.stack.clone(), 0));
+    let all_stacks = stacks
+        .iter()
+        .chain(declared)
+        .map(|stack| (typography::split_stack(&stack.stack), stack.count))
+        .chain(role_stacks);
+
+    for (stack, count) in all_stacks {
+        let primary = stack.iter().position(|family| !typography::is_generic(family));
+        for (index, name) in stack.iter().enumerate() {
+            let role = if typography::is_generic(name) {
+                "generic"
+            } else if Some(index) == primary {
+                "primary"
+            } else {
+                "fallback"
+            };
+            match families.iter_mut().find(|family| family.name.eq_ignore_ascii_case(name)) {
+                Some(family) => {
+                    family.usage += count;
+                    if role == "primary" {
+                        family.role = role.to_string();
+                    }
+                }
+                None => families.push(FontFamily {
+                    name: name.clone(),
+                    role: role.to_string(),
+                    system: false,
+                    provider: String::new(),
+                    stylesheets: Vec::new(),
+                    files: Vec::new(),
+                    usage: count,
+                    file_info: Vec::new(),
+                }),
+            }
+        }
+    }
+
+    for source in sources {
+        let family = match families.iter_mut().position(|family| family.name.eq_ignore_ascii_case(&source.family)) {
+            Some(index) => &mut families[index],
+            None => {
+                families.push(FontFamily {
+                    name: source.family.clone(),
+                    role: "primary".to_string(),
+                    system: false,
+                    provider: String::new(),
+                    stylesheets: Vec::new(),
+                    files: Vec::new(),
+                    usage: 0,
+                    file_info: Vec::new(),
+                });
+                families.last_mut().unwrap()
+            }
+        };
+        // A provider beats "self-hosted" when a family is both requested and re-declared locally
+        if family.provider.is_empty() || family.provider == "self-hosted" {
+            family.provider = source.provider.clone();
+        }
+        if !family.stylesheets.contains(&source.stylesheet) {
+            family.stylesheets.push(source.stylesheet.clone());
+        }
+        for file in &source.files {
+            if !family.files.contains(file) {
+                family.files.push(file.clone());
+            }
+        }
+    }
+
+    for family in &mut families {
+        family.system = family.role != "generic" && family.provider.is_empty() && SYSTEM_FONTS.contains(&family.name.to_lowercase().as_str());
+    }
+    let rank = |family: &FontFamily| match family.role.as_str() {
+        "primary" => 0,
+        "fallback" => 1,
+        _ => 2,
+    };
+    families.sort_by_key(|family| (rank(family), std::cmp::Reverse(family.usage)));
+    families
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requested(url: &str) -> Vec<String> {
+        requested_families(&Url::parse(url).unwrap())
+    }
+
+    fn stack(value: &str, count: usize) -> FontStack {
+        FontStack { stack: value.to_string(), count }
+    }
+
+    fn source(family: &str, provider: &str, stylesheet: &str, files: &[&str]) -> FontSource {
+        FontSource {
+            family: family.to_string(),
+            provider: provider.to_string(),
+            stylesheet: stylesheet.to_string(),
+            files: files.iter().map(|file| file.to_string()).collect(),
+        }
+    }
+
+    fn family<'a>(families: &'a [FontFamily], name: &str) -> &'a FontFamily {
+        families.iter().find(|family| family.name == name).unwrap()
+    }
+
+    #[test]
+    fn reads_provider_requests() {
+        assert_eq!(requested("https://fonts.googleapis.com/css?family=Open+Sans:400,700|Lato"), ["Open Sans", "Lato"]);
+        assert_eq!(
+            requested("https://fonts.googleapis.com/css2?family=Inter:wght@400;700&family=Roboto+Mono&display=swap"),
+            ["Inter", "Roboto Mono"]
+        );
+        assert_eq!(requested("https://fonts.bunny.net/css?family=inter:400,600"), ["inter"]);
+        assert_eq!(requested("https://api.fontshare.com/v2/css?f[]=satoshi@400,700&f[]=general-sans@500&display=swap"), ["satoshi", "general-sans"]);
+        assert!(requested("https://use.typekit.net/abc1234.css").is_empty());
+    }
+
+    #[test]
+    fn classifies_primary_fallback_and_generic() {
+        let stacks = [stack("\"Brand Sans\", Helvetica, Arial, sans-serif", 10), stack("Georgia, serif", 2)];
+        let families = resolve_families(&stacks, &[], &[stack("Helvetica, system-ui", 0)], &[]);
+        let roles: Vec<(&str, &str, usize)> = families.iter().map(|f| (f.name.as_str(), f.role.as_str(), f.usage)).collect();
+        assert_eq!(
+            roles,
+            [
+                ("Brand Sans", "primary", 10),
+                ("Helvetica", "primary", 10),
+                ("Georgia", "primary", 2),
+                ("Arial", "fallback", 10),
+                ("sans-serif", "generic", 10),
+                ("serif", "generic", 2),
+                ("system-ui", "generic", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn providers_beat_self_hosted() {
+        let stacks = [stack("Inter, sans-serif", 1)];
+        let google = source("Inter", "google", "https://fonts.googleapis.com/css2?family=Inter", &[]);
+        let local = source("Inter", "self-hosted", "https://brand.example/site.css", &["https://brand.example/inter.woff2"]);
+        for sources in [[local.clone(), google.clone()], [google, local]] {
+            let families = resolve_families(&stacks, &[], &[], &sources);
+            let inter = family(&families, "Inter");
+            assert_eq!(inter.provider, "google");
+            assert_eq!(inter.stylesheets.len(), 2);
+            assert_eq!(inter.files, ["https://brand.example/inter.woff2"]);
+        }
+    }
+
+    #[test]
+    fn loaded_but_unused_families_are_primary() {
+        let sources = [source("Brand Display", "self-hosted", "https://brand.example/site.css", &[])];
+        let families = resolve_families(&[stack("Arial, sans-serif", 3)], &[], &[], &sources);
+        assert_eq!(family(&families, "Brand Display").role, "primary");
+        assert_eq!(family(&families, "Brand Display").provider, "self-hosted");
+    }
+
+    #[test]
+    fn marks_system_fonts_not_loaded_as_web_fonts() {
+        let stacks = [stack("Roboto, \"Segoe UI\", Brand, -apple-system, sans-serif", 1)];
+        let sources = [source("Roboto", "google", "https://fonts.googleapis.com/css2?family=Roboto", &[])];
+        let families = resolve_families(&stacks, &[], &[], &sources);
+        assert!(!family(&families, "Roboto").system);
+        assert!(family(&families, "Segoe UI").system);
+        assert!(!family(&families, "Brand").system);
+        assert!(!family(&families, "-apple-system").system);
+        assert_eq!(family(&families, "-apple-system").role, "generic");
+    }
+}
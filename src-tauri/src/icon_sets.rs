@@ -0,0 +1,289 @@
+use scraper::{Html, Selector};
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use url::Url;
+
+use crate::css;
+use crate::fetch;
+use crate::stylesheets::ParsedSheets;
+
+/// External sprite files fetched per page
+const MAX_SPRITES: usize = 10;
+const MAX_SPRITE_BYTES: usize = 5_000_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct SpriteSymbol {
+    pub(crate) id: String,
+    pub(crate) view_box: String,
+}
+
+/// An SVG sprite sheet: `<symbol>`s referenced with `<use href="sprite.svg#id">`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct SpriteSheet {
+    /// Sprite file URL, or the page URL for a sprite embedded in the document
+    pub(crate) url: String,
+    pub(crate) inline: bool,
+    pub(crate) symbols: Vec<SpriteSymbol>,
+    /// Symbol ids the page actually uses
+    pub(crate) referenced: Vec<String>,
+}
+
+/// An icon font detected from its stylesheet
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct IconFont {
+    /// Library name ("Font Awesome", "Bootstrap Icons") or "Custom icon font"
+    pub(crate) name: String,
+    /// Class prefix, e.g. "fa" for `.fa-home::before`
+    pub(crate) prefix: String,
+    /// Distinct glyphs the stylesheet defines (or, for ligature fonts, that the page uses)
+    pub(crate) glyph_count: usize,
+    pub(crate) font_family: String,
+    pub(crate) stylesheet: String,
+}
+
+/// The page's icon systems
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct IconInventory {
+    pub(crate) sprites: Vec<SpriteSheet>,
+    pub(crate) icon_fonts: Vec<IconFont>,
+}
+
+/// Well-known icon libraries by class prefix
+const KNOWN_PREFIXES: &[(&str, &str)] = &[
+    ("fa", "Font Awesome"), ("fas", "Font Awesome"), ("far", "Font Awesome"), ("fab", "Font Awesome"),
+    ("bi", "Bootstrap Icons"), ("glyphicon", "Glyphicons"), ("mdi", "Material Design Icons"),
+    ("la", "Line Awesome"), ("lab", "Line Awesome"), ("ion", "Ionicons"), ("ionicons", "Ionicons"),
+    ("ri", "Remix Icon"), ("ti", "Tabler Icons"), ("feather", "Feather"), ("icofont", "IcoFont"),
+    ("dashicons", "Dashicons"), ("typcn", "Typicons"), ("el", "Elusive Icons"), ("uil", "Unicons"),
+    ("bx", "Boxicons"), ("bxs", "Boxicons"), ("bxl", "Boxicons"), ("ph", "Phosphor Icons"),
+    ("lni", "LineIcons"), ("pe", "Pixeden Stroke"), ("icon", "Custom icon font"),
+];
+
+/// Well-known icon libraries by stylesheet file name
+const KNOWN_STYLESHEETS: &[(&str, &str)] = &[
+    ("font-awesome", "Font Awesome"), ("fontawesome", "Font Awesome"), ("bootstrap-icons", "Bootstrap Icons"),
+    ("materialdesignicons", "Material Design Icons"), ("line-awesome", "Line Awesome"), ("ionicons", "Ionicons"),
+    ("remixicon", "Remix Icon"), ("tabler-icons", "Tabler Icons"), ("boxicons", "Boxicons"), ("icomoon", "IcoMoon"),
+    ("fontello", "Fontello"), ("themify", "Themify Icons"), ("dashicons", "Dashicons"),
+];
+
+fn href_local(el: &scraper::node::Element) -> Option<&str> {
+    el.attrs.iter().find(|(name, _)| name.local.as_ref() == "href").map(|(_, v)| v.as_ref())
+}
+
+/// External sprite files referenced by `<use href="/icons.svg#cart">`, with the ids used from each
+pub(crate) fn sprite_references(document: &Html, base_url: &Url) -> Vec<(Url, Vec<String>)> {
+    let mut refs: Vec<(Url, Vec<String>)> = Vec::new();
+    let Ok(selector) = Selector::parse("use") else { return refs };
+    for el in document.select(&selector) {
+        let Some(href) = href_local(el.value()) else { continue };
+        let Some((file, id)) = href.split_once('#') else { continue };
+        if file.is_empty() {
+            continue;
+        }
+        let Ok(url) = base_url.join(file) else { continue };
+        match refs.iter_mut().find(|(u, _)| *u == url) {
+            Some((_, ids)) if !ids.contains(&id.to_string()) => ids.push(id.to_string()),
+            Some(_) => {}
+            None => refs.push((url, vec![id.to_string()])),
+        }
+    }
+    refs
+}
+
+fn symbols_of(document: &Html) -> Vec<SpriteSymbol> {
+    let mut symbols = Vec::new();
+    if let Ok(selector) = Selector::parse("symbol[id]") {
+        for el in document.select(&selector) {
+            let view_box = el
+                .value()
+                .attrs
+                .iter()
+                .find(|(name, _)| name.local.as_ref() == "viewBox")
+                .map(|(_, v)| v.to_string())
+                .unwrap_or_default();
+            symbols.push(SpriteSymbol { id: el.value().id().unwrap_or("").to_string(), view_box });
+        }
+    }
+    symbols
+}
+
+/// `<symbol>`s embedded in the page itself, with the local `<use href="#id">` references
+pub(crate) fn inline_sprite(document: &Html, base_url: &Url) -> Option<SpriteSheet> {
+    let symbols = symbols_of(document);
+    if symbols.is_empty() {
+        return None;
+    }
+    let mut referenced: Vec<String> = Vec::new();
+    if let Ok(selector) = Selector::parse("use") {
+        for el in document.select(&selector) {
+            if let Some(id) = href_local(el.value()).and_then(|h| h.strip_prefix('#')) {
+                if !referenced.iter().any(|r| r == id) {
+                    referenced.push(id.to_string());
+                }
+            }
+        }
+    }
+    Some(SpriteSheet { url: base_url.to_string(), inline: true, symbols, referenced })
+}
+
+async fn fetch_sprite(client: &reqwest::Client, url: &Url) -> Result<String, String> {
+    let mut response = client
+        .get(url.as_str())
+        .header("User-Agent", fetch::USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| format!("Sprite request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    if response.content_length().is_some_and(|len| len as usize > MAX_SPRITE_BYTES) {
+        return Err(format!("{} is larger than {} bytes", url, MAX_SPRITE_BYTES));
+    }
+    // Content-Length may be missing or wrong; stop reading at the cap either way
+    let (bytes, truncated) = fetch::read_capped(&mut response, MAX_SPRITE_BYTES).await?;
+    if truncated {
+        return Err(format!("{} is larger than {} bytes", url, MAX_SPRITE_BYTES));
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Fetch each referenced sprite file and list its symbols; files that fail to load are left out
+pub(crate) async fn fetch_sprites(client: &reqwest::Client, refs: Vec<(Url, Vec<String>)>) -> Vec<SpriteSheet> {
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, (url, referenced)) in refs.into_iter().take(MAX_SPRITES).enumerate() {
+        let client = client.clone();
+        tasks.spawn(async move {
+            let result = fetch_sprite(&client, &url).await;
+            (index, url, referenced, result)
+        });
+    }
+    let mut sheets: Vec<(usize, SpriteSheet)> = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((index, url, referenced, Ok(text))) = joined else { continue };
+        // Parsed synchronously; `Html` must not live across an await
+        let symbols = symbols_of(&Html::parse_document(&text));
+        sheets.push((index, SpriteSheet { url: url.to_string(), inline: false, symbols, referenced }));
+    }
+    sheets.sort_by_key(|(index, _)| *index);
+    sheets.into_iter().map(|(_, sheet)| sheet).collect()
+}
+
+/// Private Use Area code points, where icon fonts put their glyphs
+fn is_private_use(c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
+/// Class prefix of an icon selector: `.fa-home::before` → "fa", `.icon_cart:before` → "icon"
+fn class_prefix(selector: &str) -> Option<String> {
+    let class = selector.split('.').nth(1)?;
+    let class: String = class.chars().take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_').collect();
+    let prefix = class.split(['-', '_']).next()?.to_lowercase();
+    (!prefix.is_empty() && prefix.len() < class.len()).then_some(prefix)
+}
+
+fn unquote_family(value: &str) -> String {
+    value.split(',').next().unwrap_or("").trim().trim_matches(['"', '\'']).to_string()
+}
+
+/// Find icon fonts: `::before { content: "\f101" }` rules grouped by class prefix
+pub(crate) fn detect_icon_fonts(parsed: &ParsedSheets) -> Vec<IconFont> {
+    let mut fonts: Vec<IconFont> = Vec::new();
+
+    for (sheet, rules) in parsed.per_sheet() {
+        // prefix -> distinct glyph code points
+        let mut glyphs: BTreeMap<String, HashSet<char>> = BTreeMap::new();
+        let mut families: HashMap<String, String> = HashMap::new();
+        let mut face_families: Vec<String> = Vec::new();
+
+        for rule in rules {
+            if rule.selectors.starts_with("@font-face") {
+                if let Some(family) = rule.get("font-family") {
+                    face_families.push(unquote_family(family));
+                }
+                continue;
+            }
+            if let Some(family) = rule.get("font-family") {
+                for selector in rule.selectors.split(',') {
+                    let selector = selector.trim();
+                    // `.fa`, `[class^="fa-"]`, `[class*=" icon-"]`
+                    let prefix = selector
+                        .split(['"', '\''])
+                        .nth(1)
+                        .filter(|_| selector.contains("[class"))
+                        .map(|p| p.trim().trim_end_matches(['-', '_']).to_string())
+                        .or_else(|| selector.strip_prefix('.').filter(|s| s.chars().all(|c| c.is_alphanumeric())).map(|s| s.to_string()));
+                    if let Some(prefix) = prefix {
+                        families.entry(prefix.to_lowercase()).or_insert_with(|| unquote_family(family));
+                    }
+                }
+            }
+            let Some(content) = rule.get("content") else { continue };
+            let decoded = css::unescape_string(content);
+            let mut chars = decoded.chars();
+            let (Some(glyph), None) = (chars.next(), chars.next()) else { continue };
+            if !is_private_use(glyph) {
+                continue;
+            }
+            for selector in rule.selectors.split(',') {
+                if !(selector.contains(":before") || selector.contains(":after")) {
+                    continue;
+                }
+                if let Some(prefix) = class_prefix(selector) {
+                    glyphs.entry(prefix).or_default().insert(glyph);
+                }
+            }
+        }
+
+        let sheet_name = sheet.url.rsplit('/').next().unwrap_or("").to_lowercase();
+        for (prefix, set) in glyphs {
+            // A handful of PUA glyphs is usually a one-off decoration, not an icon system
+            if set.len() < 5 {
+                continue;
+            }
+            let font_family = families
+                .get(&prefix)
+                .cloned()
+                .or_else(|| face_families.iter().find(|f| f.to_lowercase().contains(&prefix)).cloned())
+                .or_else(|| (face_families.len() == 1).then(|| face_families[0].clone()))
+                .unwrap_or_default();
+            let name = KNOWN_STYLESHEETS
+                .iter()
+                .find(|(file, _)| !sheet.inline && sheet_name.contains(file))
+                .or_else(|| KNOWN_PREFIXES.iter().find(|(p, _)| *p == prefix))
+                .map(|(_, name)| name.to_string())
+                .or_else(|| KNOWN_STYLESHEETS.iter().find(|(file, _)| font_family.to_lowercase().replace(' ', "").contains(&file.replace('-', ""))).map(|(_, name)| name.to_string()))
+                .unwrap_or_else(|| "Custom icon font".to_string());
+            fonts.push(IconFont { name, prefix, glyph_count: set.len(), font_family, stylesheet: sheet.url.clone() });
+        }
+    }
+    fonts
+}
+
+/// Ligature icon fonts (Material Icons / Material Symbols): glyphs are words inside
+/// `<span class="material-symbols-outlined">home</span>`, so count the distinct names the page uses
+pub(crate) fn ligature_icon_fonts(document: &Html, base_url: &Url) -> Vec<IconFont> {
+    let mut used: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    let Ok(selector) = Selector::parse("[class*='material-icons'], [class*='material-symbols']") else { return Vec::new() };
+    for el in document.select(&selector) {
+        let Some(class) = el.value().classes().find(|c| c.starts_with("material-icons") || c.starts_with("material-symbols")) else { continue };
+        let name = el.text().collect::<String>().trim().to_string();
+        if !name.is_empty() && name.len() < 40 {
+            used.entry(class.to_string()).or_default().insert(name);
+        }
+    }
+    used.into_iter()
+        .map(|(class, names)| {
+            let family = class
+                .split('-')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map(|f| f.to_uppercase().collect::<String>() + chars.as_str()).unwrap_or_default()
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let name = if class.starts_with("material-symbols") { "Material Symbols" } else { "Material Icons" };
+            IconFont { name: name.to_string(), prefix: class, glyph_count: names.len(), font_family: family, stylesheet: base_url.to_string() }
+        })
+        .collect()
+}
@@ -0,0 +1,199 @@
+use scraper::{Html, Selector};
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use url::Url;
+
+use crate::image_header;
+use crate::manifest::{self, WebAppManifest};
+use crate::probe;
+
+/// Bytes fetched per icon — enough for the ICO directory and a PNG IHDR
+const ICON_PREFIX_BYTES: usize = 4096;
+
+/// A site icon (favicon, touch icon, pinned-tab icon or manifest icon)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct IconInfo {
+    pub(crate) src: String,
+    /// "icon", "shortcut icon", "apple-touch-icon", "mask-icon", "manifest" or "favicon.ico"
+    pub(crate) rel: String,
+    /// Raw `sizes` attribute, e.g. "32x32" or "any"
+    pub(crate) sizes: String,
+    pub(crate) declared_width: u32,
+    pub(crate) declared_height: u32,
+    /// Real dimensions read from the file header (0 when unknown or scalable)
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// "png", "ico", "svg", ... from the file header or content type
+    pub(crate) format: String,
+    /// `color` attribute of a Safari `mask-icon`
+    pub(crate) color: Option<String>,
+    /// Ranking score; the icon list is sorted by it, best first
+    pub(crate) score: u32,
+}
+
+impl IconInfo {
+    fn new(src: String, rel: &str, sizes: &str) -> Self {
+        let (declared_width, declared_height) = manifest::largest_size(sizes);
+        IconInfo {
+            src,
+            rel: rel.to_string(),
+            sizes: sizes.to_string(),
+            declared_width,
+            declared_height,
+            width: 0,
+            height: 0,
+            format: String::new(),
+            color: None,
+            score: 0,
+        }
+    }
+
+    /// Larger is better; SVG counts as 512px since it scales; monochrome mask icons rank last
+    fn compute_score(&self) -> u32 {
+        let size = if self.format == "svg" {
+            512
+        } else if self.width > 0 {
+            self.width.min(self.height)
+        } else {
+            self.declared_width.min(self.declared_height)
+        };
+        let rel_bonus = match self.rel.as_str() {
+            "apple-touch-icon" | "manifest" => 8,
+            "icon" | "shortcut icon" => 4,
+            _ => 0,
+        };
+        let score = size.min(1024) * 10 + rel_bonus;
+        if self.rel == "mask-icon" { score / 4 } else { score }
+    }
+}
+
+/// Collect icons declared with `<link rel=...>` in the page head
+pub(crate) fn collect_icon_links(document: &Html, base_url: &Url) -> Vec<IconInfo> {
+    let mut icons = Vec::new();
+    let Ok(selector) = Selector::parse("link[rel][href]") else { return icons };
+    for el in document.select(&selector) {
+        // `rel` is a token list: "alternate icon" and "icon shortcut" are icons too
+        let rel = el.value().attr("rel").unwrap_or("").to_lowercase();
+        let tokens: Vec<&str> = rel.split_whitespace().collect();
+        let has = |token: &str| tokens.contains(&token);
+        let rel = if has("mask-icon") {
+            "mask-icon"
+        } else if has("apple-touch-icon") || has("apple-touch-icon-precomposed") {
+            "apple-touch-icon"
+        } else if has("icon") {
+            if has("shortcut") { "shortcut icon" } else { "icon" }
+        } else {
+            continue;
+        };
+        let Some(href) = el.value().attr("href") else { continue };
+        let Ok(src) = base_url.join(href) else { continue };
+        let mut icon = IconInfo::new(src.to_string(), rel, el.value().attr("sizes").unwrap_or(""));
+        icon.format = match el.value().attr("type").unwrap_or("") {
+            "image/svg+xml" => "svg".to_string(),
+            "image/png" => "png".to_string(),
+            "image/x-icon" | "image/vnd.microsoft.icon" => "ico".to_string(),
+            _ => String::new(),
+        };
+        if rel == "mask-icon" {
+            icon.color = el.value().attr("color").map(|c| c.to_string());
+        }
+        icons.push(icon);
+    }
+    icons
+}
+
+/// Build the full icon set: page `<link>` icons, manifest icons and a `/favicon.ico` probe.
+/// Every icon is fetched far enough to read its real size; unreachable ones are dropped.
+pub(crate) async fn resolve_icons(
+    client: &reqwest::Client,
+    base_url: &Url,
+    link_icons: Vec<IconInfo>,
+    web_manifest: Option<&WebAppManifest>,
+) -> Vec<IconInfo> {
+    let mut candidates = link_icons;
+    if let Some(m) = web_manifest {
+        for icon in &m.icons {
+            candidates.push(IconInfo::new(icon.src.clone(), "manifest", &icon.sizes));
+        }
+    }
+    if let Ok(favicon) = base_url.join("/favicon.ico") {
+        candidates.push(IconInfo::new(favicon.to_string(), "favicon.ico", ""));
+    }
+
+    let mut seen: HashSet<String> = HashSet::new();
+    candidates.retain(|icon| seen.insert(icon.src.clone()));
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, icon) in candidates.iter().enumerate() {
+        let client = client.clone();
+        let src = icon.src.clone();
+        tasks.spawn(async move { (index, probe::fetch_prefix(&client, &src, ICON_PREFIX_BYTES).await) });
+    }
+
+    let mut keep = vec![false; candidates.len()];
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((index, result)) = joined else { continue };
+        let icon = &mut candidates[index];
+        match result {
+            Ok(prefix) => {
+                if let Some(header) = image_header::read_header(&prefix.bytes) {
+                    icon.width = header.width;
+                    icon.height = header.height;
+                    icon.format = header.format.to_string();
+                    keep[index] = true;
+                } else if prefix.content_type.contains("svg") {
+                    icon.format = "svg".to_string();
+                    keep[index] = true;
+                } else if prefix.content_type.starts_with("image/") {
+                    keep[index] = true;
+                }
+            }
+            Err(e) => println!("[icons] Dropping {}: {}", icon.src, e),
+        }
+    }
+
+    let mut icons: Vec<IconInfo> = candidates
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(icon, ok)| ok.then_some(icon))
+        .collect();
+    for icon in &mut icons {
+        icon.score = icon.compute_score();
+    }
+    icons.sort_by_key(|icon| std::cmp::Reverse(icon.score));
+    icons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rel_as_a_token_list() {
+        let document = Html::parse_document(
+            r##"<link rel="alternate icon" href="/favicon.ico">
+               <link rel="icon shortcut" href="/shortcut.png">
+               <link rel="Shortcut Icon" href="/legacy.ico">
+               <link rel="icon" type="image/svg+xml" href="/icon.svg">
+               <link rel="apple-touch-icon-precomposed" href="/apple.png">
+               <link rel="mask-icon" href="/mask.svg" color="#635bff">
+               <link rel="stylesheet" href="/site.css">
+               <link rel="preload icons" href="/sprite.svg">"##,
+        );
+        let icons = collect_icon_links(&document, &Url::parse("https://brand.example/").unwrap());
+        let rels: Vec<(&str, &str)> = icons.iter().map(|icon| (icon.src.trim_start_matches("https://brand.example"), icon.rel.as_str())).collect();
+        assert_eq!(
+            rels,
+            [
+                ("/favicon.ico", "icon"),
+                ("/shortcut.png", "shortcut icon"),
+                ("/legacy.ico", "shortcut icon"),
+                ("/icon.svg", "icon"),
+                ("/apple.png", "apple-touch-icon"),
+                ("/mask.svg", "mask-icon"),
+            ]
+        );
+        assert_eq!(icons[3].format, "svg");
+        assert_eq!(icons[5].color.as_deref(), Some("#635bff"));
+    }
+}
@@ -0,0 +1,262 @@
+use serde::{Serialize, Deserialize};
+
+use crate::color::Rgb;
+use crate::convert;
+use crate::fetch::{self, FetchError, UrlCache};
+use crate::image_header;
+use crate::palette::PaletteColor;
+use crate::svg::InlineSvg;
+use crate::svg_colors;
+use crate::ImageInfo;
+
+/// Image kinds whose colors are extracted
+const KINDS: &[&str] = &["logo", "hero"];
+/// Images analyzed per page
+const MAX_IMAGES: usize = 16;
+/// Larger files are skipped
+const MAX_IMAGE_BYTES: u64 = 8_000_000;
+/// Images fetched concurrently
+const CONCURRENCY: usize = 6;
+/// Images are shrunk to this longest side before quantizing
+const SAMPLE_SIDE: u32 = 128;
+/// Boxes produced by median cut before similar ones are merged
+const MAX_BOXES: usize = 12;
+/// Colors reported per image
+const MAX_COLORS: usize = 6;
+/// Colors covering less of the image than this are noise (anti-aliasing, JPEG artifacts)
+const MIN_SHARE: f32 = 0.02;
+/// Box averages closer than this (RGB distance) are one color
+const MERGE_DISTANCE: f32 = 24.0;
+/// Pixels at least this transparent are ignored
+const MIN_ALPHA: u8 = 128;
+
+/// One dominant color of an image
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct DominantColor {
+    pub(crate) hex: String,
+    pub(crate) rgb: Rgb,
+    /// Share of the image's opaque pixels, 0–1
+    pub(crate) share: f32,
+}
+
+/// Extracted colors shared across analyses. `None` records an image that answered 4xx or could not be decoded.
+pub(crate) type ColorCache = UrlCache<Option<Vec<DominantColor>>>;
+
+/// Pixels in one median-cut box
+struct ColorBox(Vec<[u8; 3]>);
+
+impl ColorBox {
+    /// Widest channel and its range
+    fn widest(&self) -> (usize, u8) {
+        (0..3)
+            .map(|c| {
+                let (lo, hi) = self.0.iter().fold((255u8, 0u8), |(lo, hi), p| (lo.min(p[c]), hi.max(p[c])));
+                (c, hi.saturating_sub(lo))
+            })
+            .max_by_key(|(_, range)| *range)
+            .unwrap_or((0, 0))
+    }
+
+    fn average(&self) -> [f32; 3] {
+        let n = self.0.len().max(1) as f32;
+        let mut sum = [0f32; 3];
+        for p in &self.0 {
+            for c in 0..3 {
+                sum[c] += p[c] as f32;
+            }
+        }
+        sum.map(|s| s / n)
+    }
+}
+
+/// Median cut: repeatedly split the box with the widest channel range at its median
+fn median_cut(pixels: Vec<[u8; 3]>) -> Vec<([f32; 3], usize)> {
+    let mut boxes = vec![ColorBox(pixels)];
+    while boxes.len() < MAX_BOXES {
+        let Some((index, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.0.len() > 1)
+            .map(|(i, b)| (i, b.widest()))
+            .filter(|(_, (_, range))| *range > 0)
+            .max_by_key(|(i, (_, range))| *range as usize * boxes[*i].0.len())
+            .map(|(i, (channel, _))| (i, channel))
+        else {
+            break;
+        };
+        let mut pixels = std::mem::take(&mut boxes[index].0);
+        pixels.sort_unstable_by_key(|p| p[channel]);
+        let upper = pixels.split_off(pixels.len() / 2);
+        boxes[index].0 = pixels;
+        boxes.push(ColorBox(upper));
+    }
+    boxes.iter().filter(|b| !b.0.is_empty()).map(|b| (b.average(), b.0.len())).collect()
+}
+
+/// Dominant colors of an image, most common first. Transparent pixels are ignored, so a logo
+/// on a transparent canvas reports its artwork rather than the background.
+pub(crate) fn dominant_colors(bytes: &[u8]) -> Result<Vec<DominantColor>, String> {
+    let rgba = convert::decode_rgba(bytes, SAMPLE_SIDE)?;
+    let pixels: Vec<[u8; 3]> = rgba.pixels().filter(|p| p[3] >= MIN_ALPHA).map(|p| [p[0], p[1], p[2]]).collect();
+    let total = pixels.len();
+    if total == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Median cut over-splits large flat areas; merge boxes that average to nearly the same color
+    let mut merged: Vec<([f32; 3], usize)> = Vec::new();
+    let mut boxes = median_cut(pixels);
+    boxes.sort_by_key(|b| std::cmp::Reverse(b.1));
+    for (color, count) in boxes {
+        let distance = |other: &[f32; 3]| (0..3).map(|c| (color[c] - other[c]).powi(2)).sum::<f32>().sqrt();
+        match merged.iter_mut().find(|(other, _)| distance(other) < MERGE_DISTANCE) {
+            Some((other, other_count)) => {
+                let weight = *other_count as f32 / (*other_count + count) as f32;
+                for c in 0..3 {
+                    other[c] = other[c] * weight + color[c] * (1.0 - weight);
+                }
+                *other_count += count;
+            }
+            None => merged.push((color, count)),
+        }
+    }
+    merged.sort_by_key(|m| std::cmp::Reverse(m.1));
+
+    Ok(merged
+        .into_iter()
+        .map(|(color, count)| (Rgb::new(color[0].round() as u8, color[1].round() as u8, color[2].round() as u8), count as f32 / total as f32))
+        .filter(|(_, share)| *share >= MIN_SHARE)
+        .take(MAX_COLORS)
+        .map(|(rgb, share)| DominantColor { hex: rgb.to_hex(), rgb, share })
+        .collect())
+}
+
+/// SVGs are read as vectors (exact paint colors by area), everything else is quantized
+fn colors_from_bytes(bytes: &[u8], url: &str) -> Result<Vec<DominantColor>, String> {
+    if image_header::read_header(bytes).is_some_and(|header| header.format == "svg") {
+        return Ok(svg_colors::svg_colors(bytes, url)
+            .into_iter()
+            .filter(|color| color.share >= MIN_SHARE)
+            .take(MAX_COLORS)
+            .map(|color| DominantColor { hex: color.hex, rgb: color.rgb, share: color.share })
+            .collect());
+    }
+    dominant_colors(bytes)
+}
+
+async fn colors_for_url(client: reqwest::Client, url: String) -> Result<Vec<DominantColor>, FetchError> {
+    let result = match fetch::fetch_capped(&client, &url, MAX_IMAGE_BYTES as usize, None).await {
+        // Decoding and quantizing are CPU-bound; keep them off the async workers
+        Ok(bytes) => {
+            let url = url.clone();
+            tokio::task::spawn_blocking(move || colors_from_bytes(&bytes, &url).map_err(FetchError::permanent))
+                .await
+                .unwrap_or_else(|e| Err(FetchError::transient(format!("Task failed: {}", e))))
+        }
+        Err(e) => Err(e),
+    };
+    result.inspect_err(|e| println!("[image_colors] No colors for {}: {}", url, e))
+}
+
+/// Fill `dominant_colors` on the page's logos and hero images (run after classification)
+pub(crate) async fn extract_image_colors(images: &mut [ImageInfo], cache: &ColorCache) {
+    let targets: Vec<usize> = images
+        .iter()
+        .enumerate()
+        .filter(|(_, image)| KINDS.contains(&image.kind.as_str()))
+        .filter(|(_, image)| image.src.starts_with("http://") || image.src.starts_with("https://"))
+        .filter(|(_, image)| image.file_size.is_none_or(|size| size <= MAX_IMAGE_BYTES))
+        .map(|(index, _)| index)
+        .take(MAX_IMAGES)
+        .collect();
+
+    let urls = targets.iter().map(|index| images[*index].src.clone()).collect();
+    let mut colors = fetch::fetch_each(urls, cache, CONCURRENCY, colors_for_url).await;
+    for index in targets {
+        if let Some(Some(found)) = colors.remove(&images[index].src) {
+            images[index].dominant_colors = found;
+        }
+    }
+}
+
+/// Palette entries from logo artwork, each logo's colors in order of coverage. Vector colors
+/// (logo-marked inline SVGs and SVG logo files) are tagged "svg" and come before raster ones ("logo").
+pub(crate) fn logo_palette(images: &[ImageInfo], inline_svgs: &[InlineSvg]) -> Vec<PaletteColor> {
+    let inline = inline_svgs
+        .iter()
+        .filter(|svg| svg.logo)
+        .flat_map(|svg| svg.colors.iter().map(|color| PaletteColor::new(color.rgb, "svg", false).with_asset(&color.asset)));
+    let logos = || images.iter().filter(|image| image.kind == "logo");
+    let files = |vector: bool| {
+        logos()
+            .filter(move |image| (image.format == "svg") == vector)
+            .flat_map(move |image| {
+                let source = if vector { "svg" } else { "logo" };
+                image.dominant_colors.iter().map(move |color| PaletteColor::new(color.rgb, source, false).with_asset(&image.src))
+            })
+    };
+    inline.chain(files(true)).chain(files(false)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbaImage};
+
+    fn png(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 4]) -> Vec<u8> {
+        let image = RgbaImage::from_fn(width, height, |x, y| image::Rgba(pixel(x, y)));
+        let mut out = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut out, ImageFormat::Png).unwrap();
+        out.into_inner()
+    }
+
+    fn hexes(colors: &[DominantColor]) -> Vec<&str> {
+        colors.iter().map(|color| color.hex.as_str()).collect()
+    }
+
+    #[test]
+    fn flat_image_has_one_color() {
+        let colors = dominant_colors(&png(20, 20, |_, _| [255, 0, 0, 255])).unwrap();
+        assert_eq!(hexes(&colors), ["#ff0000"]);
+        assert_eq!(colors[0].share, 1.0);
+    }
+
+    #[test]
+    fn skips_transparent_pixels() {
+        let colors = dominant_colors(&png(10, 10, |x, _| if x < 5 { [0, 0, 255, 255] } else { [255, 0, 0, MIN_ALPHA - 1] })).unwrap();
+        assert_eq!(hexes(&colors), ["#0000ff"]);
+        assert_eq!(colors[0].share, 1.0);
+        assert!(dominant_colors(&png(4, 4, |_, _| [0, 0, 0, 0])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn orders_colors_by_share() {
+        let colors = dominant_colors(&png(10, 10, |_, y| match y {
+            0..=5 => [255, 0, 0, 255],
+            6..=8 => [0, 160, 0, 255],
+            _ => [0, 0, 255, 255],
+        }))
+        .unwrap();
+        assert_eq!(hexes(&colors), ["#ff0000", "#00a000", "#0000ff"]);
+        let shares: Vec<f32> = colors.iter().map(|color| (color.share * 100.0).round() / 100.0).collect();
+        assert_eq!(shares, [0.6, 0.3, 0.1]);
+    }
+
+    #[test]
+    fn drops_colors_under_the_minimum_share() {
+        // 1% black specks on white
+        let colors = dominant_colors(&png(100, 100, |x, _| if x == 0 { [0, 0, 0, 255] } else { [255, 255, 255, 255] })).unwrap();
+        assert_eq!(hexes(&colors), ["#ffffff"]);
+    }
+
+    #[test]
+    fn merges_nearby_boxes() {
+        // (200, 0, 0) and (210, 10, 0) are about 14 apart, under the merge distance
+        let colors = dominant_colors(&png(10, 10, |x, _| if x < 5 { [200, 0, 0, 255] } else { [210, 10, 0, 255] })).unwrap();
+        assert_eq!(hexes(&colors), ["#cd0500"]);
+        assert_eq!(colors[0].share, 1.0);
+        // 40 apart stays two colors
+        let split = dominant_colors(&png(10, 10, |x, _| if x < 5 { [200, 0, 0, 255] } else { [240, 0, 0, 255] })).unwrap();
+        assert_eq!(split.len(), 2);
+    }
+}
@@ -0,0 +1,294 @@
+use regex_lite::Regex;
+use std::sync::OnceLock;
+
+/// Intrinsic size read from the first bytes of an image file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ImageHeader {
+    pub(crate) format: &'static str,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+fn be_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    bytes.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn be_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    bytes.get(at..at + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    bytes.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn le_u24(bytes: &[u8], at: usize) -> Option<u32> {
+    bytes.get(at..at + 3).map(|b| u32::from_le_bytes([b[0], b[1], b[2], 0]))
+}
+
+/// PNG: the IHDR chunk always comes first, width/height at bytes 16..24
+fn png(bytes: &[u8]) -> Option<ImageHeader> {
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") || bytes.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    Some(ImageHeader { format: "png", width: be_u32(bytes, 16)?, height: be_u32(bytes, 20)? })
+}
+
+/// ICO / CUR: a directory of images; report the largest. A stored size of 0 means 256.
+/// Entries that embed a PNG store 0 as well, so prefer the PNG's own IHDR when it's in range.
+fn ico(bytes: &[u8]) -> Option<ImageHeader> {
+    if bytes.get(0..4)? != [0, 0, 1, 0] && bytes.get(0..4)? != [0, 0, 2, 0] {
+        return None;
+    }
+    let count = le_u16(bytes, 4)? as usize;
+    if count == 0 {
+        return None;
+    }
+    let mut best: Option<(u32, u32)> = None;
+    for i in 0..count {
+        let entry = 6 + i * 16;
+        let Some(dir) = bytes.get(entry..entry + 16) else { break };
+        let mut width = if dir[0] == 0 { 256 } else { dir[0] as u32 };
+        let mut height = if dir[1] == 0 { 256 } else { dir[1] as u32 };
+        let offset = u32::from_le_bytes([dir[12], dir[13], dir[14], dir[15]]) as usize;
+        if let Some(embedded) = bytes.get(offset..).and_then(png) {
+            width = embedded.width;
+            height = embedded.height;
+        }
+        if best.is_none_or(|(w, h)| width as u64 * height as u64 > w as u64 * h as u64) {
+            best = Some((width, height));
+        }
+    }
+    best.map(|(width, height)| ImageHeader { format: "ico", width, height })
+}
+
+/// JPEG: walk the marker segments up to the first start-of-frame (SOF0–SOF15, minus DHT/DAC/JPG)
+fn jpeg(bytes: &[u8]) -> Option<ImageHeader> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut at = 2;
+    loop {
+        if *bytes.get(at)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(at + 1)?;
+        match marker {
+            // Fill bytes
+            0xFF => at += 1,
+            // Standalone markers without a length
+            0x01 | 0xD0..=0xD8 => at += 2,
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                return Some(ImageHeader {
+                    format: "jpeg",
+                    width: be_u16(bytes, at + 7)? as u32,
+                    height: be_u16(bytes, at + 5)? as u32,
+                });
+            }
+            _ => at += 2 + be_u16(bytes, at + 2)? as usize,
+        }
+    }
+}
+
+/// GIF: logical screen size right after the signature
+fn gif(bytes: &[u8]) -> Option<ImageHeader> {
+    if !bytes.starts_with(b"GIF87a") && !bytes.starts_with(b"GIF89a") {
+        return None;
+    }
+    Some(ImageHeader { format: "gif", width: le_u16(bytes, 6)? as u32, height: le_u16(bytes, 8)? as u32 })
+}
+
+/// WebP: lossy (`VP8 `), lossless (`VP8L`) and extended (`VP8X`) bitstreams store the size differently
+fn webp(bytes: &[u8]) -> Option<ImageHeader> {
+    if bytes.get(0..4)? != b"RIFF" || bytes.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    let (width, height) = match bytes.get(12..16)? {
+        b"VP8 " => ((le_u16(bytes, 26)? & 0x3FFF) as u32, (le_u16(bytes, 28)? & 0x3FFF) as u32),
+        b"VP8L" => {
+            let b = bytes.get(21..25)?;
+            let width = 1 + (b[0] as u32 | ((b[1] as u32 & 0x3F) << 8));
+            let height = 1 + ((b[1] as u32 >> 6) | ((b[2] as u32) << 2) | ((b[3] as u32 & 0x0F) << 10));
+            (width, height)
+        }
+        b"VP8X" => (1 + le_u24(bytes, 24)?, 1 + le_u24(bytes, 27)?),
+        _ => return None,
+    };
+    Some(ImageHeader { format: "webp", width, height })
+}
+
+/// AVIF: an ISO-BMFF `ftyp` with an AVIF brand; the size lives in the first `ispe` property box
+fn avif(bytes: &[u8]) -> Option<ImageHeader> {
+    if bytes.get(4..8)? != b"ftyp" {
+        return None;
+    }
+    let ftyp_len = (be_u32(bytes, 0)? as usize).min(bytes.len());
+    let brands = bytes.get(8..ftyp_len)?;
+    if !brands.chunks(4).any(|brand| brand == b"avif" || brand == b"avis") {
+        return None;
+    }
+    let ispe = bytes.windows(4).position(|w| w == b"ispe")?;
+    // Box type, then 4 bytes of version/flags, then width and height
+    Some(ImageHeader { format: "avif", width: be_u32(bytes, ispe + 8)?, height: be_u32(bytes, ispe + 12)? })
+}
+
+/// A numeric SVG length in user units; percentages and relative units are unknown
+fn svg_length(value: &str) -> Option<u32> {
+    let value = value.trim().trim_end_matches("px");
+    value.parse::<f32>().ok().filter(|v| *v > 0.0).map(|v| v.round() as u32)
+}
+
+/// Attributes of the root `<svg>` tag
+fn width_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"\swidth\s*=\s*["']([^"']*)["']"#).ok()).as_ref()
+}
+
+fn height_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"\sheight\s*=\s*["']([^"']*)["']"#).ok()).as_ref()
+}
+
+fn view_box_re() -> Option<&'static Regex> {
+    static RE: OnceLock<Option<Regex>> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"\sviewBox\s*=\s*["']([^"']*)["']"#).ok()).as_ref()
+}
+
+/// SVG: `width`/`height` on the root element, falling back to the `viewBox` size.
+/// Scalable files without either report 0×0.
+fn svg(bytes: &[u8]) -> Option<ImageHeader> {
+    let text = String::from_utf8_lossy(bytes);
+    let start = text.find("<svg")?;
+    let head = text.get(..start)?.trim_start_matches('\u{feff}').trim_start();
+    if !(head.is_empty() || head.starts_with("<?xml") || head.starts_with("<!--") || head.starts_with("<!DOCTYPE")) {
+        return None;
+    }
+    let tag = &text[start..start + text[start..].find('>')?];
+    let attr = |re: Option<&Regex>| -> Option<String> { re?.captures(tag).map(|c| c[1].to_string()) };
+    let mut width = attr(width_re()).and_then(|w| svg_length(&w)).unwrap_or(0);
+    let mut height = attr(height_re()).and_then(|h| svg_length(&h)).unwrap_or(0);
+    if width == 0 || height == 0 {
+        let view_box: Vec<f32> = attr(view_box_re())
+            .unwrap_or_default()
+            .split([' ', ','])
+            .filter_map(|v| v.parse().ok())
+            .collect();
+        if view_box.len() == 4 {
+            width = view_box[2].round().max(0.0) as u32;
+            height = view_box[3].round().max(0.0) as u32;
+        }
+    }
+    Some(ImageHeader { format: "svg", width, height })
+}
+
+/// Detect the format from magic bytes and read the intrinsic dimensions
+pub(crate) fn read_header(bytes: &[u8]) -> Option<ImageHeader> {
+    png(bytes)
+        .or_else(|| jpeg(bytes))
+        .or_else(|| gif(bytes))
+        .or_else(|| webp(bytes))
+        .or_else(|| avif(bytes))
+        .or_else(|| ico(bytes))
+        .or_else(|| svg(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(format: &'static str, width: u32, height: u32) -> Option<ImageHeader> {
+        Some(ImageHeader { format, width, height })
+    }
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn reads_png_and_gif() {
+        assert_eq!(read_header(&png_bytes(640, 480)), header("png", 640, 480));
+        assert_eq!(read_header(b"GIF89a\x20\x03\x58\x02"), header("gif", 800, 600));
+        assert_eq!(read_header(b"GIF89a\x20"), None);
+    }
+
+    #[test]
+    fn reads_jpeg_after_app_segments() {
+        let mut bytes = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
+        bytes.extend([0; 14]);
+        bytes.extend([0xFF, 0xFF, 0xC2, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80]);
+        assert_eq!(read_header(&bytes), header("jpeg", 640, 480));
+        // A Huffman table segment isn't a frame header
+        let dht = [0xFF, 0xD8, 0xFF, 0xC4, 0x00, 0x02, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00, 0x10, 0x00, 0x20];
+        assert_eq!(read_header(&dht), header("jpeg", 32, 16));
+    }
+
+    #[test]
+    fn reads_webp_variants() {
+        let riff = |chunk: &[u8; 4], payload: &[u8]| {
+            let mut bytes = b"RIFF\0\0\0\0WEBP".to_vec();
+            bytes.extend(chunk);
+            bytes.extend([0; 4]);
+            bytes.extend(payload);
+            bytes
+        };
+        let mut lossy = vec![0; 3];
+        lossy.extend([0x9D, 0x01, 0x2A]);
+        lossy.extend([0x01, 0x00, 0x80, 0xC0]);
+        // The top two bits of each dimension are the scaling factor
+        assert_eq!(read_header(&riff(b"VP8 ", &lossy)), header("webp", 1, 128));
+
+        let bits = 99u32 | (49 << 14);
+        let mut lossless = vec![0x2F];
+        lossless.extend(bits.to_le_bytes());
+        assert_eq!(read_header(&riff(b"VP8L", &lossless)), header("webp", 100, 50));
+
+        let mut extended = vec![0; 4];
+        extended.extend([0xFF, 0x03, 0x00, 0xFF, 0x01, 0x00]);
+        assert_eq!(read_header(&riff(b"VP8X", &extended)), header("webp", 1024, 512));
+    }
+
+    #[test]
+    fn reads_avif_ispe() {
+        let mut bytes = vec![0, 0, 0, 16];
+        bytes.extend(b"ftypavif\0\0\0\0");
+        bytes.extend(b"\0\0\0\x14ispe\0\0\0\0");
+        bytes.extend(1920u32.to_be_bytes());
+        bytes.extend(1080u32.to_be_bytes());
+        assert_eq!(read_header(&bytes), header("avif", 1920, 1080));
+
+        let mut heic = bytes.clone();
+        heic[8..12].copy_from_slice(b"heic");
+        assert_eq!(read_header(&heic), None);
+    }
+
+    #[test]
+    fn reads_largest_ico_entry() {
+        let mut bytes = vec![0, 0, 1, 0, 3, 0];
+        let entry = |w: u8, h: u8, offset: u32| {
+            let mut dir = vec![w, h, 0, 0, 1, 0, 32, 0, 0, 0, 0, 0];
+            dir.extend(offset.to_le_bytes());
+            dir
+        };
+        bytes.extend(entry(16, 16, 1000));
+        bytes.extend(entry(48, 48, 1000));
+        // Embedded PNG: the directory says 256, the IHDR knows better
+        bytes.extend(entry(0, 0, 54));
+        bytes.extend(png_bytes(512, 512));
+        assert_eq!(read_header(&bytes), header("ico", 512, 512));
+
+        let mut no_png = bytes[..54].to_vec();
+        no_png[4] = 2;
+        assert_eq!(read_header(&no_png), header("ico", 48, 48));
+    }
+
+    #[test]
+    fn reads_svg_size() {
+        let svg = |text: &str| read_header(text.as_bytes());
+        assert_eq!(svg(r#"<svg xmlns="http://www.w3.org/2000/svg" width="120px" height='40'>"#), header("svg", 120, 40));
+        assert_eq!(svg(r#"<?xml version="1.0"?><svg width="100%" viewBox="0 0 24.4 12">"#), header("svg", 24, 12));
+        assert_eq!(svg("\u{feff}<!-- logo --><svg><path/></svg>"), header("svg", 0, 0));
+        assert_eq!(svg("<html><body><svg width='10' height='10'></svg>"), None);
+    }
+}
@@ -0,0 +1,64 @@
+use scraper::{Html, Selector};
+use serde_json::{Map, Value};
+
+/// Parse every `<script type="application/ld+json">` block in the document.
+/// Blocks that fail to parse (trailing commas, HTML comments, etc.) are skipped.
+pub(crate) fn blocks(document: &Html) -> Vec<Value> {
+    let mut out = Vec::new();
+    if let Ok(selector) = Selector::parse("script[type='application/ld+json']") {
+        for el in document.select(&selector) {
+            let text = el.text().collect::<String>();
+            let trimmed = text.trim().trim_start_matches("<!--").trim_end_matches("-->").trim();
+            if let Ok(value) = serde_json::from_str::<Value>(trimmed) {
+                out.push(value);
+            }
+        }
+    }
+    out
+}
+
+/// Visit every JSON object in a JSON-LD tree, including nested `@graph` entries.
+pub(crate) fn walk_objects<'a>(value: &'a Value, visit: &mut dyn FnMut(&'a Map<String, Value>)) {
+    match value {
+        Value::Object(map) => {
+            visit(map);
+            for child in map.values() {
+                walk_objects(child, visit);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                walk_objects(item, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect the string values of a property that may be a string, an array of strings,
+/// or an object carrying `url` / `@id` (e.g. `"logo": {"@type": "ImageObject", "url": ...}`).
+pub(crate) fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().flat_map(strings).collect(),
+        Value::Object(map) => map
+            .get("url")
+            .or_else(|| map.get("contentUrl"))
+            .or_else(|| map.get("@id"))
+            .map(strings)
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a JSON-LD object's `@type` matches one of the given types.
+pub(crate) fn has_type(map: &Map<String, Value>, types: &[&str]) -> bool {
+    match map.get("@type") {
+        Some(Value::String(t)) => types.contains(&t.as_str()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|t| t.as_str())
+            .any(|t| types.contains(&t)),
+        _ => false,
+    }
+}
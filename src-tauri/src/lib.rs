@@ -1,10 +1,62 @@
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, State};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, State};
 use serde::{Serialize, Deserialize};
-use std::sync::{Arc, Mutex};
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
 use tokio::sync::oneshot;
 use scraper::{Html, Selector};
 use url::Url;
 
+mod assets;
+mod cdn;
+mod color;
+mod color_specs;
+mod contacts;
+mod convert;
+mod css;
+mod dedupe;
+mod download;
+mod fetch;
+mod filenames;
+mod font_files;
+mod fonts;
+mod icon_sets;
+mod icons;
+mod image_colors;
+mod image_header;
+mod jsonld;
+mod logo;
+mod manifest;
+mod palette;
+mod probe;
+mod scales;
+mod settings;
+mod social;
+mod srcset;
+mod stylesheets;
+mod svg;
+mod svg_colors;
+mod themes;
+mod typography;
+
+use assets::AssetRequest;
+use color_specs::ColorSpec;
+use contacts::ContactDetails;
+use convert::ConvertOptions;
+use fonts::{FontFamily, FontSource, FontStack};
+use icon_sets::IconInventory;
+use image_colors::DominantColor;
+use icons::IconInfo;
+use logo::ImageHints;
+use manifest::WebAppManifest;
+use palette::{CvdConflict, PaletteColor};
+use scales::{ColorScale, ScaleTokens};
+use social::SocialProfile;
+use srcset::ImageVariant;
+use stylesheets::Stylesheet;
+use svg::InlineSvg;
+use themes::{ColorTokens, ThemeColor};
+use typography::{BrowserTextStyle, TextStyle, Typography};
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct PageMetadata {
     title: String,
@@ -12,12 +64,41 @@ struct PageMetadata {
     favicon: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 struct ImageInfo {
     src: String,
     alt: String,
     width: u32,
     height: u32,
+    /// Where the image was declared when it isn't a plain page element (e.g. "manifest")
+    #[serde(default)]
+    source: String,
+    /// "logo", "icon", "hero", "product", "social-preview" or "background"
+    #[serde(default)]
+    kind: String,
+    /// Logo likelihood, 0–100
+    #[serde(default)]
+    score: u32,
+    /// "png", "jpeg", "webp", "svg", ... read from the file header
+    #[serde(default)]
+    format: String,
+    #[serde(default)]
+    content_type: String,
+    /// Size in bytes, when the server reports it
+    #[serde(default)]
+    file_size: Option<u64>,
+    /// Every responsive candidate (`srcset`, `<picture><source>`); `src` is the largest of them
+    #[serde(default)]
+    variants: Vec<ImageVariant>,
+    /// Most common colors of logos and hero images, largest share first
+    #[serde(default)]
+    dominant_colors: Vec<DominantColor>,
+}
+
+impl ImageInfo {
+    fn new(src: String, alt: String, width: u32, height: u32) -> Self {
+        ImageInfo { src, alt, width, height, ..Default::default() }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -36,20 +117,120 @@ struct BrowserAnalysis {
     #[serde(default)]
     text_content: Vec<TextBlock>,
     metadata: PageMetadata,
+    /// Rendered DOM (`document.documentElement.outerHTML`) so the Rust extractors can see JS-rendered content
+    #[serde(default)]
+    html: String,
+    /// Computed text styles of body, headings, buttons and links
+    #[serde(default)]
+    typography: Vec<BrowserTextStyle>,
+    /// Distinct computed `font-family` stacks, most used first
+    #[serde(default)]
+    font_stacks: Vec<FontStack>,
 }
 
 /// The full analysis result sent to the frontend (browser data + server-side scrape)
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct AnalysisResult {
     colors: Vec<String>,
+    /// Declared brand colors, then SVG and raster logo colors, then computed styles, each tagged with its source
+    palette: Vec<PaletteColor>,
     fonts: Vec<String>,
     images: Vec<ImageInfo>,
     text_content: Vec<TextBlock>,
     metadata: PageMetadata,
+    social_profiles: Vec<SocialProfile>,
+    contacts: ContactDetails,
+    manifest: Option<WebAppManifest>,
+    /// Every site icon, ranked best first; the first one also becomes `metadata.favicon`
+    icons: Vec<IconInfo>,
+    /// Inline `<svg>` marks with no URL of their own, sanitized and ready to save
+    inline_svgs: Vec<InlineSvg>,
+    /// SVG sprite sheets and icon fonts the site draws its UI icons from
+    icon_sets: IconInventory,
+    /// Colors spelled out in page text (hex, RGB, CMYK, Pantone), e.g. on a brand-guidelines page
+    color_specs: Vec<ColorSpec>,
+    /// 50–950 tint/shade ramps for the palette's primary, secondary and accent colors
+    color_scales: Vec<ColorScale>,
+    /// The ramps as CSS custom properties and a Tailwind colors object
+    scale_tokens: ScaleTokens,
+    /// Palette pairs that become hard to tell apart with protanopia, deuteranopia, tritanopia or achromatopsia
+    cvd_conflicts: Vec<CvdConflict>,
+    /// Dark-scheme colors: dark theme-color metas, then colors painted by `prefers-color-scheme: dark`,
+    /// `.dark` and `[data-theme=dark]` rules
+    dark_palette: Vec<PaletteColor>,
+    /// Color custom properties for the light and dark schemes
+    color_tokens: ColorTokens,
+    /// Family, size, weight, line height, letter spacing and case of body, headings, buttons and links
+    typography: Typography,
+    /// Every family in the page's font stacks: primary faces, fallbacks and generics, with their provider
+    font_families: Vec<FontFamily>,
+}
+
+/// Everything the server-side scraper pulls out of the raw HTML
+#[derive(Default)]
+struct ServerScrape {
+    images: Vec<ImageInfo>,
+    text_content: Vec<TextBlock>,
+    social_profiles: Vec<SocialProfile>,
+    contacts: ContactDetails,
+    manifest: Option<WebAppManifest>,
+    icons: Vec<IconInfo>,
+    image_hints: ImageHints,
+    inline_svgs: Vec<InlineSvg>,
+    /// Inline `<style>` blocks and fetched stylesheets in cascade order (imports before their importer)
+    stylesheets: Vec<Stylesheet>,
+    icon_sets: IconInventory,
+    /// `<meta name="theme-color">` values, with the scheme their `media` targets
+    theme_colors: Vec<ThemeColor>,
+    color_tokens: ColorTokens,
+    dark_palette: Vec<PaletteColor>,
+    /// Text styles declared for body, headings, buttons and links
+    typography: Vec<TextStyle>,
+    /// `@font-face` rules and provider stylesheet requests
+    font_sources: Vec<FontSource>,
+    /// `font-family` values declared in the stylesheets
+    font_stacks: Vec<FontStack>,
+}
+
+/// Optional switches for `analyze_page`; every field has a default so the frontend can omit them
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+struct AnalyzeOptions {
+    /// Group images by perceptual hash, confirming URL-pattern matches (`hero-small.jpg` and
+    /// `hero.jpg`) and catching copies under unrelated URLs. Off by default: it downloads up to
+    /// `dedupe::MAX_HASHES` images (or their smallest srcset candidates), and URL patterns alone
+    /// decide without it.
+    content_dedupe: bool,
 }
 
 struct AppState {
     pending_analysis: Arc<Mutex<Option<oneshot::Sender<Result<BrowserAnalysis, String>>>>>,
+    probe_cache: probe::ProbeCache,
+    hash_cache: dedupe::HashCache,
+    color_cache: image_colors::ColorCache,
+    font_cache: font_files::FontCache,
+    /// Download folder and spot colors, saved to `settings_path` whenever they change
+    settings: Mutex<settings::Settings>,
+    settings_path: std::path::PathBuf,
+}
+
+impl AppState {
+    fn destination_dir(&self) -> std::path::PathBuf {
+        self.settings
+            .lock()
+            .unwrap()
+            .download_dir
+            .clone()
+            .or_else(dirs::download_dir)
+            .unwrap_or_else(|| std::path::PathBuf::from("."))
+    }
+
+    /// Apply `change` to the settings and save them
+    fn update_settings(&self, change: impl FnOnce(&mut settings::Settings)) -> Result<(), String> {
+        let mut current = self.settings.lock().unwrap();
+        change(&mut current);
+        settings::save(&self.settings_path, &current)
+    }
 }
 
 #[tauri::command]
@@ -70,7 +251,7 @@ async fn complete_analysis(state: State<'_, AppState>, data: BrowserAnalysis) ->
 
 /// Server-side scraper: fetches HTML via HTTP and parses text + images
 /// This replicates the Python webscrap.py approach using reqwest + scraper (BeautifulSoup equivalent)
-async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBlock>), String> {
+async fn server_side_scrape(url_str: &str) -> Result<ServerScrape, String> {
     println!("[server-side scrape] Fetching URL: {}", url_str);
 
     let base_url = Url::parse(url_str).map_err(|e| format!("Invalid URL: {}", e))?;
@@ -84,7 +265,7 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
 
     let response = client
         .get(url_str)
-        .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36")
+        .header("User-Agent", fetch::USER_AGENT)
         .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8")
         .header("Accept-Language", "en-US,en;q=0.9")
         .header("Accept-Encoding", "gzip, deflate, br")
@@ -103,7 +284,67 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
         .await
         .map_err(|e| format!("Failed to read response: {}", e))?;
 
-    let document = Html::parse_document(&html_text);
+    let (mut scrape, linked) = parse_server_html(&html_text, &base_url);
+
+    // ── Web App Manifest (<link rel="manifest">) ──
+    scrape.manifest = match linked.manifest {
+        Some(manifest_url) => match manifest::fetch_manifest(&client, &manifest_url).await {
+            Ok(parsed) => {
+                println!("[server-side scrape] Manifest '{}' with {} icons", parsed.name, parsed.icons.len());
+                Some(parsed)
+            }
+            Err(e) => {
+                println!("[server-side scrape] Manifest fetch failed (non-fatal): {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    // ── Icon set: <link> icons + manifest icons + /favicon.ico, sized from file headers ──
+    scrape.icons = icons::resolve_icons(&client, &base_url, linked.icons, scrape.manifest.as_ref()).await;
+
+    println!("[server-side scrape] Found {} icons", scrape.icons.len());
+
+    // ── Stylesheets (<link rel="stylesheet"> + @import) ──
+    scrape.stylesheets = stylesheets::fetch_stylesheets(&client, linked.stylesheets).await;
+
+    // ── Icon sets: external sprite files + icon fonts declared in the stylesheets ──
+    let sprites = icon_sets::fetch_sprites(&client, linked.sprites).await;
+    scrape.icon_sets.sprites.extend(sprites);
+    // Every step below reads the same rules, so parse each stylesheet once
+    let parsed = stylesheets::ParsedSheets::parse(&scrape.stylesheets);
+    let fonts = icon_sets::detect_icon_fonts(&parsed);
+    scrape.icon_sets.icon_fonts.splice(0..0, fonts);
+
+    // ── Color schemes: light/dark custom properties and the dark-theme palette ──
+    (scrape.color_tokens, scrape.dark_palette) = themes::extract_schemes(&parsed.rules, &scrape.theme_colors);
+    scrape.typography = typography::from_stylesheets(&parsed.rules);
+
+    // ── Fonts: where each family is loaded from ──
+    scrape.font_sources = fonts::font_sources(&parsed);
+    scrape.font_stacks = fonts::declared_stacks(&parsed.rules);
+
+    println!("[server-side scrape] Found {} stylesheets, {} sprite sheets, {} icon fonts",
+        scrape.stylesheets.len(), scrape.icon_sets.sprites.len(), scrape.icon_sets.icon_fonts.len());
+
+    Ok(scrape)
+}
+
+/// URLs found while parsing the page that need a follow-up request
+struct LinkedResources {
+    manifest: Option<Url>,
+    icons: Vec<IconInfo>,
+    /// `<link>` stylesheets and `<style>` blocks in document order
+    stylesheets: Vec<stylesheets::SheetSource>,
+    /// External sprite files with the symbol ids the page uses from each
+    sprites: Vec<(Url, Vec<String>)>,
+}
+
+/// Parse the raw HTML into images, text, social profiles and contacts.
+/// Kept synchronous because `scraper::Html` is not `Send` and can't be held across an `.await`.
+fn parse_server_html(html_text: &str, base_url: &Url) -> (ServerScrape, LinkedResources) {
+    let document = Html::parse_document(html_text);
 
     // ── Extract Images (like webscrap.py: soup.find_all("img")) ──
     let mut images: Vec<ImageInfo> = Vec::new();
@@ -121,6 +362,7 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                          "data-lazy", "data-url", "data-image", "data-bg",
                          "data-hi-res-src", "data-retina", "data-full-src",
                          "data-zoom-image", "data-large-file", "data-medium-file"];
+            let first_index = images.len();
             for attr in &attrs {
                 if let Some(src) = el.value().attr(attr) {
                     let src_clean = src.split(',').next().unwrap_or("").trim().split(' ').next().unwrap_or("");
@@ -128,31 +370,36 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                         let full_url = base_url.join(src_clean).map(|u| u.to_string()).unwrap_or_else(|_| src_clean.to_string());
                         if !full_url.starts_with("data:") && !seen_urls.contains(&full_url) {
                             seen_urls.insert(full_url.clone());
-                            images.push(ImageInfo { src: full_url, alt: alt.clone(), width, height });
+                            images.push(ImageInfo::new(full_url, alt.clone(), width, height));
                         }
                     }
                 }
             }
 
-            // Also extract ALL entries from srcset
-            if let Some(srcset) = el.value().attr("srcset") {
-                for entry in srcset.split(',') {
-                    let src = entry.trim().split(' ').next().unwrap_or("");
-                    if !src.is_empty() {
-                        let full_url = base_url.join(src).map(|u| u.to_string()).unwrap_or_else(|_| src.to_string());
-                        if !full_url.starts_with("data:") && !seen_urls.contains(&full_url) {
-                            seen_urls.insert(full_url.clone());
-                            images.push(ImageInfo { src: full_url, alt: alt.clone(), width: 0, height: 0 });
-                        }
-                    }
+            // srcset and <picture><source> candidates become variants of this one image
+            let variants = srcset::img_variants(el, base_url);
+            if !variants.is_empty() {
+                for variant in &variants {
+                    seen_urls.insert(variant.src.clone());
                 }
+                if first_index == images.len() {
+                    images.push(ImageInfo::new(variants[0].src.clone(), alt.clone(), width, height));
+                }
+                images[first_index].variants = variants;
             }
         }
     }
 
-    // <picture> <source> tags — extract ALL srcset entries
+    // <source> tags outside a <picture> with an <img> — extract ALL srcset entries
     if let Ok(source_selector) = Selector::parse("source[srcset]") {
         for el in document.select(&source_selector) {
+            let in_picture = el
+                .parent()
+                .and_then(scraper::ElementRef::wrap)
+                .is_some_and(|p| p.value().name() == "picture" && p.children().filter_map(scraper::ElementRef::wrap).any(|c| c.value().name() == "img"));
+            if in_picture {
+                continue;
+            }
             if let Some(srcset) = el.value().attr("srcset").or(el.value().attr("data-srcset")) {
                 for entry in srcset.split(',') {
                     let src = entry.trim().split(' ').next().unwrap_or("");
@@ -160,7 +407,7 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                         let full_url = base_url.join(src).map(|u| u.to_string()).unwrap_or_else(|_| src.to_string());
                         if !full_url.starts_with("data:") && !seen_urls.contains(&full_url) {
                             seen_urls.insert(full_url.clone());
-                            images.push(ImageInfo { src: full_url, alt: String::new(), width: 0, height: 0 });
+                            images.push(ImageInfo::new(full_url, String::new(), 0, 0));
                         }
                     }
                 }
@@ -178,7 +425,7 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                     let full_url = base_url.join(href).map(|u| u.to_string()).unwrap_or_else(|_| href.to_string());
                     if !seen_urls.contains(&full_url) {
                         seen_urls.insert(full_url.clone());
-                        images.push(ImageInfo { src: full_url, alt: String::new(), width: 0, height: 0 });
+                        images.push(ImageInfo::new(full_url, String::new(), 0, 0));
                     }
                 }
             }
@@ -192,12 +439,20 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                 let full_url = base_url.join(content).map(|u| u.to_string()).unwrap_or_else(|_| content.to_string());
                 if !seen_urls.contains(&full_url) {
                     seen_urls.insert(full_url.clone());
-                    images.push(ImageInfo { src: full_url, alt: "Social preview".to_string(), width: 0, height: 0 });
+                    images.push(ImageInfo::new(full_url, "Social preview".to_string(), 0, 0));
                 }
             }
         }
     }
 
+    // JSON-LD Organization.logo (often not rendered as an <img> at all)
+    for full_url in logo::organization_logos(&document, base_url) {
+        if !seen_urls.contains(&full_url) {
+            seen_urls.insert(full_url.clone());
+            images.push(ImageInfo::new(full_url, "Organization logo".to_string(), 0, 0));
+        }
+    }
+
     // <video poster> images
     if let Ok(video_selector) = Selector::parse("video[poster]") {
         for el in document.select(&video_selector) {
@@ -205,7 +460,7 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                 let full_url = base_url.join(poster).map(|u| u.to_string()).unwrap_or_else(|_| poster.to_string());
                 if !seen_urls.contains(&full_url) {
                     seen_urls.insert(full_url.clone());
-                    images.push(ImageInfo { src: full_url, alt: "Video poster".to_string(), width: 0, height: 0 });
+                    images.push(ImageInfo::new(full_url, "Video poster".to_string(), 0, 0));
                 }
             }
         }
@@ -225,7 +480,7 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                                 let full_url = base_url.join(src).map(|u| u.to_string()).unwrap_or_else(|_| src.to_string());
                                 if !seen_urls.contains(&full_url) {
                                     seen_urls.insert(full_url.clone());
-                                    images.push(ImageInfo { src: full_url, alt: String::new(), width: 0, height: 0 });
+                                    images.push(ImageInfo::new(full_url, String::new(), 0, 0));
                                 }
                             }
                         }
@@ -252,13 +507,13 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
                             let url = if trimmed.starts_with("//") { format!("https:{}", trimmed) } else { trimmed.clone() };
                             if !seen_urls.contains(&url) {
                                 seen_urls.insert(url.clone());
-                                images.push(ImageInfo { src: url, alt: String::new(), width: 0, height: 0 });
+                                images.push(ImageInfo::new(url, String::new(), 0, 0));
                             }
                         } else if trimmed.starts_with("/") {
                             let full_url = base_url.join(&trimmed).map(|u| u.to_string()).unwrap_or_default();
                             if !full_url.is_empty() && !seen_urls.contains(&full_url) {
                                 seen_urls.insert(full_url.clone());
-                                images.push(ImageInfo { src: full_url, alt: String::new(), width: 0, height: 0 });
+                                images.push(ImageInfo::new(full_url, String::new(), 0, 0));
                             }
                         }
                     }
@@ -292,16 +547,61 @@ async fn server_side_scrape(url_str: &str) -> Result<(Vec<ImageInfo>, Vec<TextBl
 
     println!("[server-side scrape] Found {} text blocks", text_blocks.len());
 
+    // ── Extract Social Profiles (JSON-LD sameAs, twitter:site, footer links) ──
+    let social_profiles = social::extract_social_profiles(&document, base_url);
+
+    println!("[server-side scrape] Found {} social profiles", social_profiles.len());
+
+    // ── Extract Contact Details (mailto:/tel:, microdata, JSON-LD, <address>, text) ──
+    let contacts = contacts::extract_contacts(&document, base_url);
+
+    println!("[server-side scrape] Found {} emails, {} phones, {} addresses",
+        contacts.emails.len(), contacts.phones.len(), contacts.addresses.len());
+
+    // ── Page context for logo detection (header/nav, home links, JSON-LD logo) ──
+    let image_hints = logo::collect_image_hints(&document, base_url);
+
+    // ── Inline <svg> logos and icons ──
+    let inline_svgs = svg::extract_inline_svgs(&document);
+
+    println!("[server-side scrape] Found {} inline SVGs", inline_svgs.len());
+
+    // ── Icon sets: in-page sprite sheet and ligature icon fonts (Material Icons) ──
+    let icon_sets = IconInventory {
+        sprites: icon_sets::inline_sprite(&document, base_url).into_iter().collect(),
+        icon_fonts: icon_sets::ligature_icon_fonts(&document, base_url),
+    };
+
+    // ── Linked resources fetched after parsing (manifest, icons, stylesheets, sprites) ──
+    let linked = LinkedResources {
+        manifest: manifest::manifest_url(&document, base_url),
+        icons: icons::collect_icon_links(&document, base_url),
+        stylesheets: stylesheets::stylesheet_sources(&document, base_url),
+        sprites: icon_sets::sprite_references(&document, base_url),
+    };
+
     // Cap results
     images.truncate(500);
     text_blocks.truncate(500);
 
-    Ok((images, text_blocks))
+    let scrape = ServerScrape {
+        images,
+        text_content: text_blocks,
+        social_profiles,
+        contacts,
+        image_hints,
+        inline_svgs,
+        icon_sets,
+        theme_colors: themes::theme_colors(&document),
+        ..Default::default()
+    };
+    (scrape, linked)
 }
 
 #[tauri::command]
-async fn analyze_page(app: AppHandle, state: State<'_, AppState>, url: String) -> Result<AnalysisResult, String> {
+async fn analyze_page(app: AppHandle, state: State<'_, AppState>, url: String, options: Option<AnalyzeOptions>) -> Result<AnalysisResult, String> {
     println!("Analyzing URL: {}", url);
+    let options = options.unwrap_or_default();
     let label = "scraper-window";
 
     // Close existing window if any
@@ -347,19 +647,24 @@ async fn analyze_page(app: AppHandle, state: State<'_, AppState>, url: String) -
     let browser_data = browser_result?;
 
     // Wait for server-side scrape
-    let (server_images, server_text) = match server_scrape_handle.await {
+    let server = match server_scrape_handle.await {
         Ok(Ok(data)) => data,
         Ok(Err(e)) => {
             println!("Server-side scrape failed (non-fatal): {}", e);
-            (Vec::new(), Vec::new())
+            ServerScrape::default()
         },
         Err(e) => {
             println!("Server-side scrape task failed (non-fatal): {}", e);
-            (Vec::new(), Vec::new())
+            ServerScrape::default()
         },
     };
+    let server_images = server.images;
+    let server_text = server.text_content;
 
-    // ── MERGE results from both scrapers (deduplicate by NORMALIZED URL) ──
+    // ── MERGE results from both scrapers ──
+    // Size variants are collapsed by NORMALIZED URL. With content dedupe on, the normalized URL
+    // only proposes matches: images are merged by exact URL here and the perceptual hash decides
+    // after probing, which also catches the same picture under unrelated CDN URLs.
     fn normalize_image_url(url: &str) -> String {
         // Strip query params and hash
         let base = url.split('?').next().unwrap_or(url).split('#').next().unwrap_or(url);
@@ -380,41 +685,74 @@ async fn analyze_page(app: AppHandle, state: State<'_, AppState>, url: String) -
             (filename, "")
         };
         // Strip ALL resolution/size patterns from the base
-        let patterns = [
-            (r"[-_](cc_ft_|ft_|uncropped_scaled_within_)\d+", ""),    // Zillow
-            (r"[-_]\d{2,4}x\d{2,4}", ""),                             // -300x200
-            (r"@\dx", ""),                                              // @2x
-            (r"[-_](small|medium|large|thumb|thumbnail|scaled|preview|mini|full|original|cropped)", ""),
-            (r"[-_]\d{2,4}w?$", ""),                                   // _768, _384w, trailing numbers
-            (r"[-_]+$", ""),                                            // clean trailing separators
-        ];
+        static PATTERNS: OnceLock<Vec<regex_lite::Regex>> = OnceLock::new();
+        let patterns = PATTERNS.get_or_init(|| {
+            [
+                r"[-_](cc_ft_|ft_|uncropped_scaled_within_)\d+",    // Zillow
+                r"[-_]\d{2,4}x\d{2,4}",                             // -300x200
+                r"@\dx",                                            // @2x
+                r"[-_](small|medium|large|thumb|thumbnail|scaled|preview|mini|full|original|cropped)",
+                r"[-_]\d{2,4}w?$",                                  // _768, _384w, trailing numbers
+                r"[-_]+$",                                          // clean trailing separators
+            ]
+            .into_iter()
+            .filter_map(|pattern| regex_lite::Regex::new(pattern).ok())
+            .collect()
+        });
         let mut cleaned = file_base.to_string();
-        for (pattern, replacement) in &patterns {
-            if let Ok(re) = regex_lite::Regex::new(pattern) {
-                cleaned = re.replace_all(&cleaned, *replacement).to_string();
-            }
+        for re in patterns {
+            cleaned = re.replace_all(&cleaned, "").to_string();
         }
         format!("{}{}{}", dir, cleaned, ext)
     }
 
-    let mut seen_image_urls: std::collections::HashSet<String> = std::collections::HashSet::new();
+    /// Add an image, or fold it into an earlier one that shares its URL or any srcset candidate
+    /// (normalized when `by_pattern`, exact otherwise)
+    fn merge_image(img: ImageInfo, merged: &mut Vec<ImageInfo>, index: &mut std::collections::HashMap<String, usize>, by_pattern: bool) {
+        let keys: Vec<String> = std::iter::once(&img.src)
+            .chain(img.variants.iter().map(|v| &v.src))
+            .map(|src| if by_pattern { normalize_image_url(src) } else { src.clone() })
+            .collect();
+        let position = match keys.iter().find_map(|key| index.get(key).copied()) {
+            Some(existing) => {
+                let entry = &mut merged[existing];
+                if entry.alt.is_empty() {
+                    entry.alt = img.alt;
+                }
+                srcset::merge_variants(&mut entry.variants, img.variants);
+                existing
+            }
+            None => {
+                merged.push(img);
+                merged.len() - 1
+            }
+        };
+        for key in keys {
+            index.entry(key).or_insert(position);
+        }
+    }
+
+    let by_pattern = !options.content_dedupe;
+    let mut image_index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
     let mut merged_images: Vec<ImageInfo> = Vec::new();
 
-    // Browser images first (higher quality — they have actual rendered dimensions)
+    // Manifest icons first (declared brand assets with known sizes)
+    for img in server.manifest.iter().flat_map(|m| m.icon_images()) {
+        merge_image(img, &mut merged_images, &mut image_index, by_pattern);
+    }
+
+    // Then browser images (higher quality than the raw HTML's — they have actual rendered dimensions)
     for img in &browser_data.images {
-        let norm = normalize_image_url(&img.src);
-        if !seen_image_urls.contains(&norm) {
-            seen_image_urls.insert(norm);
-            merged_images.push(img.clone());
-        }
+        merge_image(img.clone(), &mut merged_images, &mut image_index, by_pattern);
     }
     // Then server-side images (catches anything the browser missed)
     for img in &server_images {
-        let norm = normalize_image_url(&img.src);
-        if !seen_image_urls.contains(&norm) {
-            seen_image_urls.insert(norm);
-            merged_images.push(img.clone());
-        }
+        merge_image(img.clone(), &mut merged_images, &mut image_index, by_pattern);
+    }
+
+    // The largest srcset candidate becomes the default download
+    for img in &mut merged_images {
+        srcset::promote_largest(img);
     }
 
     let mut seen_text: std::collections::HashSet<String> = std::collections::HashSet::new();
@@ -435,6 +773,75 @@ async fn analyze_page(app: AppHandle, state: State<'_, AppState>, url: String) -
         }
     }
 
+    // Social profiles, contacts, image context and inline SVGs: rendered DOM first (footers and icon
+    // components are often injected by JS), then raw HTML
+    let (mut social_profiles, mut merged_contacts, mut image_hints, browser_svgs) = match Url::parse(&url) {
+        Ok(page_url) if !browser_data.html.is_empty() => {
+            let rendered = Html::parse_document(&browser_data.html);
+            (
+                social::extract_social_profiles(&rendered, &page_url),
+                contacts::extract_contacts(&rendered, &page_url),
+                logo::collect_image_hints(&rendered, &page_url),
+                svg::extract_inline_svgs(&rendered),
+            )
+        }
+        _ => (Vec::new(), ContactDetails::default(), ImageHints::default(), Vec::new()),
+    };
+    social::merge_profiles(&mut social_profiles, server.social_profiles);
+    merged_contacts.merge(server.contacts);
+    image_hints.merge(server.image_hints);
+    let inline_svgs = svg::merge_inline_svgs(browser_svgs, server.inline_svgs);
+
+    // Probe: real dimensions, format and file size; broken URLs are dropped
+    let probe_cache = state.probe_cache.clone();
+    merged_images = probe::probe_images(merged_images, &probe_cache).await;
+
+    // Content dedupe: the same picture under different CDN URLs, keeping the largest copy
+    if options.content_dedupe {
+        let hash_cache = state.hash_cache.clone();
+        // With content dedupe on, merging above keyed images by exact URL, so this is the only
+        // pass that normalizes them (one call per image, against the precompiled patterns)
+        let patterns = merged_images.iter().map(|img| normalize_image_url(&img.src)).collect();
+        merged_images = dedupe::dedupe_images(merged_images, patterns, &hash_cache).await;
+    }
+
+    // Logo detection: tag every image with a kind and move logos to the front
+    logo::classify_images(&mut merged_images, &image_hints);
+
+    // Image colors: dominant colors of logo artwork and hero images
+    let color_cache = state.color_cache.clone();
+    image_colors::extract_image_colors(&mut merged_images, &color_cache).await;
+
+    // Palette: colors spelled out in the text, manifest theme/background and light theme-color metas,
+    // then logo artwork (SVG paint colors first), then computed styles
+    let color_specs = color_specs::extract_color_specs(&merged_text);
+    let mut declared_colors = color_specs::palette_colors(&color_specs);
+    declared_colors.extend(server.manifest.as_ref().map(|m| m.palette_colors()).unwrap_or_default());
+    declared_colors.extend(themes::theme_color_entries(&server.theme_colors, false));
+    let logo_colors = image_colors::logo_palette(&merged_images, &inline_svgs);
+    let mut palette = palette::build_palette(declared_colors, logo_colors, &browser_data.colors);
+    palette::match_spot_colors(&mut palette, &state.settings.lock().unwrap().spot_colors);
+    scales::assign_roles(&mut palette);
+    let color_scales = scales::build_scales(&palette);
+    let scale_tokens = scales::scale_tokens(&color_scales, &state.settings.lock().unwrap().spot_colors);
+    let cvd_conflicts = palette::cvd_conflicts(&palette);
+    let mut dark_palette = server.dark_palette;
+    palette::match_spot_colors(&mut dark_palette, &state.settings.lock().unwrap().spot_colors);
+
+    // Typography: computed styles per role, stylesheet declarations for roles the page didn't render
+    let typography = typography::build_typography(&browser_data.typography, server.typography);
+    let mut font_families = fonts::resolve_families(&browser_data.font_stacks, &typography.styles, &server.font_stacks, &server.font_sources);
+
+    // Font files: real family, foundry, license and axes from the name, OS/2 and fvar tables
+    let font_cache = state.font_cache.clone();
+    font_files::extract_font_info(&mut font_families, &url, &font_cache).await;
+
+    // Favicon: the best-ranked icon instead of whichever <link rel=icon> came first
+    let mut metadata = browser_data.metadata;
+    if let Some(best) = server.icons.first() {
+        metadata.favicon = best.src.clone();
+    }
+
     merged_images.truncate(500);
     merged_text.truncate(500);
 
@@ -446,42 +853,128 @@ async fn analyze_page(app: AppHandle, state: State<'_, AppState>, url: String) -
     // Combine all results
     Ok(AnalysisResult {
         colors: browser_data.colors,
+        palette,
         fonts: browser_data.fonts,
         images: merged_images,
         text_content: merged_text,
-        metadata: browser_data.metadata,
+        metadata,
+        social_profiles,
+        contacts: merged_contacts,
+        manifest: server.manifest,
+        icons: server.icons,
+        inline_svgs,
+        icon_sets: server.icon_sets,
+        color_specs,
+        color_scales,
+        scale_tokens,
+        cvd_conflicts,
+        dark_palette,
+        color_tokens: server.color_tokens,
+        typography,
+        font_families,
     })
 }
 
-/// Download an image from a URL and save it to ~/Downloads
+/// Current download destination
 #[tauri::command]
-async fn download_image(url: String) -> Result<String, String> {
-    println!("Downloading image: {}", url);
+fn get_download_dir(state: State<'_, AppState>) -> String {
+    state.destination_dir().to_string_lossy().to_string()
+}
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Mozilla/5.0")
-        .send()
+/// Change the download destination, remembered across launches; `None` goes back to the OS downloads folder
+#[tauri::command]
+fn set_download_dir(state: State<'_, AppState>, path: Option<String>) -> Result<String, String> {
+    let dir = match path.filter(|p| !p.trim().is_empty()) {
+        Some(p) => Some(settings::existing_dir(&p)?),
+        None => None,
+    };
+    state.update_settings(|settings| settings.download_dir = dir)?;
+    Ok(state.destination_dir().to_string_lossy().to_string())
+}
+
+/// Load a spot-color reference table (CSV or JSON) that palette entries are matched against;
+/// `None` clears it. Returns the number of colors loaded.
+#[tauri::command]
+async fn set_spot_colors(state: State<'_, AppState>, path: Option<String>) -> Result<usize, String> {
+    let table = match path.filter(|p| !p.trim().is_empty()) {
+        // File I/O is blocking; keep it off the async workers
+        Some(p) => tokio::task::spawn_blocking(move || -> Result<Vec<color::SpotColor>, String> {
+            let file = settings::existing_file(&p)?;
+            let text = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            color::parse_spot_table(&text)
+        })
         .await
-        .map_err(|e| format!("Download failed: {}", e))?;
+        .map_err(|e| format!("Read task failed: {}", e))??,
+        None => Vec::new(),
+    };
+    println!("[color] Loaded {} spot colors", table.len());
+    let count = table.len();
+    state.update_settings(|settings| settings.spot_colors = table)?;
+    Ok(count)
+}
 
-    let bytes = response.bytes().await.map_err(|e| format!("Failed to read image: {}", e))?;
+/// Download an image from a URL into the download folder, streaming it to disk and
+/// reporting progress through `download-progress` events. `convert` optionally re-encodes,
+/// resizes or applies a preset (favicon set, OG card, square avatar).
+#[tauri::command]
+async fn download_image(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    max_bytes: Option<u64>,
+    convert: Option<ConvertOptions>,
+) -> Result<String, String> {
+    println!("Downloading image: {}", url);
 
-    // Extract filename from URL
-    let parsed_url = Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
-    let filename = parsed_url.path_segments()
-        .and_then(|segs| segs.last())
-        .and_then(|name| if name.is_empty() { None } else { Some(name.to_string()) })
-        .unwrap_or_else(|| "image.png".to_string());
+    let client = download::client()?;
+    // Thumbnails from transforming CDNs → the original upload, when a HEAD request confirms it
+    let requested = url.clone();
+    let url = cdn::resolve_original(&client, &url).await;
+    let max_bytes = max_bytes.unwrap_or(download::DEFAULT_MAX_BYTES);
+    let save_path = download::download_to_dir(&client, &url, &state.destination_dir(), "image", max_bytes, |progress| {
+        // Report the URL the frontend asked for so it can match events to its buttons
+        let _ = app.emit("download-progress", download::DownloadProgress { url: requested.clone(), ..progress });
+    })
+    .await?;
+    let save_path = match convert {
+        Some(options) => tokio::task::spawn_blocking(move || convert::convert_file(&save_path, &url, &options))
+            .await
+            .map_err(|e| format!("Conversion task failed: {}", e))??,
+        None => save_path,
+    };
+
+    println!("Image saved to: {:?}", save_path);
+    Ok(save_path.to_string_lossy().to_string())
+}
 
-    // Save to ~/Downloads
-    let downloads_dir = dirs::download_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
-    let save_path = downloads_dir.join(&filename);
+/// Download a batch of images, icons, SVGs and fonts into one ZIP (or folder) in the download folder,
+/// sorted into per-kind subdirectories with a `manifest.json` describing every file and the page's palette
+#[tauri::command]
+async fn download_assets(state: State<'_, AppState>, assets: Vec<AssetRequest>, page_url: String, palette: Option<Vec<PaletteColor>>, as_zip: Option<bool>) -> Result<String, String> {
+    let dest_dir = state.destination_dir();
+    assets::download_bundle(assets, page_url, palette.unwrap_or_default(), as_zip.unwrap_or(true), dest_dir).await
+}
 
-    std::fs::write(&save_path, &bytes).map_err(|e| format!("Failed to save file: {}", e))?;
+/// Save a captured inline SVG to the download folder
+#[tauri::command]
+async fn save_svg(state: State<'_, AppState>, markup: String, name: String, convert: Option<ConvertOptions>) -> Result<String, String> {
+    // The markup round-trips through the frontend, so sanitize it again before writing
+    let clean = svg::sanitize_svg_markup(&markup).ok_or_else(|| "No <svg> element in markup".to_string())?;
+
+    let stem = filenames::sanitize(name.trim_end_matches(".svg"));
+    let filename = format!("{}.svg", if stem.is_empty() { "image" } else { &stem });
+    let (save_path, mut file) = filenames::create_unique_file(&state.destination_dir(), &filename)?;
+    file.write_all(clean.as_bytes()).map_err(|e| format!("Failed to save file: {}", e))?;
+    drop(file);
+    let save_path = match convert {
+        // Captured markup has no URL of its own to embed
+        Some(options) => tokio::task::spawn_blocking(move || convert::convert_file(&save_path, "", &options))
+            .await
+            .map_err(|e| format!("Conversion task failed: {}", e))??,
+        None => save_path,
+    };
 
-    println!("Image saved to: {:?}", save_path);
+    println!("SVG saved to: {:?}", save_path);
     Ok(save_path.to_string_lossy().to_string())
 }
 
@@ -495,10 +988,20 @@ async fn open_in_browser(url: String) -> Result<(), String> {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .manage(AppState {
-            pending_analysis: Arc::new(Mutex::new(None)),
+        .setup(|app| {
+            let settings_path = app.path().app_config_dir()?.join("settings.json");
+            app.manage(AppState {
+                pending_analysis: Arc::new(Mutex::new(None)),
+                probe_cache: probe::ProbeCache::default(),
+                hash_cache: dedupe::HashCache::default(),
+                color_cache: image_colors::ColorCache::default(),
+                font_cache: font_files::FontCache::default(),
+                settings: Mutex::new(settings::load(&settings_path)),
+                settings_path,
+            });
+            Ok(())
         })
-        .invoke_handler(tauri::generate_handler![greet, analyze_page, complete_analysis, download_image, open_in_browser])
+        .invoke_handler(tauri::generate_handler![greet, analyze_page, complete_analysis, download_image, download_assets, save_svg, get_download_dir, set_download_dir, set_spot_colors, open_in_browser])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
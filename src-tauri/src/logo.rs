@@ -0,0 +1,360 @@
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+use url::Url;
+
+use crate::jsonld;
+use crate::social::ORGANIZATION_TYPES;
+use crate::ImageInfo;
+
+/// Score at or above which an image is classified as a logo
+const LOGO_THRESHOLD: u32 = 40;
+
+/// Page context for one image URL, gathered from the DOM around the element that references it
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ImageHint {
+    in_header: bool,
+    in_nav: bool,
+    links_home: bool,
+    /// "logo" / "brand" in the element's or a close ancestor's alt, class or id
+    logo_marked: bool,
+    /// Inside an element whose class/id mentions hero, banner or carousel
+    hero_marked: bool,
+    /// Referenced from an inline `background` style
+    background: bool,
+    /// `og:image` / `twitter:image`
+    social_preview: bool,
+    /// `Organization.logo` in JSON-LD
+    organization_logo: bool,
+}
+
+impl ImageHint {
+    fn absorb(&mut self, other: &ImageHint) {
+        self.in_header |= other.in_header;
+        self.in_nav |= other.in_nav;
+        self.links_home |= other.links_home;
+        self.logo_marked |= other.logo_marked;
+        self.hero_marked |= other.hero_marked;
+        self.background |= other.background;
+        self.social_preview |= other.social_preview;
+        self.organization_logo |= other.organization_logo;
+    }
+}
+
+/// Image hints keyed by absolute URL (without query string or fragment)
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ImageHints(HashMap<String, ImageHint>);
+
+impl ImageHints {
+    fn add(&mut self, url: &str, hint: ImageHint) {
+        self.0.entry(hint_key(url)).or_default().absorb(&hint);
+    }
+
+    pub(crate) fn merge(&mut self, other: ImageHints) {
+        for (key, hint) in other.0 {
+            self.0.entry(key).or_default().absorb(&hint);
+        }
+    }
+
+    fn get(&self, url: &str) -> Option<&ImageHint> {
+        self.0.get(&hint_key(url))
+    }
+}
+
+fn hint_key(url: &str) -> String {
+    url.split(['?', '#']).next().unwrap_or(url).to_string()
+}
+
+fn attr_mentions(el: &ElementRef, words: &[&str]) -> bool {
+    ["class", "id", "alt", "aria-label", "title"].iter().any(|attr| {
+        el.value()
+            .attr(attr)
+            .map(|v| v.to_lowercase())
+            .is_some_and(|v| words.iter().any(|w| v.contains(w)))
+    })
+}
+
+/// Whether an `<a href>` points at the site's home page (`/`, `/en/`, `https://brand.com`)
+fn is_home_link(href: &str, base_url: &Url) -> bool {
+    let Ok(target) = base_url.join(href) else { return false };
+    if target.host_str() != base_url.host_str() {
+        return false;
+    }
+    let path = target.path().trim_end_matches('/').trim_end_matches("index.html").trim_end_matches('/');
+    path.is_empty() || (path.len() <= 6 && path.matches('/').count() == 1)
+}
+
+/// "logo" in the element's own alt, class, id or label, or "logo" / "brand" on a close ancestor
+pub(crate) fn marked_as_logo(el: ElementRef) -> bool {
+    attr_mentions(&el, &["logo"]) || el.ancestors().filter_map(ElementRef::wrap).take(3).any(|a| attr_mentions(&a, &["logo", "brand"]))
+}
+
+/// Describe where an element sits on the page
+fn element_hint(el: ElementRef, base_url: &Url) -> ImageHint {
+    let mut hint = ImageHint { logo_marked: marked_as_logo(el), ..Default::default() };
+    for (depth, ancestor) in el.ancestors().filter_map(ElementRef::wrap).enumerate() {
+        let name = ancestor.value().name();
+        let role = ancestor.value().attr("role").unwrap_or("");
+        if name == "header" || role == "banner" || (depth < 4 && attr_mentions(&ancestor, &["header", "masthead"])) {
+            hint.in_header = true;
+        }
+        if name == "nav" || role == "navigation" {
+            hint.in_nav = true;
+        }
+        if name == "a" && ancestor.value().attr("href").is_some_and(|href| is_home_link(href, base_url)) {
+            hint.links_home = true;
+        }
+        if depth < 4 && attr_mentions(&ancestor, &["hero", "banner", "carousel", "slider", "jumbotron"]) {
+            hint.hero_marked = true;
+        }
+    }
+    hint
+}
+
+/// Walk the document and record context for every image URL it references
+pub(crate) fn collect_image_hints(document: &Html, base_url: &Url) -> ImageHints {
+    let mut hints = ImageHints::default();
+    let resolve = |src: &str| base_url.join(src.trim()).map(|u| u.to_string()).ok();
+
+    // <img> (every source attribute and srcset candidate shares the element's context)
+    if let Ok(selector) = Selector::parse("img, picture source, svg image") {
+        for el in document.select(&selector) {
+            // <source> takes its context from the <picture>'s <img>
+            let context = if el.value().name() == "source" {
+                el.parent().and_then(ElementRef::wrap).unwrap_or(el)
+            } else {
+                el
+            };
+            let hint = element_hint(context, base_url);
+            let mut sources: Vec<&str> = ["src", "data-src", "data-lazy-src", "data-original", "href", "xlink:href"]
+                .iter()
+                .filter_map(|attr| el.value().attr(attr))
+                .collect();
+            for srcset in ["srcset", "data-srcset"].iter().filter_map(|attr| el.value().attr(attr)) {
+                sources.extend(srcset.split(',').filter_map(|entry| entry.split_whitespace().next()));
+            }
+            for src in sources {
+                if let Some(url) = resolve(src) {
+                    hints.add(&url, hint.clone());
+                }
+            }
+        }
+    }
+
+    // Inline background images
+    if let Ok(selector) = Selector::parse("[style*='url(']") {
+        for el in document.select(&selector) {
+            let style = el.value().attr("style").unwrap_or("");
+            let mut hint = element_hint(el, base_url);
+            hint.background = true;
+            for part in style.split("url(").skip(1) {
+                if let Some(end) = part.find(')') {
+                    if let Some(url) = resolve(part[..end].trim().trim_matches('"').trim_matches('\'')) {
+                        hints.add(&url, hint.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    // Social preview images
+    if let Ok(selector) = Selector::parse("meta[property='og:image'], meta[name='twitter:image']") {
+        for el in document.select(&selector) {
+            if let Some(url) = el.value().attr("content").and_then(resolve) {
+                hints.add(&url, ImageHint { social_preview: true, ..Default::default() });
+            }
+        }
+    }
+
+    // JSON-LD Organization.logo
+    for url in organization_logos(document, base_url) {
+        hints.add(&url, ImageHint { organization_logo: true, ..Default::default() });
+    }
+
+    hints
+}
+
+/// Absolute URLs of `Organization.logo` declared in JSON-LD
+pub(crate) fn organization_logos(document: &Html, base_url: &Url) -> Vec<String> {
+    let mut logos = Vec::new();
+    for block in jsonld::blocks(document) {
+        jsonld::walk_objects(&block, &mut |obj| {
+            if jsonld::has_type(obj, ORGANIZATION_TYPES) {
+                for logo in obj.get("logo").map(jsonld::strings).unwrap_or_default() {
+                    if let Ok(url) = base_url.join(logo.trim()) {
+                        logos.push(url.to_string());
+                    }
+                }
+            }
+        });
+    }
+    logos
+}
+
+fn file_name(src: &str) -> String {
+    let path = Url::parse(src).map(|u| u.path().to_string()).unwrap_or_else(|_| src.to_string());
+    path.rsplit('/').next().unwrap_or("").to_lowercase()
+}
+
+/// Logo likelihood for an image, 0–100
+fn logo_score(image: &ImageInfo, hint: &ImageHint) -> u32 {
+    let name = file_name(&image.src);
+    let alt = image.alt.to_lowercase();
+    let mut score: i32 = 0;
+    if hint.organization_logo {
+        score += 45;
+    }
+    if name.contains("logo") || name.contains("wordmark") || name.contains("logotype") {
+        score += 25;
+    }
+    if hint.logo_marked || alt.contains("logo") {
+        score += 25;
+    }
+    if hint.links_home {
+        score += 20;
+    }
+    if hint.in_header {
+        score += 15;
+    }
+    if hint.in_nav {
+        score += 10;
+    }
+    // Probed format first; the extension only for images the probe couldn't read
+    let svg = if image.format.is_empty() { name.ends_with(".svg") || image.src.starts_with("data:image/svg") } else { image.format == "svg" };
+    if svg {
+        score += 10;
+    }
+    if image.width > 0 && image.height > 0 {
+        let ratio = image.width as f32 / image.height as f32;
+        // Logos are square marks or wide wordmarks, and rarely huge
+        if (0.8..=6.0).contains(&ratio) && image.width <= 800 {
+            score += 5;
+        }
+        if ratio < 0.5 || image.width > 1600 {
+            score -= 20;
+        }
+    }
+    if hint.social_preview || hint.background {
+        score -= 15;
+    }
+    score.clamp(0, 100) as u32
+}
+
+/// Assign a `kind` and logo `score` to every image, then move likely logos to the front
+pub(crate) fn classify_images(images: &mut [ImageInfo], hints: &ImageHints) {
+    let empty = ImageHint::default();
+    for image in images.iter_mut() {
+        let hint = hints.get(&image.src).unwrap_or(&empty);
+        let name = file_name(&image.src);
+        let score = logo_score(image, hint);
+        let small_square = image.width > 0 && image.width <= 64 && image.width == image.height;
+
+        image.score = score;
+        image.kind = if score >= LOGO_THRESHOLD {
+            "logo"
+        } else if image.source == "manifest" || name.contains("favicon") || name.contains("apple-touch") || small_square {
+            "icon"
+        } else if hint.social_preview || image.alt == "Social preview" {
+            "social-preview"
+        } else if hint.hero_marked || image.width >= 1200 {
+            "hero"
+        } else if hint.background {
+            "background"
+        } else {
+            "product"
+        }
+        .to_string();
+    }
+    images.sort_by_key(|image| std::cmp::Reverse(if image.kind == "logo" { image.score } else { 0 }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://brand.example/about/";
+
+    fn image(src: &str, alt: &str, width: u32, height: u32) -> ImageInfo {
+        ImageInfo::new(src.to_string(), alt.to_string(), width, height)
+    }
+
+    fn hints(html: &str) -> ImageHints {
+        collect_image_hints(&Html::parse_document(html), &Url::parse(BASE).unwrap())
+    }
+
+    #[test]
+    fn header_home_link_outscores_content_images() {
+        let hints = hints(
+            r#"<header><a href="/"><img src="/img/mark.png" alt="Brand"></a></header>
+               <main><img src="/img/team.jpg" alt="Our team"></main>"#,
+        );
+        let header = hints.get("https://brand.example/img/mark.png").unwrap();
+        assert!(header.in_header && header.links_home);
+        let score = logo_score(&image("https://brand.example/img/mark.png", "Brand", 200, 60), header);
+        assert_eq!(score, 20 + 15 + 5);
+        let team = hints.get("https://brand.example/img/team.jpg").unwrap();
+        assert_eq!(logo_score(&image("https://brand.example/img/team.jpg", "Our team", 800, 600), team), 5);
+    }
+
+    #[test]
+    fn alt_and_file_name_hints() {
+        let none = ImageHint::default();
+        assert_eq!(logo_score(&image("https://brand.example/brand-logo.png", "", 0, 0), &none), 25);
+        assert_eq!(logo_score(&image("https://brand.example/a.png", "Acme Logo", 0, 0), &none), 25);
+        assert_eq!(logo_score(&image("https://brand.example/wordmark.png", "Acme logo", 0, 0), &none), 50);
+        // Query strings don't count towards the file name
+        assert_eq!(logo_score(&image("https://brand.example/a.png?v=logo", "", 0, 0), &none), 0);
+    }
+
+    #[test]
+    fn svg_bonus_trusts_the_probed_format() {
+        let none = ImageHint::default();
+        let mut svg = image("https://brand.example/mark.svg", "", 0, 0);
+        assert_eq!(logo_score(&svg, &none), 10);
+        svg.format = "png".to_string();
+        assert_eq!(logo_score(&svg, &none), 0);
+        let mut served = image("https://brand.example/render?id=3", "", 0, 0);
+        served.format = "svg".to_string();
+        assert_eq!(logo_score(&served, &none), 10);
+        assert_eq!(logo_score(&image("data:image/svg+xml;base64,PHN2Zz4=", "", 0, 0), &none), 10);
+    }
+
+    #[test]
+    fn shape_and_context_penalties() {
+        let logo_name = |width, height| image("https://brand.example/logo.png", "", width, height);
+        let none = ImageHint::default();
+        assert_eq!(logo_score(&logo_name(300, 100), &none), 30);
+        assert_eq!(logo_score(&logo_name(100, 300), &none), 5);
+        assert_eq!(logo_score(&logo_name(2000, 1000), &none), 5);
+        let preview = ImageHint { social_preview: true, ..Default::default() };
+        assert_eq!(logo_score(&logo_name(0, 0), &preview), 10);
+        let everything = ImageHint { organization_logo: true, logo_marked: true, links_home: true, in_header: true, in_nav: true, ..Default::default() };
+        assert_eq!(logo_score(&image("https://brand.example/logo.svg", "", 200, 50), &everything), 100);
+    }
+
+    #[test]
+    fn classifies_kinds_and_moves_logos_first() {
+        let hints = hints(
+            r#"<head><meta property="og:image" content="/share.jpg">
+               <script type="application/ld+json">{"@type": "Organization", "logo": "/org-mark.png"}</script></head>
+               <body><header><a href="/"><img src="/logo.svg"></a></header>
+               <section class="hero-banner"><img src="/hero.jpg"></section>
+               <div style="background: url('/texture.png')"></div>
+               <img src="/favicon-32.png"><img src="/shoe.jpg"></body>"#,
+        );
+        let mut images = vec![
+            image("https://brand.example/shoe.jpg", "Red shoe", 600, 600),
+            image("https://brand.example/hero.jpg", "", 1000, 500),
+            image("https://brand.example/share.jpg", "", 1200, 630),
+            image("https://brand.example/texture.png", "", 400, 400),
+            image("https://brand.example/favicon-32.png", "", 32, 32),
+            image("https://brand.example/org-mark.png?v=2", "", 0, 0),
+            image("https://brand.example/logo.svg", "", 0, 0),
+        ];
+        classify_images(&mut images, &hints);
+        let names: Vec<String> = images.iter().map(|image| file_name(&image.src)).collect();
+        assert_eq!(names, ["logo.svg", "org-mark.png", "shoe.jpg", "hero.jpg", "share.jpg", "texture.png", "favicon-32.png"]);
+        let kinds: Vec<&str> = images.iter().map(|image| image.kind.as_str()).collect();
+        assert_eq!(kinds, ["logo", "logo", "product", "hero", "social-preview", "background", "icon"]);
+        assert!(images[0].score >= images[1].score);
+    }
+}
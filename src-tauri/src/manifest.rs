@@ -0,0 +1,153 @@
+use scraper::{Html, Selector};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::color;
+use crate::fetch;
+use crate::palette::PaletteColor;
+use crate::ImageInfo;
+
+/// Manifests are a few KB; anything past this is not one
+const MAX_MANIFEST_BYTES: usize = 1_000_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ManifestIcon {
+    pub(crate) src: String,
+    /// Raw `sizes` value, e.g. "192x192 512x512" or "any"
+    pub(crate) sizes: String,
+    /// Largest declared size (0 for "any" / missing)
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    #[serde(rename = "type")]
+    pub(crate) mime_type: String,
+    /// "any", "maskable", "monochrome" (space-separated when several)
+    pub(crate) purpose: String,
+}
+
+/// The parts of a Web App Manifest (`manifest.webmanifest`) that describe the brand
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct WebAppManifest {
+    pub(crate) url: String,
+    pub(crate) name: String,
+    pub(crate) short_name: String,
+    pub(crate) theme_color: String,
+    pub(crate) background_color: String,
+    pub(crate) icons: Vec<ManifestIcon>,
+}
+
+impl WebAppManifest {
+    /// Icons as images, largest first, tagged as high-confidence brand assets
+    pub(crate) fn icon_images(&self) -> Vec<ImageInfo> {
+        let label = if self.name.is_empty() { &self.short_name } else { &self.name };
+        let mut icons: Vec<&ManifestIcon> = self.icons.iter().collect();
+        icons.sort_by_key(|icon| std::cmp::Reverse(icon.width));
+        icons
+            .into_iter()
+            .map(|icon| {
+                let mut alt = format!("{} app icon", label).trim().to_string();
+                if !icon.sizes.is_empty() {
+                    alt.push_str(&format!(" {}", icon.sizes));
+                }
+                if !icon.purpose.is_empty() && icon.purpose != "any" {
+                    alt.push_str(&format!(" ({})", icon.purpose));
+                }
+                let mut image = ImageInfo::new(icon.src.clone(), alt, icon.width, icon.height);
+                image.source = "manifest".to_string();
+                image
+            })
+            .collect()
+    }
+
+    /// `theme_color` and `background_color` as declared brand colors
+    pub(crate) fn palette_colors(&self) -> Vec<PaletteColor> {
+        [(&self.theme_color, "manifest-theme"), (&self.background_color, "manifest-background")]
+            .into_iter()
+            .filter_map(|(value, source)| color::parse_css_color(value).map(|rgb| PaletteColor::new(rgb, source, true)))
+            .collect()
+    }
+}
+
+/// Resolve `<link rel="manifest" href="...">` against the page URL
+pub(crate) fn manifest_url(document: &Html, base_url: &Url) -> Option<Url> {
+    let selector = Selector::parse("link[rel~='manifest'][href]").ok()?;
+    let href = document.select(&selector).next()?.value().attr("href")?;
+    base_url.join(href).ok()
+}
+
+/// Parse `sizes` ("48x48 96x96", "any") and return the largest declared width x height
+pub(crate) fn largest_size(sizes: &str) -> (u32, u32) {
+    sizes
+        .split_whitespace()
+        .filter_map(|size| {
+            let (w, h) = size.to_lowercase().split_once('x').map(|(w, h)| (w.to_string(), h.to_string()))?;
+            Some((w.parse().ok()?, h.parse().ok()?))
+        })
+        .max_by_key(|(w, h): &(u32, u32)| *w as u64 * *h as u64)
+        .unwrap_or((0, 0))
+}
+
+/// Parse manifest JSON. Icon `src` values are resolved against the manifest's own URL, per spec.
+pub(crate) fn parse_manifest(text: &str, manifest_url: &Url) -> Result<WebAppManifest, String> {
+    let json: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Invalid manifest JSON: {}", e))?;
+    let field = |key: &str| json.get(key).and_then(|v| v.as_str()).unwrap_or("").trim().to_string();
+
+    let icons = json
+        .get("icons")
+        .and_then(|v| v.as_array())
+        .map(|icons| {
+            icons
+                .iter()
+                .filter_map(|icon| {
+                    let src = icon.get("src")?.as_str()?;
+                    let src = manifest_url.join(src).map(|u| u.to_string()).ok()?;
+                    let sizes = icon.get("sizes").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                    let (width, height) = largest_size(&sizes);
+                    Some(ManifestIcon {
+                        src,
+                        sizes,
+                        width,
+                        height,
+                        mime_type: icon.get("type").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                        purpose: icon.get("purpose").and_then(|v| v.as_str()).unwrap_or("any").to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(WebAppManifest {
+        url: manifest_url.to_string(),
+        name: field("name"),
+        short_name: field("short_name"),
+        theme_color: field("theme_color"),
+        background_color: field("background_color"),
+        icons,
+    })
+}
+
+/// Fetch and parse the manifest linked from the page
+pub(crate) async fn fetch_manifest(client: &reqwest::Client, manifest_url: &Url) -> Result<WebAppManifest, String> {
+    let mut response = client
+        .get(manifest_url.as_str())
+        .header("User-Agent", fetch::USER_AGENT)
+        .header("Accept", "application/manifest+json,application/json;q=0.9,*/*;q=0.8")
+        .send()
+        .await
+        .map_err(|e| format!("Manifest request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Manifest request returned {}", response.status()));
+    }
+
+    if response.content_length().is_some_and(|len| len as usize > MAX_MANIFEST_BYTES) {
+        return Err(format!("Manifest is larger than {} bytes", MAX_MANIFEST_BYTES));
+    }
+    let (bytes, truncated) = fetch::read_capped(&mut response, MAX_MANIFEST_BYTES).await?;
+    if truncated {
+        return Err(format!("Manifest is larger than {} bytes", MAX_MANIFEST_BYTES));
+    }
+
+    parse_manifest(&String::from_utf8_lossy(&bytes), manifest_url)
+}
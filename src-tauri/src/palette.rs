@@ -0,0 +1,184 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+
+use crate::color::{self, ColorMatch, Deficiency, Rgb, SpotColor};
+
+/// Palette entries checked pairwise for color-vision conflicts (the top of the ranking)
+const MAX_CVD_COLORS: usize = 16;
+/// CIEDE2000 difference two colors need with typical vision to count as clearly distinct
+const MIN_DISTINCT_DELTA_E: f32 = 10.0;
+/// CIEDE2000 difference below which simulated colors are hard to tell apart. Kept well under
+/// `MIN_DISTINCT_DELTA_E` so pairs hovering near one threshold aren't reported.
+const MAX_CONFUSED_DELTA_E: f32 = 5.0;
+
+/// One color in the brand palette, tagged with where it came from
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct PaletteColor {
+    pub(crate) hex: String,
+    pub(crate) rgb: Rgb,
+    /// e.g. "manifest-theme", "manifest-background", "svg", "logo", "computed"
+    pub(crate) source: String,
+    /// Colors the site declares as its brand (manifest, guidelines) rather than ones inferred from styles
+    pub(crate) declared: bool,
+    /// Image URL or inline SVG id the color was extracted from; empty for page-level sources
+    #[serde(default)]
+    pub(crate) asset: String,
+    /// Name the site gives the color ("Midnight Blue"), when it spells one out
+    #[serde(default)]
+    pub(crate) name: String,
+    /// Naive CMYK percentages (no ICC profile)
+    #[serde(default)]
+    pub(crate) cmyk: [u8; 4],
+    /// Closest CSS named color
+    #[serde(default)]
+    pub(crate) nearest_css: Option<ColorMatch>,
+    /// Closest entry of the user's spot-color table, when one is loaded
+    #[serde(default)]
+    pub(crate) nearest_spot: Option<ColorMatch>,
+    /// "primary", "secondary" or "accent" for the colors a tint/shade scale is built from; empty otherwise
+    #[serde(default)]
+    pub(crate) role: String,
+}
+
+impl PaletteColor {
+    pub(crate) fn new(rgb: Rgb, source: &str, declared: bool) -> Self {
+        PaletteColor {
+            hex: rgb.to_hex(),
+            rgb,
+            source: source.to_string(),
+            declared,
+            asset: String::new(),
+            name: String::new(),
+            cmyk: rgb.to_cmyk(),
+            nearest_css: color::nearest_named(rgb),
+            nearest_spot: None,
+            role: String::new(),
+        }
+    }
+
+    pub(crate) fn with_asset(mut self, asset: &str) -> Self {
+        self.asset = asset.to_string();
+        self
+    }
+
+    pub(crate) fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+/// Two palette colors that look distinct but become hard to tell apart with a color vision deficiency
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct CvdConflict {
+    pub(crate) condition: Deficiency,
+    /// The two palette colors, in palette order
+    pub(crate) colors: [String; 2],
+    /// How they appear with the condition
+    pub(crate) simulated: [String; 2],
+    /// CIEDE2000 difference with typical vision
+    pub(crate) delta_e: f32,
+    /// CIEDE2000 difference with the condition
+    pub(crate) simulated_delta_e: f32,
+}
+
+/// Assemble the palette: declared colors first, then colors extracted from logo artwork (SVG, then raster), then
+/// browser computed styles (already sorted by usage). Each color appears once, keeping its highest-ranked source.
+pub(crate) fn build_palette(declared: Vec<PaletteColor>, extracted: Vec<PaletteColor>, computed: &[String]) -> Vec<PaletteColor> {
+    let mut seen: HashSet<Rgb> = HashSet::new();
+    let mut palette = Vec::new();
+    let computed = computed
+        .iter()
+        .filter_map(|c| color::parse_css_color(c))
+        .map(|rgb| PaletteColor::new(rgb, "computed", false));
+    for entry in declared.into_iter().chain(extracted).chain(computed) {
+        if seen.insert(entry.rgb) {
+            palette.push(entry);
+        }
+    }
+    palette
+}
+
+/// Attach the closest spot color from the user's reference table to every entry
+pub(crate) fn match_spot_colors(palette: &mut [PaletteColor], table: &[SpotColor]) {
+    for entry in palette {
+        entry.nearest_spot = color::nearest_spot(entry.rgb, table);
+    }
+}
+
+/// Pairs among the top palette colors that are distinguishable with typical vision but not under
+/// one of the simulated deficiencies, per condition in palette order
+pub(crate) fn cvd_conflicts(palette: &[PaletteColor]) -> Vec<CvdConflict> {
+    let colors: Vec<Rgb> = palette.iter().take(MAX_CVD_COLORS).map(|entry| entry.rgb).collect();
+    let mut conflicts = Vec::new();
+    for condition in Deficiency::ALL {
+        let simulated: Vec<Rgb> = colors.iter().map(|rgb| color::simulate(*rgb, condition)).collect();
+        for i in 0..colors.len() {
+            for j in i + 1..colors.len() {
+                let delta_e = color::delta_e(colors[i], colors[j]);
+                if delta_e < MIN_DISTINCT_DELTA_E {
+                    continue;
+                }
+                let simulated_delta_e = color::delta_e(simulated[i], simulated[j]);
+                if simulated_delta_e < MAX_CONFUSED_DELTA_E {
+                    conflicts.push(CvdConflict {
+                        condition,
+                        colors: [colors[i].to_hex(), colors[j].to_hex()],
+                        simulated: [simulated[i].to_hex(), simulated[j].to_hex()],
+                        delta_e,
+                        simulated_delta_e,
+                    });
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Brown-red and olive green: about 31 apart with typical vision, under 4 with protanopia
+    // or deuteranopia, still about 35 with tritanopia
+    const RED: &str = "#a05a00";
+    const GREEN: &str = "#5a7300";
+
+    fn palette(hexes: &[&str]) -> Vec<PaletteColor> {
+        hexes.iter().map(|hex| PaletteColor::new(color::parse_css_color(hex).unwrap(), "css", false)).collect()
+    }
+
+    fn conditions_for(conflicts: &[CvdConflict], a: &str, b: &str) -> Vec<Deficiency> {
+        conflicts.iter().filter(|c| c.colors == [a.to_string(), b.to_string()]).map(|c| c.condition).collect()
+    }
+
+    #[test]
+    fn flags_red_green_pairs_for_red_green_deficiencies() {
+        let conflicts = cvd_conflicts(&palette(&[RED, GREEN]));
+        let conditions = conditions_for(&conflicts, RED, GREEN);
+        assert!(conditions.contains(&Deficiency::Protanopia));
+        assert!(conditions.contains(&Deficiency::Deuteranopia));
+        assert!(!conditions.contains(&Deficiency::Tritanopia));
+        for conflict in &conflicts {
+            assert!(conflict.delta_e >= MIN_DISTINCT_DELTA_E && conflict.simulated_delta_e < MAX_CONFUSED_DELTA_E);
+        }
+    }
+
+    #[test]
+    fn skips_pairs_already_similar() {
+        let colors = palette(&[RED, "#a45c04"]);
+        assert!(color::delta_e(colors[0].rgb, colors[1].rgb) < MIN_DISTINCT_DELTA_E);
+        assert!(cvd_conflicts(&colors).is_empty());
+    }
+
+    #[test]
+    fn checks_only_the_top_colors() {
+        let grays: Vec<String> = (0..MAX_CVD_COLORS - 2).map(|i| format!("#{0:02x}{0:02x}{0:02x}", i * 17)).collect();
+        let mut hexes: Vec<&str> = grays.iter().map(String::as_str).collect();
+        hexes.extend([RED, GREEN]);
+        assert!(conditions_for(&cvd_conflicts(&palette(&hexes)), RED, GREEN).contains(&Deficiency::Protanopia));
+
+        // Pushed to 17th and 18th place
+        hexes.insert(0, "#ffffff");
+        assert!(conditions_for(&cvd_conflicts(&palette(&hexes)), RED, GREEN).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::fetch::{self, FetchError, UrlCache};
+use crate::image_header;
+use crate::ImageInfo;
+
+/// Bytes fetched per image — enough for every supported header except JPEGs with very large EXIF blocks
+const IMAGE_PREFIX_BYTES: usize = 16 * 1024;
+/// Images probed concurrently
+const PROBE_CONCURRENCY: usize = 8;
+/// Images probed per analysis; the rest are returned unprobed
+const MAX_PROBES: usize = 300;
+
+/// The first bytes of a remote file plus the response metadata we care about
+#[derive(Clone, Debug)]
+pub(crate) struct Prefix {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) content_type: String,
+    /// Full file size, from `Content-Range` (ranged response) or `Content-Length` (full response)
+    pub(crate) total_length: Option<u64>,
+}
+
+/// Fetch at most `max_bytes` from the start of a file with a `Range` request.
+/// Servers that ignore `Range` still only have `max_bytes` read before the body is dropped.
+pub(crate) async fn fetch_prefix(client: &reqwest::Client, url: &str, max_bytes: usize) -> Result<Prefix, FetchError> {
+    let mut response = client
+        .get(url)
+        .header("User-Agent", fetch::USER_AGENT)
+        .header("Range", format!("bytes=0-{}", max_bytes.saturating_sub(1)))
+        .send()
+        .await
+        .map_err(|e| FetchError::transient(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(FetchError::status(url, status));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+
+    let total_length = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit('/').next())
+            .and_then(|total| total.trim().parse().ok())
+    } else {
+        response.content_length()
+    };
+
+    let (bytes, _) = fetch::read_capped(&mut response, max_bytes).await.map_err(FetchError::transient)?;
+
+    Ok(Prefix { bytes, content_type, total_length })
+}
+
+/// What a probe learned about one image URL
+#[derive(Clone, Debug)]
+pub(crate) struct ImageProbe {
+    pub(crate) format: String,
+    /// Intrinsic size (0 when the header could not be read)
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) content_type: String,
+    pub(crate) content_length: Option<u64>,
+}
+
+/// Probe results shared across analyses. `None` records a URL that answered 4xx or is not an image.
+pub(crate) type ProbeCache = UrlCache<Option<ImageProbe>>;
+
+/// Fetch the start of an image and read its format and intrinsic size
+async fn probe_image(client: &reqwest::Client, url: &str) -> Result<ImageProbe, FetchError> {
+    let prefix = fetch_prefix(client, url, IMAGE_PREFIX_BYTES).await?;
+    let header = image_header::read_header(&prefix.bytes);
+    if header.is_none() && !prefix.content_type.starts_with("image/") {
+        return Err(FetchError::permanent(format!("{} is not an image ({})", url, prefix.content_type)));
+    }
+    let format = match header {
+        Some(h) => h.format.to_string(),
+        // `image/svg+xml` → "svg", `image/x-icon` → "x-icon"
+        None => prefix.content_type.trim_start_matches("image/").split('+').next().unwrap_or("").to_string(),
+    };
+    Ok(ImageProbe {
+        format,
+        width: header.map(|h| h.width).unwrap_or(0),
+        height: header.map(|h| h.height).unwrap_or(0),
+        content_type: prefix.content_type,
+        content_length: prefix.total_length,
+    })
+}
+
+/// Fill in real dimensions, format and file size for every image with a bounded number of
+/// requests in flight. Images whose URL is broken or doesn't serve an image are dropped; those
+/// that timed out or hit a server error are kept unprobed.
+pub(crate) async fn probe_images(images: Vec<ImageInfo>, cache: &ProbeCache) -> Vec<ImageInfo> {
+    let probeable = |image: &ImageInfo| image.src.starts_with("http://") || image.src.starts_with("https://");
+    let urls: Vec<String> = images.iter().filter(|image| probeable(image)).take(MAX_PROBES).map(|image| image.src.clone()).collect();
+    println!("[probe] Probing {} images", urls.len());
+    let results = fetch::fetch_each(urls, cache, PROBE_CONCURRENCY, |client, src| async move {
+        let action = |e: &FetchError| if e.permanent { "Dropping image" } else { "Keeping unprobed image" };
+        probe_image(&client, &src).await.inspect_err(|e| println!("[probe] {}: {}", action(e), e))
+    })
+    .await;
+
+    images
+        .into_iter()
+        .filter_map(|mut image| {
+            match results.get(&image.src) {
+                Some(Some(probe)) => {
+                    if probe.width > 0 && probe.height > 0 {
+                        image.width = probe.width;
+                        image.height = probe.height;
+                    }
+                    image.format = probe.format.clone();
+                    image.content_type = probe.content_type.clone();
+                    image.file_size = probe.content_length;
+                }
+                Some(None) => return None,
+                None => {}
+            }
+            Some(image)
+        })
+        .collect()
+}
@@ -0,0 +1,266 @@
+use serde::{Serialize, Deserialize};
+
+use crate::color::{self, Rgb, SpotColor};
+use crate::palette::PaletteColor;
+
+/// Roles given to the strongest distinct brand colors, in palette order
+const ROLES: &[&str] = &["primary", "secondary", "accent"];
+/// Below this OKLCH chroma a color reads as gray and gets no role
+const MIN_CHROMA: f32 = 0.04;
+/// Hues closer than this (degrees) are variants of one color rather than a second brand color
+const MIN_HUE_DISTANCE: f32 = 30.0;
+
+/// Ceiling on the 500 step's OKLCH chroma, about as vivid as Tailwind's most saturated 500s, so
+/// a pale or very dark base isn't extrapolated into a neon ramp
+const MAX_CHROMA_500: f32 = 0.27;
+
+/// Scale steps with their OKLCH lightness and chroma relative to the 500 step (Tailwind's curves)
+const STEPS: &[(u16, f32, f32)] = &[
+    (50, 0.971, 0.06),
+    (100, 0.936, 0.14),
+    (200, 0.885, 0.26),
+    (300, 0.808, 0.46),
+    (400, 0.704, 0.80),
+    (500, 0.637, 1.00),
+    (600, 0.577, 1.03),
+    (700, 0.505, 0.90),
+    (800, 0.444, 0.75),
+    (900, 0.396, 0.60),
+    (950, 0.258, 0.39),
+];
+
+/// One step of a tint/shade ramp
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ScaleStep {
+    /// 50–950
+    pub(crate) step: u16,
+    pub(crate) hex: String,
+    pub(crate) rgb: Rgb,
+    /// WCAG contrast ratio against white, 1–21
+    pub(crate) contrast_white: f32,
+    /// WCAG contrast ratio against black, 1–21
+    pub(crate) contrast_black: f32,
+}
+
+/// A 50–950 tint/shade ramp generated from one brand color
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ColorScale {
+    /// "primary", "secondary" or "accent"
+    pub(crate) role: String,
+    /// The brand color the ramp is built around
+    pub(crate) base: String,
+    /// Step the brand color sits at unchanged (nearest in lightness)
+    pub(crate) base_step: u16,
+    pub(crate) steps: Vec<ScaleStep>,
+}
+
+/// The ramps as ready-to-paste design tokens, each step commented with its print values
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct ScaleTokens {
+    /// `:root` block of custom properties, e.g. `--color-primary-500: #635bff; /* cmyk(61, 64, 0, 0) · ... */`
+    pub(crate) css: String,
+    /// Tailwind `theme.extend.colors` object, e.g. `primary: { 50: '#f5f3ff', // cmyk(...) ... }`
+    pub(crate) tailwind: String,
+}
+
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).abs() % 360.0;
+    d.min(360.0 - d)
+}
+
+/// Tag the first chromatic palette colors with distinct hues as primary, secondary and accent.
+/// The palette is ranked (declared, then logo artwork, then usage), so order decides the role.
+pub(crate) fn assign_roles(palette: &mut [PaletteColor]) {
+    let mut hues: Vec<f32> = Vec::new();
+    for entry in palette.iter_mut() {
+        if hues.len() == ROLES.len() {
+            break;
+        }
+        let [_, chroma, hue] = entry.rgb.to_oklch();
+        if chroma < MIN_CHROMA || hues.iter().any(|h| hue_distance(*h, hue) < MIN_HUE_DISTANCE) {
+            continue;
+        }
+        entry.role = ROLES[hues.len()].to_string();
+        hues.push(hue);
+    }
+}
+
+/// Relative chroma of the curve at `lightness`, linear between the two steps around it and held at
+/// the end steps beyond them
+fn relative_chroma_at(lightness: f32) -> f32 {
+    let (first, last) = (STEPS[0], STEPS[STEPS.len() - 1]);
+    if lightness >= first.1 {
+        return first.2;
+    }
+    if lightness <= last.1 {
+        return last.2;
+    }
+    STEPS
+        .windows(2)
+        .find(|pair| lightness <= pair[0].1 && lightness >= pair[1].1)
+        .map(|pair| {
+            let t = (pair[0].1 - lightness) / (pair[0].1 - pair[1].1);
+            pair[0].2 + (pair[1].2 - pair[0].2) * t
+        })
+        .unwrap_or(1.0)
+}
+
+/// Tint and shade ramp in OKLCH: lightness follows fixed targets so steps are perceptually even
+/// across hues, the base hue is kept, and chroma tapers toward both ends. The base color replaces
+/// the step nearest to it in lightness. The rest get the chroma the curve implies at the base's
+/// own lightness, interpolated between steps and capped at `MAX_CHROMA_500`.
+pub(crate) fn build_scale(base: Rgb, role: &str) -> ColorScale {
+    let [lightness, chroma, hue] = base.to_oklch();
+    let base_index = STEPS
+        .iter()
+        .enumerate()
+        .min_by(|a, b| (a.1 .1 - lightness).abs().total_cmp(&(b.1 .1 - lightness).abs()))
+        .map(|(index, _)| index)
+        .unwrap_or(5);
+    let chroma_500 = (chroma / relative_chroma_at(lightness)).min(MAX_CHROMA_500);
+
+    let white = Rgb::new(255, 255, 255);
+    let black = Rgb::new(0, 0, 0);
+    let steps = STEPS
+        .iter()
+        .enumerate()
+        .map(|(index, &(step, target, relative_chroma))| {
+            let rgb = if index == base_index { base } else { Rgb::from_oklch(target, chroma_500 * relative_chroma, hue) };
+            ScaleStep {
+                step,
+                hex: rgb.to_hex(),
+                rgb,
+                contrast_white: color::contrast_ratio(rgb, white),
+                contrast_black: color::contrast_ratio(rgb, black),
+            }
+        })
+        .collect();
+    ColorScale { role: role.to_string(), base: base.to_hex(), base_step: STEPS[base_index].0, steps }
+}
+
+/// A ramp for every palette color with a role, in role order
+pub(crate) fn build_scales(palette: &[PaletteColor]) -> Vec<ColorScale> {
+    ROLES
+        .iter()
+        .filter_map(|role| palette.iter().find(|entry| entry.role == *role))
+        .map(|entry| build_scale(entry.rgb, &entry.role))
+        .collect()
+}
+
+/// CMYK, nearest CSS name and nearest spot color (when a table is loaded) of one step
+fn print_note(rgb: Rgb, spot_colors: &[SpotColor]) -> String {
+    let [c, m, y, k] = rgb.to_cmyk();
+    let mut parts = vec![format!("cmyk({}, {}, {}, {})", c, m, y, k)];
+    if let Some(named) = color::nearest_named(rgb) {
+        parts.push(format!("≈ {} (ΔE {:.1})", named.name, named.delta_e));
+    }
+    if let Some(spot) = color::nearest_spot(rgb, spot_colors) {
+        parts.push(format!("spot {} (ΔE {:.1})", spot.name, spot.delta_e));
+    }
+    parts.join(" · ")
+}
+
+/// CSS custom properties and a Tailwind colors object for the ramps, matched against the
+/// spot-color table for the print comments
+pub(crate) fn scale_tokens(scales: &[ColorScale], spot_colors: &[SpotColor]) -> ScaleTokens {
+    if scales.is_empty() {
+        return ScaleTokens::default();
+    }
+    let mut css = String::from(":root {\n");
+    let mut tailwind = String::from("colors: {\n");
+    for scale in scales {
+        tailwind.push_str(&format!("  {}: {{\n", scale.role));
+        for step in &scale.steps {
+            let note = print_note(step.rgb, spot_colors);
+            css.push_str(&format!("  --color-{}-{}: {}; /* {} */\n", scale.role, step.step, step.hex, note));
+            tailwind.push_str(&format!("    {}: '{}', // {}\n", step.step, step.hex, note));
+        }
+        tailwind.push_str("  },\n");
+    }
+    css.push('}');
+    tailwind.push('}');
+    ScaleTokens { css, tailwind }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hex: &str) -> PaletteColor {
+        PaletteColor::new(color::parse_css_color(hex).unwrap(), "css", false)
+    }
+
+    #[test]
+    fn roles_skip_grays_and_near_duplicate_hues() {
+        let mut palette: Vec<PaletteColor> = ["#111827", "#635bff", "#7a73ff", "#f5f5f5", "#ff5a1f", "#00d4aa", "#e11d48"].iter().map(|hex| entry(hex)).collect();
+        assign_roles(&mut palette);
+        let roles: Vec<&str> = palette.iter().map(|entry| entry.role.as_str()).collect();
+        assert_eq!(roles, ["", "primary", "", "", "secondary", "accent", ""]);
+    }
+
+    #[test]
+    fn base_color_sits_unchanged_at_its_step() {
+        for hex in ["#635bff", "#0a2540", "#fde68a", "#16a34a"] {
+            let base = color::parse_css_color(hex).unwrap();
+            let scale = build_scale(base, "primary");
+            assert_eq!(scale.base, hex);
+            let at_base: Vec<&ScaleStep> = scale.steps.iter().filter(|step| step.step == scale.base_step).collect();
+            assert_eq!(at_base.len(), 1);
+            assert_eq!(at_base[0].hex, hex);
+        }
+        assert_eq!(build_scale(Rgb::new(0x3b, 0x82, 0xf6), "primary").base_step, 500);
+    }
+
+    #[test]
+    fn lightness_falls_from_50_to_950() {
+        for hex in ["#635bff", "#0a2540", "#fde68a", "#ff0000", "#16a34a"] {
+            let scale = build_scale(color::parse_css_color(hex).unwrap(), "primary");
+            let steps: Vec<u16> = scale.steps.iter().map(|step| step.step).collect();
+            assert_eq!(steps, STEPS.iter().map(|s| s.0).collect::<Vec<_>>());
+            let lightness: Vec<f32> = scale.steps.iter().map(|step| step.rgb.to_oklch()[0]).collect();
+            assert!(lightness.windows(2).all(|pair| pair[0] > pair[1]), "{}: {:?}", hex, lightness);
+        }
+    }
+
+    #[test]
+    fn chroma_is_capped() {
+        // Pale and very dark bases imply a huge 500 chroma when extrapolated along the curve. The
+        // cap applies to 500; only the 600 step's curve peaks slightly above it.
+        for hex in ["#fff0f0", "#1a0000", "#ff0000", "#00ff00"] {
+            let scale = build_scale(color::parse_css_color(hex).unwrap(), "accent");
+            for (step, &(_, _, relative)) in scale.steps.iter().zip(STEPS) {
+                if step.step == scale.base_step {
+                    continue;
+                }
+                let chroma = step.rgb.to_oklch()[1];
+                assert!(chroma <= MAX_CHROMA_500 * relative.max(1.0) + 0.01, "{} step {}: {}", hex, step.step, chroma);
+                if step.step == 500 {
+                    assert!(chroma <= MAX_CHROMA_500 + 0.01, "{} step 500: {}", hex, chroma);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tokens_list_every_step() {
+        let scales = [build_scale(Rgb::new(0x3b, 0x82, 0xf6), "primary"), build_scale(Rgb::new(0xff, 0x5a, 0x1f), "secondary")];
+        let tokens = scale_tokens(&scales, &[]);
+        assert!(tokens.css.starts_with(":root {\n  --color-primary-50: #"));
+        assert!(tokens.css.contains("  --color-primary-500: #3b82f6; /* cmyk(76, 47, 0, 4) · ≈ dodgerblue (ΔE 5.7) */\n"));
+        assert!(tokens.css.ends_with(" */\n}"));
+        assert_eq!(tokens.css.lines().filter(|line| line.starts_with("  --color-")).count(), 22);
+        assert!(!tokens.css.contains("spot"));
+        assert!(tokens.tailwind.starts_with("colors: {\n  primary: {\n    50: '#"));
+        assert!(tokens.tailwind.contains("    500: '#3b82f6', // cmyk(76, 47, 0, 4) · ≈ dodgerblue (ΔE 5.7)\n"));
+        assert!(tokens.tailwind.contains("  },\n  secondary: {\n"));
+        assert!(tokens.tailwind.ends_with("  },\n}"));
+
+        let spots = [SpotColor { name: "Brand Blue".to_string(), rgb: Rgb::new(0x3b, 0x82, 0xf6) }];
+        let tokens = scale_tokens(&scales[..1], &spots);
+        assert!(tokens.css.contains("#3b82f6; /* cmyk(76, 47, 0, 4) · ≈ dodgerblue (ΔE 5.7) · spot Brand Blue (ΔE 0.0) */\n"));
+        assert!(tokens.tailwind.contains("    500: '#3b82f6', // cmyk(76, 47, 0, 4) · ≈ dodgerblue (ΔE 5.7) · spot Brand Blue (ΔE 0.0)\n"));
+
+        let empty = scale_tokens(&[], &[]);
+        assert!(empty.css.is_empty() && empty.tailwind.is_empty());
+    }
+}
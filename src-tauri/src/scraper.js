@@ -24,34 +24,6 @@
         });
     }
 
-    // Aggressively normalize URLs to collapse all resolution/size variants into one
-    function normalizeUrl(src) {
-        if (!src) return '';
-        try { src = new URL(src, document.location.href).href; } catch (e) { }
-        // Strip query params and hash
-        let norm = src.split('?')[0].split('#')[0].replace(/\/+$/, '');
-        // Extract just the path (remove protocol + domain for comparison)
-        try { norm = new URL(norm).pathname; } catch (e) { }
-        // Get the filename portion
-        const lastSlash = norm.lastIndexOf('/');
-        const dir = norm.substring(0, lastSlash + 1);
-        let filename = norm.substring(lastSlash + 1);
-        // Get extension
-        const extMatch = filename.match(/\.(\w+)$/);
-        const ext = extMatch ? extMatch[0] : '';
-        let base = ext ? filename.slice(0, -ext.length) : filename;
-        // Strip ALL resolution/size patterns from the base filename:
-        base = base
-            .replace(/[-_](cc_ft_|ft_|uncropped_scaled_within_)\d+/gi, '')  // Zillow
-            .replace(/[-_]\d{2,4}x\d{2,4}/g, '')                           // -300x200
-            .replace(/@\dx/g, '')                                            // @2x
-            .replace(/[-_](small|medium|large|thumb|thumbnail|scaled|preview|mini|full|original|cropped)/gi, '')
-            .replace(/[-_]\d{3,4}w?$/g, '')                                 // _768, _384w
-            .replace(/[-_]\d{2,4}$/g, '')                                   // trailing numbers like _384
-            .replace(/[-_]+$/, '');                                          // clean trailing separators
-        return dir + base + ext;
-    }
-
     async function analyze() {
         await scrollPage();
 
@@ -70,6 +42,7 @@
         const allElements = document.querySelectorAll('*');
         const colorMap = {};
         const fontSet = new Set();
+        const stackMap = {};
 
         allElements.forEach(el => {
             const style = window.getComputedStyle(el);
@@ -83,6 +56,7 @@
             const font = style.fontFamily;
             if (font) {
                 font.split(',').forEach(f => fontSet.add(f.trim().replace(/['"]/g, '')));
+                stackMap[font] = (stackMap[font] || 0) + 1;
             }
         });
 
@@ -92,23 +66,69 @@
             .map(([c]) => c);
 
         data.fonts = Array.from(fontSet);
+        // Whole stacks, so the Rust side can tell the intended face from its fallbacks
+        data.font_stacks = Object.entries(stackMap)
+            .sort((a, b) => b[1] - a[1])
+            .slice(0, 50)
+            .map(([stack, count]) => ({ stack, count }));
 
-        // ── Image Extraction (deduplicated — one per visual image) ──
-        const seenNormalized = new Set();
+        // ── Image Extraction (one per URL — size variants are grouped on the Rust side) ──
+        const seenUrls = new Set();
         const imageList = [];
 
-        function addImage(rawSrc, alt, w, h) {
+        function addImage(rawSrc, alt, w, h, variants) {
             if (!rawSrc || rawSrc.startsWith('data:') || rawSrc.startsWith('blob:')) return;
             let src;
             try { src = new URL(rawSrc, document.location.href).href; } catch (e) { src = rawSrc; }
-            const norm = normalizeUrl(src);
-            if (seenNormalized.has(norm)) return;
-            seenNormalized.add(norm);
-            imageList.push({ src, alt: alt || '', width: w || 0, height: h || 0 });
+            const key = src.split('#')[0];
+            if (seenUrls.has(key)) return;
+            seenUrls.add(key);
+            imageList.push({ src, alt: alt || '', width: w || 0, height: h || 0, variants: variants || [] });
+        }
+
+        // srcset → [{ src, width, density, media, type }]. URLs may contain commas (image CDNs),
+        // so a URL runs to the next whitespace and only the descriptor ends at a comma.
+        function parseSrcset(srcset, media, type) {
+            const variants = [];
+            let rest = srcset || '';
+            while (true) {
+                rest = rest.replace(/^[\s,]+/, '');
+                if (!rest) break;
+                const urlEnd = rest.search(/\s/);
+                let url = urlEnd === -1 ? rest : rest.slice(0, urlEnd);
+                rest = urlEnd === -1 ? '' : rest.slice(urlEnd);
+                let descriptor = '';
+                if (url.endsWith(',')) {
+                    url = url.replace(/,+$/, '');
+                } else {
+                    const end = rest.indexOf(',');
+                    descriptor = (end === -1 ? rest : rest.slice(0, end)).trim();
+                    rest = end === -1 ? '' : rest.slice(end);
+                }
+                let src;
+                try { src = new URL(url, document.location.href).href; } catch (e) { continue; }
+                if (src.startsWith('data:')) continue;
+                variants.push({
+                    src,
+                    width: descriptor.endsWith('w') ? parseInt(descriptor, 10) || 0 : 0,
+                    density: descriptor.endsWith('x') ? parseFloat(descriptor) || 0 : 0,
+                    media: media || '',
+                    type: type || '',
+                });
+            }
+            return variants;
         }
 
-        // 1. All <img> tags — take only the BEST source per element (no srcset duplicates)
+        // 1. All <img> tags — one image per element, srcset/<picture> candidates as its variants
         document.querySelectorAll('img').forEach(img => {
+            const variants = [];
+            const picture = img.parentElement && img.parentElement.tagName === 'PICTURE' ? img.parentElement : null;
+            if (picture) {
+                picture.querySelectorAll(':scope > source').forEach(source => {
+                    variants.push(...parseSrcset(source.getAttribute('srcset') || source.getAttribute('data-srcset'), source.media, source.type));
+                });
+            }
+            variants.push(...parseSrcset(img.getAttribute('srcset') || img.getAttribute('data-srcset')));
             // currentSrc = what the browser actually loaded (best resolution for viewport)
             const bestSrc = img.currentSrc || img.src
                 || img.getAttribute('data-src')
@@ -116,12 +136,15 @@
                 || img.getAttribute('data-original')
                 || img.getAttribute('data-lazy')
                 || img.getAttribute('data-url')
-                || img.getAttribute('data-image');
-            addImage(bestSrc, img.alt, img.naturalWidth, img.naturalHeight);
+                || img.getAttribute('data-image')
+                || (variants[0] && variants[0].src);
+            addImage(bestSrc, img.alt, img.naturalWidth, img.naturalHeight, variants);
         });
 
-        // 2. <picture> <source> — take only the best (last/largest) from each srcset
+        // 2. <source> outside a <picture> with an <img> — take only the best (last/largest) from each srcset
         document.querySelectorAll('picture source, source[srcset]').forEach(source => {
+            const parent = source.parentElement;
+            if (parent && parent.tagName === 'PICTURE' && parent.querySelector(':scope > img')) return;
             const srcset = source.srcset || source.getAttribute('data-srcset');
             if (srcset) {
                 const parts = srcset.split(',').map(s => s.trim());
@@ -204,6 +227,49 @@
 
         data.text_content = textBlocks.slice(0, 200);
 
+        // ── Typography: computed text styles per role, the most common among the first visible matches ──
+        // A list is tried in order, moving on only when a selector finds nothing visible, so content
+        // links win over the nav and footer links that come first in the document
+        const typeRoles = {
+            body: 'body',
+            h1: 'h1', h2: 'h2', h3: 'h3', h4: 'h4', h5: 'h5', h6: 'h6',
+            button: 'button, [role="button"], input[type="submit"], .btn, .button',
+            link: ['main a[href]', 'article a[href]', 'p a[href]', 'a[href]'],
+        };
+        data.typography = [];
+        Object.entries(typeRoles).forEach(([role, selectors]) => {
+            const counts = {};
+            let sampled = 0;
+            for (const selector of [].concat(selectors)) {
+                if (sampled > 0) break;
+                for (const el of document.querySelectorAll(selector)) {
+                    if (sampled >= 30) break;
+                    if (role !== 'body') {
+                        const rect = el.getBoundingClientRect();
+                        if (rect.width === 0 || rect.height === 0 || !(el.textContent || el.value || '').trim()) continue;
+                    }
+                    sampled++;
+                    const s = window.getComputedStyle(el);
+                    const style = {
+                        font_family: s.fontFamily,
+                        font_size: s.fontSize,
+                        font_weight: s.fontWeight,
+                        line_height: s.lineHeight,
+                        letter_spacing: s.letterSpacing,
+                        text_transform: s.textTransform,
+                    };
+                    const key = JSON.stringify(style);
+                    counts[key] = counts[key] || { style, count: 0 };
+                    counts[key].count++;
+                }
+            }
+            const best = Object.values(counts).sort((a, b) => b.count - a.count)[0];
+            if (best) data.typography.push({ role, ...best.style, count: best.count });
+        });
+
+        // ── Rendered DOM for the Rust-side extractors (contacts, etc.) ──
+        data.html = document.documentElement.outerHTML.slice(0, 2000000);
+
         if (window.__TAURI__ && window.__TAURI__.core) {
             window.__TAURI__.core.invoke('complete_analysis', { data })
                 .then(() => console.log('Browser analysis sent — images: ' + data.images.length + ', text: ' + data.text_content.length))
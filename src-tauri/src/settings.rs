@@ -0,0 +1,104 @@
+use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
+
+use crate::color::SpotColor;
+
+/// User preferences kept across launches, stored as JSON in the app config folder
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub(crate) struct Settings {
+    /// Where downloads are written; `None` means the OS downloads folder
+    pub(crate) download_dir: Option<PathBuf>,
+    /// User-supplied spot-color reference table palette entries are matched against
+    pub(crate) spot_colors: Vec<SpotColor>,
+}
+
+/// A path typed into the settings, checked to be an absolute path to an existing folder
+pub(crate) fn existing_dir(path: &str) -> Result<PathBuf, String> {
+    let dir = absolute(path)?;
+    if !dir.exists() {
+        return Err(format!("Folder not found: {}", dir.display()));
+    }
+    if !dir.is_dir() {
+        return Err(format!("Not a folder: {}", dir.display()));
+    }
+    Ok(dir)
+}
+
+/// A path typed into the settings, checked to be an absolute path to an existing file
+pub(crate) fn existing_file(path: &str) -> Result<PathBuf, String> {
+    let file = absolute(path)?;
+    if !file.exists() {
+        return Err(format!("File not found: {}", file.display()));
+    }
+    if !file.is_file() {
+        return Err(format!("Not a file: {}", file.display()));
+    }
+    Ok(file)
+}
+
+/// Relative paths would resolve against wherever the app was launched from
+fn absolute(path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path.trim());
+    if path.is_absolute() {
+        Ok(path)
+    } else {
+        Err(format!("Enter a full path: {}", path.display()))
+    }
+}
+
+/// Saved settings, or defaults when the file is missing or unreadable
+pub(crate) fn load(path: &Path) -> Settings {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Settings::default(),
+        Err(e) => {
+            println!("[settings] Failed to read {}: {}", path.display(), e);
+            return Settings::default();
+        }
+    };
+    match serde_json::from_str(&text) {
+        Ok(settings) => settings,
+        Err(e) => {
+            println!("[settings] Ignoring invalid {}: {}", path.display(), e);
+            Settings::default()
+        }
+    }
+}
+
+/// Write the settings through a temporary file, so a crash mid-write never leaves a truncated file
+pub(crate) fn save(path: &Path, settings: &Settings) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, json).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    std::fs::rename(&temp, path).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_paths_must_exist() {
+        let dir = std::env::temp_dir().join(format!("brandsnap-settings-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("spots.csv");
+        std::fs::write(&file, "name,hex\n").unwrap();
+        let text = |path: &Path| path.to_string_lossy().to_string();
+
+        assert_eq!(existing_dir(&format!("  {}  ", text(&dir))), Ok(dir.clone()));
+        assert_eq!(existing_file(&text(&file)), Ok(file.clone()));
+        assert_eq!(existing_dir(&text(&file)), Err(format!("Not a folder: {}", file.display())));
+        assert_eq!(existing_file(&text(&dir)), Err(format!("Not a file: {}", dir.display())));
+        let missing = dir.join("missing");
+        assert_eq!(existing_dir(&text(&missing)), Err(format!("Folder not found: {}", missing.display())));
+        assert_eq!(existing_file(&text(&missing)), Err(format!("File not found: {}", missing.display())));
+        assert_eq!(existing_dir("Downloads"), Err("Enter a full path: Downloads".to_string()));
+        assert_eq!(existing_file(""), Err("Enter a full path: ".to_string()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
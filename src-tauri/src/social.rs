@@ -0,0 +1,270 @@
+use scraper::{Html, Selector};
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use url::Url;
+
+use crate::jsonld;
+
+/// A brand's account on a social platform, normalized to a canonical profile URL
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct SocialProfile {
+    pub(crate) platform: String,
+    pub(crate) handle: String,
+    pub(crate) url: String,
+    /// Where the profile was found: "json-ld", "twitter-meta" or "link"
+    pub(crate) source: String,
+}
+
+/// JSON-LD types whose `sameAs` lists describe the site owner (not an article author)
+pub(crate) const ORGANIZATION_TYPES: &[&str] = &[
+    "Organization", "Corporation", "LocalBusiness", "Brand", "OnlineStore", "Store",
+    "NGO", "EducationalOrganization", "NewsMediaOrganization", "WebSite",
+];
+
+/// First path segments that never name a profile (share dialogs, auth, legal pages, ...)
+const RESERVED_SEGMENTS: &[&str] = &[
+    "share", "sharer", "sharer.php", "intent", "dialog", "plugins", "hashtag", "search",
+    "explore", "home", "login", "signup", "privacy", "policies", "policy", "legal", "help",
+    "about", "tos", "terms", "settings", "i", "shareArticle", "sharing", "submit", "watch",
+    "embed", "tr", "events", "groups", "pin", "p", "reel", "reels", "stories", "status",
+];
+
+/// Parse a profile URL into (platform, handle, canonical URL).
+/// Returns `None` for share-intent links, posts, and hosts that aren't a known platform.
+pub(crate) fn parse_profile_url(raw: &str) -> Option<(String, String, String)> {
+    let parsed = Url::parse(raw.trim()).ok()?;
+    let host = parsed.host_str()?.to_lowercase();
+    let host = host
+        .trim_start_matches("www.")
+        .trim_start_matches("m.")
+        .trim_start_matches("mobile.");
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|s| s.filter(|seg| !seg.is_empty()).collect())
+        .unwrap_or_default();
+    let first = *segments.first()?;
+    if RESERVED_SEGMENTS.iter().any(|r| r.eq_ignore_ascii_case(first)) {
+        return None;
+    }
+
+    // Platforms whose profile is the first path segment (optionally prefixed with '@')
+    let simple = |platform: &str, canonical: &str, max_segments: usize| {
+        if segments.len() > max_segments {
+            return None;
+        }
+        let handle = first.trim_start_matches('@');
+        if handle.is_empty() || !handle.chars().all(|c| c.is_alphanumeric() || "._-".contains(c)) {
+            return None;
+        }
+        Some((platform.to_string(), handle.to_string(), format!("{}{}", canonical, handle)))
+    };
+
+    match host {
+        "twitter.com" | "x.com" => simple("x", "https://x.com/", 1),
+        "facebook.com" | "fb.com" => {
+            if first.eq_ignore_ascii_case("profile.php") {
+                let id = parsed.query_pairs().find(|(k, _)| k == "id")?.1.to_string();
+                Some(("facebook".into(), id.clone(), format!("https://www.facebook.com/profile.php?id={}", id)))
+            } else {
+                simple("facebook", "https://www.facebook.com/", 1)
+            }
+        }
+        "instagram.com" => simple("instagram", "https://www.instagram.com/", 1),
+        "threads.net" | "threads.com" => simple("threads", "https://www.threads.net/@", 1),
+        "tiktok.com" if first.starts_with('@') => simple("tiktok", "https://www.tiktok.com/@", 1),
+        "pinterest.com" => simple("pinterest", "https://www.pinterest.com/", 1),
+        "github.com" => simple("github", "https://github.com/", 1),
+        "medium.com" if first.starts_with('@') => simple("medium", "https://medium.com/@", 1),
+        "dribbble.com" => simple("dribbble", "https://dribbble.com/", 1),
+        "behance.net" => simple("behance", "https://www.behance.net/", 1),
+        "twitch.tv" => simple("twitch", "https://www.twitch.tv/", 1),
+        "vimeo.com" if !first.chars().all(|c| c.is_ascii_digit()) => simple("vimeo", "https://vimeo.com/", 1),
+        "t.me" | "telegram.me" => simple("telegram", "https://t.me/", 1),
+        "discord.gg" => simple("discord", "https://discord.gg/", 1),
+        "bsky.app" if first == "profile" && segments.len() == 2 => {
+            let handle = segments[1];
+            Some(("bluesky".into(), handle.to_string(), format!("https://bsky.app/profile/{}", handle)))
+        }
+        "linkedin.com" if segments.len() >= 2 && ["company", "in", "school", "showcase"].contains(&first) => {
+            let handle = segments[1];
+            Some(("linkedin".into(), handle.to_string(), format!("https://www.linkedin.com/{}/{}", first, handle)))
+        }
+        "youtube.com" => {
+            if first.starts_with('@') {
+                simple("youtube", "https://www.youtube.com/@", 2)
+            } else if segments.len() >= 2 && ["channel", "c", "user"].contains(&first) {
+                let handle = segments[1];
+                Some(("youtube".into(), handle.to_string(), format!("https://www.youtube.com/{}/{}", first, handle)))
+            } else {
+                None
+            }
+        }
+        "reddit.com" if segments.len() >= 2 && ["r", "user", "u"].contains(&first) => {
+            let handle = segments[1];
+            let kind = if first == "r" { "r" } else { "user" };
+            Some(("reddit".into(), handle.to_string(), format!("https://www.reddit.com/{}/{}", kind, handle)))
+        }
+        "snapchat.com" if first == "add" && segments.len() == 2 => {
+            let handle = segments[1];
+            Some(("snapchat".into(), handle.to_string(), format!("https://www.snapchat.com/add/{}", handle)))
+        }
+        _ => None,
+    }
+}
+
+fn profile_key(profile: &SocialProfile) -> (String, String) {
+    (profile.platform.clone(), profile.handle.to_lowercase())
+}
+
+/// Append the profiles from `other` that aren't in `profiles` yet (same platform and handle)
+pub(crate) fn merge_profiles(profiles: &mut Vec<SocialProfile>, other: Vec<SocialProfile>) {
+    let mut seen: HashSet<(String, String)> = profiles.iter().map(profile_key).collect();
+    for profile in other {
+        if seen.insert(profile_key(&profile)) {
+            profiles.push(profile);
+        }
+    }
+}
+
+/// Extract social profiles from JSON-LD `sameAs`, `twitter:site`/`twitter:creator` and `a[href]` links.
+/// Structured data comes first so it wins when the same account is also linked in the footer.
+pub(crate) fn extract_social_profiles(document: &Html, base_url: &Url) -> Vec<SocialProfile> {
+    let mut profiles: Vec<SocialProfile> = Vec::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+
+    let mut push = |raw: &str, source: &str| {
+        if let Some((platform, handle, url)) = parse_profile_url(raw) {
+            if seen.insert((platform.clone(), handle.to_lowercase())) {
+                profiles.push(SocialProfile { platform, handle, url, source: source.to_string() });
+            }
+        }
+    };
+
+    // ── JSON-LD sameAs on the site's Organization / WebSite ──
+    for block in jsonld::blocks(document) {
+        jsonld::walk_objects(&block, &mut |obj| {
+            if jsonld::has_type(obj, ORGANIZATION_TYPES) {
+                if let Some(same_as) = obj.get("sameAs") {
+                    for link in jsonld::strings(same_as) {
+                        push(&link, "json-ld");
+                    }
+                }
+            }
+        });
+    }
+
+    // ── Twitter card handles ──
+    if let Ok(selector) = Selector::parse("meta[name='twitter:site'], meta[name='twitter:creator'], meta[property='twitter:site']") {
+        for el in document.select(&selector) {
+            if let Some(content) = el.value().attr("content") {
+                let handle = content.trim().trim_start_matches('@');
+                if handle.is_empty() {
+                    continue;
+                }
+                if handle.starts_with("http") {
+                    push(handle, "twitter-meta");
+                } else {
+                    push(&format!("https://x.com/{}", handle), "twitter-meta");
+                }
+            }
+        }
+    }
+
+    // ── Plain links (header, footer, contact pages) ──
+    if let Ok(selector) = Selector::parse("a[href]") {
+        for el in document.select(&selector) {
+            if let Some(href) = el.value().attr("href") {
+                if let Ok(full) = base_url.join(href) {
+                    push(full.as_str(), "link");
+                }
+            }
+        }
+    }
+
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(url: &str) -> Option<(String, String, String)> {
+        parse_profile_url(url)
+    }
+
+    fn profile(platform: &str, handle: &str, url: &str) -> Option<(String, String, String)> {
+        Some((platform.to_string(), handle.to_string(), url.to_string()))
+    }
+
+    #[test]
+    fn canonicalizes_first_segment_profiles() {
+        assert_eq!(parsed("https://twitter.com/BrandHQ"), profile("x", "BrandHQ", "https://x.com/BrandHQ"));
+        assert_eq!(parsed("https://mobile.x.com/@brandhq/"), profile("x", "brandhq", "https://x.com/brandhq"));
+        assert_eq!(parsed("https://m.facebook.com/brand.page"), profile("facebook", "brand.page", "https://www.facebook.com/brand.page"));
+        assert_eq!(parsed("http://instagram.com/brand_co"), profile("instagram", "brand_co", "https://www.instagram.com/brand_co"));
+        assert_eq!(parsed("https://www.threads.net/@brand"), profile("threads", "brand", "https://www.threads.net/@brand"));
+        assert_eq!(parsed("https://www.tiktok.com/@brand"), profile("tiktok", "brand", "https://www.tiktok.com/@brand"));
+        assert_eq!(parsed("https://github.com/brand-inc"), profile("github", "brand-inc", "https://github.com/brand-inc"));
+        assert_eq!(parsed("https://t.me/brandchat"), profile("telegram", "brandchat", "https://t.me/brandchat"));
+    }
+
+    #[test]
+    fn canonicalizes_nested_profiles() {
+        assert_eq!(
+            parsed("https://www.facebook.com/profile.php?id=1000123&ref=footer"),
+            profile("facebook", "1000123", "https://www.facebook.com/profile.php?id=1000123")
+        );
+        assert_eq!(parsed("https://www.linkedin.com/company/brand-inc/about/"), profile("linkedin", "brand-inc", "https://www.linkedin.com/company/brand-inc"));
+        assert_eq!(parsed("https://www.youtube.com/@brand/videos"), profile("youtube", "brand", "https://www.youtube.com/@brand"));
+        assert_eq!(parsed("https://youtube.com/channel/UC123abc"), profile("youtube", "UC123abc", "https://www.youtube.com/channel/UC123abc"));
+        assert_eq!(parsed("https://bsky.app/profile/brand.bsky.social"), profile("bluesky", "brand.bsky.social", "https://bsky.app/profile/brand.bsky.social"));
+        assert_eq!(parsed("https://www.reddit.com/r/brand/"), profile("reddit", "brand", "https://www.reddit.com/r/brand"));
+        assert_eq!(parsed("https://www.reddit.com/u/brand"), profile("reddit", "brand", "https://www.reddit.com/user/brand"));
+        assert_eq!(parsed("https://www.snapchat.com/add/brand"), profile("snapchat", "brand", "https://www.snapchat.com/add/brand"));
+    }
+
+    #[test]
+    fn drops_tracking_query_strings() {
+        assert_eq!(
+            parsed("https://www.instagram.com/brand/?utm_source=site&igshid=abc123"),
+            profile("instagram", "brand", "https://www.instagram.com/brand")
+        );
+        assert_eq!(parsed("https://x.com/brand?ref_src=twsrc%5Etfw#top"), profile("x", "brand", "https://x.com/brand"));
+    }
+
+    #[test]
+    fn rejects_share_links_and_posts() {
+        for url in [
+            "https://twitter.com/intent/tweet?text=hi",
+            "https://twitter.com/share?url=https://brand.example",
+            "https://www.facebook.com/sharer/sharer.php?u=https://brand.example",
+            "https://www.linkedin.com/shareArticle?mini=true",
+            "https://www.instagram.com/p/Cx12ab/",
+            "https://www.instagram.com/reel/Cx12ab/",
+            "https://x.com/brand/status/1234567890",
+            "https://www.youtube.com/watch?v=abc",
+            "https://pinterest.com/pin/create/button/",
+            "https://www.tiktok.com/tag/brand",
+            "https://vimeo.com/123456",
+            "https://x.com/brand%20name",
+            "https://brand.example/brand",
+            "not a url",
+        ] {
+            assert_eq!(parsed(url), None, "{}", url);
+        }
+    }
+
+    #[test]
+    fn merges_profiles_by_platform_and_handle() {
+        let one = |platform: &str, handle: &str, source: &str| SocialProfile {
+            platform: platform.to_string(),
+            handle: handle.to_string(),
+            url: String::new(),
+            source: source.to_string(),
+        };
+        let mut profiles = vec![one("x", "Brand", "json-ld")];
+        merge_profiles(&mut profiles, vec![one("x", "brand", "link"), one("github", "brand", "link")]);
+        let merged: Vec<(&str, &str)> = profiles.iter().map(|p| (p.platform.as_str(), p.source.as_str())).collect();
+        assert_eq!(merged, [("x", "json-ld"), ("github", "link")]);
+    }
+}
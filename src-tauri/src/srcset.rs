@@ -0,0 +1,187 @@
+use scraper::ElementRef;
+use serde::{Serialize, Deserialize};
+use url::Url;
+
+use crate::ImageInfo;
+
+/// One candidate of a responsive image (`srcset` entry, `<picture><source>` or the plain `src`)
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct ImageVariant {
+    pub(crate) src: String,
+    /// `w` descriptor (0 when the candidate has none)
+    #[serde(default)]
+    pub(crate) width: u32,
+    /// `x` descriptor (0 when the candidate has none)
+    #[serde(default)]
+    pub(crate) density: f32,
+    /// `media` condition of the `<picture><source>` it came from
+    #[serde(default)]
+    pub(crate) media: String,
+    /// `type` of the `<picture><source>` it came from, e.g. "image/avif"
+    #[serde(default, rename = "type")]
+    pub(crate) mime_type: String,
+}
+
+/// Parse a `srcset` attribute. URLs may contain commas (`w_400,h_300` on image CDNs), so a
+/// candidate's URL runs to the next whitespace and only the descriptors end at a comma.
+pub(crate) fn parse_srcset(srcset: &str, base_url: &Url, media: &str, mime_type: &str) -> Vec<ImageVariant> {
+    let mut variants = Vec::new();
+    let mut rest = srcset;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            break;
+        }
+        let url_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let mut raw_url = &rest[..url_end];
+        rest = &rest[url_end..];
+        let mut descriptor = "";
+        if raw_url.ends_with(',') {
+            raw_url = raw_url.trim_end_matches(',');
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            descriptor = rest[..end].trim();
+            rest = &rest[end..];
+        }
+
+        let Ok(url) = base_url.join(raw_url) else { continue };
+        if url.scheme() == "data" {
+            continue;
+        }
+        let mut variant = ImageVariant { src: url.to_string(), media: media.to_string(), mime_type: mime_type.to_string(), ..Default::default() };
+        if let Some(w) = descriptor.strip_suffix('w') {
+            variant.width = w.trim().parse().unwrap_or(0);
+        } else if let Some(x) = descriptor.strip_suffix('x') {
+            variant.density = x.trim().parse().unwrap_or(0.0);
+        }
+        variants.push(variant);
+    }
+    variants
+}
+
+/// Every `srcset` candidate for an `<img>`: its own `srcset`/`data-srcset` plus, inside a
+/// `<picture>`, each `<source>` with its media condition and type
+pub(crate) fn img_variants(img: ElementRef, base_url: &Url) -> Vec<ImageVariant> {
+    let mut variants = Vec::new();
+    if let Some(picture) = img.parent().and_then(ElementRef::wrap).filter(|p| p.value().name() == "picture") {
+        for source in picture.children().filter_map(ElementRef::wrap).filter(|s| s.value().name() == "source") {
+            let el = source.value();
+            if let Some(srcset) = el.attr("srcset").or(el.attr("data-srcset")) {
+                variants.extend(parse_srcset(srcset, base_url, el.attr("media").unwrap_or(""), el.attr("type").unwrap_or("")));
+            }
+        }
+    }
+    for attr in ["srcset", "data-srcset"] {
+        if let Some(srcset) = img.value().attr(attr) {
+            variants.extend(parse_srcset(srcset, base_url, "", ""));
+        }
+    }
+    variants
+}
+
+/// Add candidates that aren't already in the list (by URL)
+pub(crate) fn merge_variants(into: &mut Vec<ImageVariant>, from: Vec<ImageVariant>) {
+    for variant in from {
+        if !into.iter().any(|v| v.src == variant.src) {
+            into.push(variant);
+        }
+    }
+}
+
+/// Width descriptors outrank densities; a candidate without either counts as 1x
+fn rank(variant: &ImageVariant) -> (u32, f32) {
+    (variant.width, if variant.density > 0.0 { variant.density } else { 1.0 })
+}
+
+/// Make the largest candidate the image's `src` (the default download).
+/// The original `src` is kept in the list so no candidate is lost.
+pub(crate) fn promote_largest(image: &mut ImageInfo) {
+    if image.variants.is_empty() {
+        return;
+    }
+    if !image.variants.iter().any(|v| v.src == image.src) {
+        image.variants.push(ImageVariant { src: image.src.clone(), ..Default::default() });
+    }
+    // Ties keep the current `src`
+    let current = image.variants.iter().find(|v| v.src == image.src);
+    let largest = image
+        .variants
+        .iter()
+        .fold(current, |best, v| match best {
+            Some(b) if rank(v).partial_cmp(&rank(b)) != Some(std::cmp::Ordering::Greater) => Some(b),
+            _ => Some(v),
+        });
+    if let Some(largest) = largest {
+        image.src = largest.src.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://example.com/blog/post").unwrap()
+    }
+
+    fn variant(src: &str, width: u32, density: f32) -> ImageVariant {
+        ImageVariant { src: src.to_string(), width, density, ..Default::default() }
+    }
+
+    #[test]
+    fn parses_descriptors_and_resolves_urls() {
+        let variants = parse_srcset("small.jpg 480w, /img/large.jpg 1080w,hero@2x.jpg 2x, plain.jpg", &base(), "(min-width: 600px)", "image/jpeg");
+        let parsed: Vec<(&str, u32, f32)> = variants.iter().map(|v| (v.src.as_str(), v.width, v.density)).collect();
+        assert_eq!(parsed, [
+            ("https://example.com/blog/small.jpg", 480, 0.0),
+            ("https://example.com/img/large.jpg", 1080, 0.0),
+            ("https://example.com/blog/hero@2x.jpg", 0, 2.0),
+            ("https://example.com/blog/plain.jpg", 0, 0.0),
+        ]);
+        assert!(variants.iter().all(|v| v.media == "(min-width: 600px)" && v.mime_type == "image/jpeg"));
+    }
+
+    #[test]
+    fn keeps_commas_inside_urls() {
+        let variants = parse_srcset(
+            "https://cdn.test/w_400,h_300/a.jpg 400w, https://cdn.test/w_800,h_600/a.jpg 800w",
+            &base(),
+            "",
+            "",
+        );
+        let srcs: Vec<&str> = variants.iter().map(|v| v.src.as_str()).collect();
+        assert_eq!(srcs, ["https://cdn.test/w_400,h_300/a.jpg", "https://cdn.test/w_800,h_600/a.jpg"]);
+        assert_eq!(variants[1].width, 800);
+    }
+
+    #[test]
+    fn skips_data_urls_and_junk() {
+        let variants = parse_srcset(" , data:image/gif;base64,R0lGOD 1x, a.png, b.png 2x ,", &base(), "", "");
+        let srcs: Vec<&str> = variants.iter().map(|v| v.src.as_str()).collect();
+        assert_eq!(srcs, ["https://example.com/blog/a.png", "https://example.com/blog/b.png"]);
+        assert_eq!(variants[1].density, 2.0);
+    }
+
+    #[test]
+    fn promotes_the_largest_candidate() {
+        let mut image = ImageInfo::new("https://example.com/a-1x.jpg".into(), String::new(), 0, 0);
+        image.variants = vec![variant("https://example.com/a-2x.jpg", 0, 2.0), variant("https://example.com/a-800.jpg", 800, 0.0)];
+        promote_largest(&mut image);
+        assert_eq!(image.src, "https://example.com/a-800.jpg");
+        assert!(image.variants.iter().any(|v| v.src == "https://example.com/a-1x.jpg"));
+
+        // Ties keep the current src
+        let mut tied = ImageInfo::new("https://example.com/b.jpg".into(), String::new(), 0, 0);
+        tied.variants = vec![variant("https://example.com/b-1x.jpg", 0, 1.0)];
+        promote_largest(&mut tied);
+        assert_eq!(tied.src, "https://example.com/b.jpg");
+    }
+
+    #[test]
+    fn merges_new_candidates_only() {
+        let mut variants = vec![variant("https://example.com/a.jpg", 400, 0.0)];
+        merge_variants(&mut variants, vec![variant("https://example.com/a.jpg", 0, 0.0), variant("https://example.com/b.jpg", 800, 0.0)]);
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0].width, 400);
+    }
+}
@@ -0,0 +1,160 @@
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use std::ops::Range;
+use url::Url;
+
+use crate::css::{self, CssRule};
+use crate::fetch;
+
+/// Stylesheets fetched per page (linked + one level of `@import`)
+const MAX_STYLESHEETS: usize = 20;
+/// Larger stylesheets are truncated
+const MAX_STYLESHEET_BYTES: usize = 3_000_000;
+
+/// CSS text with the URL it came from (the page URL for inline `<style>` blocks)
+#[derive(Clone, Debug)]
+pub(crate) struct Stylesheet {
+    pub(crate) url: String,
+    pub(crate) inline: bool,
+    pub(crate) text: String,
+}
+
+/// Rules of every stylesheet, parsed once and kept in cascade order
+pub(crate) struct ParsedSheets<'a> {
+    pub(crate) sheets: &'a [Stylesheet],
+    pub(crate) rules: Vec<CssRule>,
+    /// Slice of `rules` each stylesheet contributed, by stylesheet index
+    ranges: Vec<Range<usize>>,
+}
+
+impl<'a> ParsedSheets<'a> {
+    pub(crate) fn parse(sheets: &'a [Stylesheet]) -> Self {
+        let mut rules = Vec::new();
+        let mut ranges = Vec::new();
+        for sheet in sheets {
+            let start = rules.len();
+            rules.extend(css::parse_rules(&sheet.text));
+            ranges.push(start..rules.len());
+        }
+        ParsedSheets { sheets, rules, ranges }
+    }
+
+    /// Each stylesheet with its own rules
+    pub(crate) fn per_sheet(&self) -> impl Iterator<Item = (&Stylesheet, &[CssRule])> {
+        self.sheets.iter().zip(&self.ranges).map(|(sheet, range)| (sheet, &self.rules[range.clone()]))
+    }
+}
+
+/// A stylesheet reference at its place in the document: a `<link>` still to fetch or a `<style>` block
+#[derive(Clone, Debug)]
+pub(crate) enum SheetSource {
+    Linked(Url),
+    Inline(Stylesheet),
+}
+
+/// `<link rel="stylesheet">` targets (resolved against the page) and `<style>` blocks, in document order
+pub(crate) fn stylesheet_sources(document: &Html, base_url: &Url) -> Vec<SheetSource> {
+    let mut sources = Vec::new();
+    let Ok(selector) = Selector::parse("link[rel~='stylesheet'][href], style") else { return sources };
+    for el in document.select(&selector) {
+        if el.value().name() == "style" {
+            let text = el.text().collect::<String>();
+            if !text.trim().is_empty() {
+                sources.push(SheetSource::Inline(Stylesheet { url: base_url.to_string(), inline: true, text }));
+            }
+        } else if let Some(url) = el.value().attr("href").and_then(|href| base_url.join(href).ok()) {
+            if !sources.iter().any(|source| matches!(source, SheetSource::Linked(linked) if *linked == url)) {
+                sources.push(SheetSource::Linked(url));
+            }
+        }
+    }
+    sources
+}
+
+async fn fetch_css(client: &reqwest::Client, url: &Url) -> Result<String, String> {
+    let mut response = client
+        .get(url.as_str())
+        .header("User-Agent", fetch::USER_AGENT)
+        .header("Accept", "text/css,*/*;q=0.1")
+        .send()
+        .await
+        .map_err(|e| format!("Stylesheet request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    // Oversized sheets are truncated; a character split at the cut decodes as U+FFFD
+    let (bytes, _) = fetch::read_capped(&mut response, MAX_STYLESHEET_BYTES).await?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Fetch `urls` concurrently, keeping their order. Failed fetches are logged and left out.
+async fn fetch_all(client: &reqwest::Client, urls: Vec<Url>) -> Vec<Stylesheet> {
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, url) in urls.into_iter().enumerate() {
+        let client = client.clone();
+        tasks.spawn(async move {
+            let result = fetch_css(&client, &url).await;
+            (index, url, result)
+        });
+    }
+    let mut fetched: Vec<(usize, Stylesheet)> = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, url, Ok(text))) => fetched.push((index, Stylesheet { url: url.to_string(), inline: false, text })),
+            Ok((_, url, Err(e))) => println!("[stylesheets] Skipping {}: {}", url, e),
+            Err(e) => println!("[stylesheets] Fetch task failed: {}", e),
+        }
+    }
+    fetched.sort_by_key(|(index, _)| *index);
+    fetched.into_iter().map(|(_, sheet)| sheet).collect()
+}
+
+/// `@import` targets of a sheet, resolved against it
+fn import_urls(sheet: &Stylesheet) -> Vec<Url> {
+    let Ok(base) = Url::parse(&sheet.url) else { return Vec::new() };
+    css::imports(&sheet.text).iter().filter_map(|import| base.join(import).ok()).collect()
+}
+
+/// Fetch the linked stylesheets, then one level of the `@import`s they (and the inline blocks)
+/// pull in, and return every sheet in cascade order: each sheet where its `<link>` or `<style>`
+/// sits in the document, preceded by the sheets it imports, so later rules win.
+pub(crate) async fn fetch_stylesheets(client: &reqwest::Client, sources: Vec<SheetSource>) -> Vec<Stylesheet> {
+    let mut seen: HashSet<Url> = HashSet::new();
+    let mut claim = |url: &Url| seen.len() < MAX_STYLESHEETS && seen.insert(url.clone());
+
+    let linked: Vec<Url> = sources
+        .iter()
+        .filter_map(|source| match source {
+            SheetSource::Linked(url) => Some(url.clone()),
+            SheetSource::Inline(_) => None,
+        })
+        .filter(|url| claim(url))
+        .collect();
+    let mut fetched = fetch_all(client, linked).await;
+
+    // Each sheet in document order, with the imports it declares
+    let mut sheets: Vec<(Stylesheet, Vec<Url>)> = Vec::new();
+    for source in sources {
+        let sheet = match source {
+            SheetSource::Inline(sheet) => sheet,
+            SheetSource::Linked(url) => match fetched.iter().position(|sheet| sheet.url == url.as_str()) {
+                Some(index) => fetched.remove(index),
+                None => continue,
+            },
+        };
+        let imports = import_urls(&sheet).into_iter().filter(|url| claim(url)).collect();
+        sheets.push((sheet, imports));
+    }
+
+    let mut imported = fetch_all(client, sheets.iter().flat_map(|(_, imports)| imports.clone()).collect()).await;
+    let mut ordered = Vec::new();
+    for (sheet, imports) in sheets {
+        for url in imports {
+            if let Some(index) = imported.iter().position(|import| import.url == url.as_str()) {
+                ordered.push(imported.remove(index));
+            }
+        }
+        ordered.push(sheet);
+    }
+    ordered
+}
@@ -0,0 +1,405 @@
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::css;
+use crate::logo;
+use crate::svg_colors::{self, SvgColor};
+
+/// Inline SVGs larger than this are skipped (usually illustrations or charts, not brand marks)
+const MAX_SVG_BYTES: usize = 200_000;
+/// Maximum nesting of `<use>` references we will inline
+const MAX_USE_DEPTH: usize = 4;
+
+/// A standalone, sanitized copy of an inline `<svg>` element
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct InlineSvg {
+    /// Element id, accessible label, or a positional fallback (`inline-svg-3`)
+    pub(crate) id: String,
+    /// `aria-label` or `<title>` text
+    pub(crate) label: String,
+    pub(crate) view_box: String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// Self-contained markup with `<use>` references resolved and scripts removed
+    pub(crate) markup: String,
+    /// Fill, stroke and gradient colors of the artwork, largest painted area first
+    pub(crate) colors: Vec<SvgColor>,
+    /// Marked as the site logo (its own or a close ancestor's class, id or label)
+    pub(crate) logo: bool,
+}
+
+/// Elements that are dropped entirely from sanitized output
+const BLOCKED_ELEMENTS: &[&str] = &["script", "foreignobject", "iframe", "object", "embed", "audio", "video", "handler"];
+
+fn attr_local<'a>(el: &'a scraper::node::Element, local: &str) -> Option<&'a str> {
+    el.attrs.iter().find(|(name, _)| name.local.as_ref() == local).map(|(_, v)| v.as_ref())
+}
+
+fn escape_attr(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;")
+}
+
+fn escape_text(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// A URL value as browsers compare its scheme: lowercase, with the whitespace and control characters
+/// they ignore removed (`java&#9;script:` decodes to a tab inside the scheme)
+fn scheme_text(value: &str) -> String {
+    value.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_control()).collect::<String>().to_lowercase()
+}
+
+fn is_unsafe_url(name: &str, value: &str) -> bool {
+    let lower = scheme_text(value);
+    lower.starts_with("javascript:") || (lower.starts_with("data:") && !lower.starts_with("data:image/") && (name == "href" || name == "src"))
+}
+
+fn is_unsafe_attr(name: &str, value: &str) -> bool {
+    let name = name.to_lowercase();
+    if name.starts_with("on") {
+        return true;
+    }
+    // Animation values are `;`-separated lists, any entry of which can be applied
+    if matches!(name.as_str(), "values" | "to" | "from" | "by") {
+        return value.split(';').any(|part| is_unsafe_url("href", part));
+    }
+    is_unsafe_url(&name, value)
+}
+
+/// Index just past the `)` closing a CSS function whose arguments start at `start`, skipping
+/// quoted strings and nested parentheses
+fn closing_paren(text: &str, start: usize) -> usize {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (offset, c) in text[start..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return start + offset + 1,
+            (None, ')') => depth -= 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Style text (a `style` attribute, a `<style>` element, a `fill="url(#g)"` value) with every
+/// reference that could run script or load something external removed: `@import` rules are
+/// dropped, and `url()` values other than same-document fragments and `data:image/` URIs, as well
+/// as `image-set()`, become `none`. Escapes and comments are removed first, since `u\72l(` and
+/// `url/**/(` can still spell a `url(`.
+fn sanitize_css(text: &str) -> String {
+    let text = css::strip_comments(&css::unescape(text));
+    // ASCII lowercasing keeps byte offsets aligned with `text`
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::new();
+    let mut i = 0;
+    while let Some(c) = text[i..].chars().next() {
+        let rest = &lower[i..];
+        if rest.starts_with("@import") {
+            i = rest.find(';').map(|end| i + end + 1).unwrap_or(text.len());
+            continue;
+        }
+        if let Some(function) = ["url(", "image-set("].into_iter().find(|f| rest.starts_with(f)) {
+            let end = closing_paren(&text, i + function.len());
+            let target = text[i + function.len()..end].trim_end_matches(')').trim().trim_matches(['"', '\'']).trim();
+            let local = target.starts_with('#') || scheme_text(target).starts_with("data:image/");
+            out.push_str(if function == "url(" && local { &text[i..end] } else { "none" });
+            i = end;
+            continue;
+        }
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out
+}
+
+/// An attribute value as it may be written out: `None` when the attribute has to go, values
+/// that hold CSS (`style`, `fill="url(...)"`) with their external references removed
+fn clean_attr(name: &str, value: &str) -> Option<String> {
+    if is_unsafe_attr(name, value) {
+        return None;
+    }
+    let css_like = name.eq_ignore_ascii_case("style") || value.contains('\\') || value.to_ascii_lowercase().contains("url(");
+    Some(if css_like && !matches!(name, "href" | "src") { sanitize_css(value) } else { value.to_string() })
+}
+
+/// `<animate>`/`<set>` rewriting a link target, which can swap in a `javascript:` URL after sanitizing
+fn animates_href(el: &scraper::node::Element) -> bool {
+    matches!(el.name.local.as_ref().to_lowercase().as_str(), "animate" | "set")
+        && attr_local(el, "attributeName").is_some_and(|target| matches!(scheme_text(target).as_str(), "href" | "xlink:href"))
+}
+
+/// Serializes SVG subtrees into standalone markup, inlining `<use>` targets from the same document
+struct Serializer<'a> {
+    ids: &'a HashMap<String, ElementRef<'a>>,
+}
+
+impl Serializer<'_> {
+    fn element(&self, node: ElementRef, out: &mut String, depth: usize) {
+        let el = node.value();
+        let name = el.name.local.as_ref();
+        if BLOCKED_ELEMENTS.contains(&name.to_lowercase().as_str()) || animates_href(el) {
+            return;
+        }
+
+        if name == "use" && self.inline_use(el, out, depth) {
+            return;
+        }
+
+        out.push('<');
+        out.push_str(name);
+        for (attr, value) in el.attrs.iter() {
+            let local = attr.local.as_ref();
+            let Some(value) = clean_attr(local, value) else { continue };
+            out.push(' ');
+            if let Some(prefix) = &attr.prefix {
+                out.push_str(prefix.as_ref());
+                out.push(':');
+            }
+            out.push_str(local);
+            out.push_str("=\"");
+            out.push_str(&escape_attr(&value));
+            out.push('"');
+        }
+        out.push('>');
+        self.children(node, out, depth);
+        out.push_str("</");
+        out.push_str(name);
+        out.push('>');
+    }
+
+    fn children(&self, node: ElementRef, out: &mut String, depth: usize) {
+        let style = node.value().name.local.as_ref().eq_ignore_ascii_case("style");
+        for child in node.children() {
+            if let Some(child_el) = ElementRef::wrap(child) {
+                self.element(child_el, out, depth);
+            } else if let Node::Text(text) = child.value() {
+                if style {
+                    out.push_str(&escape_text(&sanitize_css(text)));
+                } else {
+                    out.push_str(&escape_text(text));
+                }
+            }
+        }
+    }
+
+    /// `<use href="#icon">` → the referenced content, positioned like the `<use>` would place it.
+    /// A `<symbol>` becomes a nested `<svg>` carrying its viewBox; other targets become a translated `<g>`.
+    /// Returns false when the reference points outside the document, so the `<use>` is kept as-is.
+    fn inline_use(&self, el: &scraper::node::Element, out: &mut String, depth: usize) -> bool {
+        let Some(target_id) = attr_local(el, "href").and_then(|href| href.strip_prefix('#')) else { return false };
+        let Some(target) = self.ids.get(target_id) else { return false };
+        if depth >= MAX_USE_DEPTH {
+            return true;
+        }
+        let target_el = target.value();
+        let geometry = |key: &str| {
+            attr_local(el, key)
+                .and_then(|v| clean_attr(key, v))
+                .map(|v| format!(" {}=\"{}\"", key, escape_attr(&v)))
+                .unwrap_or_default()
+        };
+        let presentation: String = ["class", "fill", "stroke", "style", "opacity"]
+            .iter()
+            .map(|key| geometry(key))
+            .collect();
+
+        if target_el.name.local.as_ref() == "symbol" {
+            let view_box = attr_local(target_el, "viewBox")
+                .map(|v| format!(" viewBox=\"{}\"", escape_attr(v)))
+                .unwrap_or_default();
+            out.push_str(&format!("<svg{}{}{}{}{}{}>", view_box, geometry("x"), geometry("y"), geometry("width"), geometry("height"), presentation));
+            self.children(*target, out, depth + 1);
+            out.push_str("</svg>");
+        } else {
+            let x = attr_local(el, "x").unwrap_or("0");
+            let y = attr_local(el, "y").unwrap_or("0");
+            out.push_str(&format!("<g transform=\"translate({},{})\"{}>", escape_attr(x), escape_attr(y), presentation));
+            self.element(*target, out, depth + 1);
+            out.push_str("</g>");
+        }
+        true
+    }
+}
+
+fn parse_length(value: Option<&str>) -> u32 {
+    value
+        .map(|v| v.trim().trim_end_matches("px"))
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|v| v.round().max(0.0) as u32)
+        .unwrap_or(0)
+}
+
+/// A sprite container: hidden `<svg>` that only holds `<symbol>`/`<defs>` for `<use>` to reference
+fn is_sprite_sheet(el: ElementRef) -> bool {
+    let children: Vec<ElementRef> = el.children().filter_map(ElementRef::wrap).collect();
+    !children.is_empty() && children.iter().all(|c| matches!(c.value().name(), "symbol" | "defs"))
+}
+
+/// Serialize a standalone `<svg>` element, resolving `<use>` references against `ids`
+fn serialize_svg(el: ElementRef, ids: &HashMap<String, ElementRef>) -> String {
+    let mut markup = String::new();
+    Serializer { ids }.element(el, &mut markup, 0);
+    // Standalone files need the SVG namespace declared explicitly
+    if !markup.contains("xmlns=") {
+        markup = markup.replacen("<svg", "<svg xmlns=\"http://www.w3.org/2000/svg\"", 1);
+    }
+    if markup.contains("xlink:") && !markup.contains("xmlns:xlink") {
+        markup = markup.replacen("<svg", "<svg xmlns:xlink=\"http://www.w3.org/1999/xlink\"", 1);
+    }
+    markup
+}
+
+fn index_ids(document: &Html) -> HashMap<String, ElementRef<'_>> {
+    let mut ids = HashMap::new();
+    if let Ok(selector) = Selector::parse("[id]") {
+        for el in document.select(&selector) {
+            if let Some(id) = el.value().id() {
+                ids.entry(id.to_string()).or_insert(el);
+            }
+        }
+    }
+    ids
+}
+
+/// Capture every top-level inline `<svg>` (sprite sheets excluded) as a sanitized standalone asset
+pub(crate) fn extract_inline_svgs(document: &Html) -> Vec<InlineSvg> {
+    let mut svgs = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let ids = index_ids(document);
+    let Ok(selector) = Selector::parse("svg") else { return svgs };
+
+    for el in document.select(&selector) {
+        let nested = el.ancestors().filter_map(ElementRef::wrap).any(|a| a.value().name() == "svg");
+        if nested || is_sprite_sheet(el) {
+            continue;
+        }
+        let markup = serialize_svg(el, &ids);
+        if markup.len() > MAX_SVG_BYTES || !seen.insert(markup.clone()) {
+            continue;
+        }
+
+        let value = el.value();
+        let view_box = attr_local(value, "viewBox").unwrap_or("").to_string();
+        let vb: Vec<f32> = view_box.split([' ', ',']).filter_map(|v| v.parse().ok()).collect();
+        let mut width = parse_length(value.attr("width"));
+        let mut height = parse_length(value.attr("height"));
+        if (width == 0 || height == 0) && vb.len() == 4 {
+            width = vb[2].round() as u32;
+            height = vb[3].round() as u32;
+        }
+
+        let title = Selector::parse("title")
+            .ok()
+            .and_then(|sel| el.select(&sel).next())
+            .map(|t| t.text().collect::<String>().trim().to_string());
+        let label = value.attr("aria-label").map(|l| l.trim().to_string()).or(title).unwrap_or_default();
+        let id = value
+            .id()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| format!("inline-svg-{}", svgs.len() + 1));
+
+        let logo = logo::marked_as_logo(el) || label.to_lowercase().contains("logo");
+        let colors = svg_colors::svg_colors(markup.as_bytes(), &id);
+        svgs.push(InlineSvg { id, label, view_box, width, height, markup, colors, logo });
+        if svgs.len() >= 200 {
+            break;
+        }
+    }
+    svgs
+}
+
+/// Re-sanitize SVG markup coming back from the frontend before it is written to disk
+pub(crate) fn sanitize_svg_markup(markup: &str) -> Option<String> {
+    let fragment = Html::parse_fragment(markup);
+    let selector = Selector::parse("svg").ok()?;
+    let root = fragment.select(&selector).next()?;
+    let ids = index_ids(&fragment);
+    Some(serialize_svg(root, &ids))
+}
+
+/// Merge SVG lists, dropping identical artwork
+pub(crate) fn merge_inline_svgs(first: Vec<InlineSvg>, second: Vec<InlineSvg>) -> Vec<InlineSvg> {
+    let mut seen: HashSet<String> = HashSet::new();
+    first.into_iter().chain(second).filter(|svg| seen.insert(svg.markup.clone())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sanitize(markup: &str) -> String {
+        sanitize_svg_markup(markup).unwrap_or_default()
+    }
+
+    #[test]
+    fn drops_scripts_and_event_handlers() {
+        let out = sanitize(r##"<svg onload="alert(1)"><script>alert(2)</script><foreignObject><p>x</p></foreignObject><rect onclick="go()" width="4"/></svg>"##);
+        assert!(!out.contains("alert") && !out.contains("onclick") && !out.contains("<p>"), "{}", out);
+        assert!(out.contains(r##"<rect width="4">"##), "{}", out);
+        assert!(out.starts_with(r##"<svg xmlns="http://www.w3.org/2000/svg""##), "{}", out);
+    }
+
+    #[test]
+    fn drops_javascript_links() {
+        let out = sanitize(concat!(
+            r##"<svg><a href="javascript:alert(1)"><rect/></a>"##,
+            r##"<a xlink:href=" JaVa&#9;Script:alert(2)"><rect/></a>"##,
+            r##"<image href="data:text/html,<b>x</b>"/><image href="data:image/png;base64,AAAA"/>"##,
+            r##"<a href="https://example.com/"><rect/></a></svg>"##,
+        ));
+        assert!(!out.to_lowercase().contains("script:") && !out.contains("text/html"), "{}", out);
+        assert!(out.contains("data:image/png;base64,AAAA") && out.contains("https://example.com/"), "{}", out);
+    }
+
+    #[test]
+    fn drops_href_animations() {
+        let out = sanitize(concat!(
+            r##"<svg><a href="#"><animate attributeName="href" to="javascript:alert(1)"/>"##,
+            r##"<set attributeName="xlink:href" to="https://example.com/"/>"##,
+            r##"<animate attributeName="opacity" values="0;javascript:alert(2)"/>"##,
+            r##"<animate attributeName="opacity" values="0;1"/><rect/></a></svg>"##,
+        ));
+        assert!(!out.contains("<set") && !out.contains("alert") && !out.contains("example.com"), "{}", out);
+        assert!(out.contains(r##"values="0;1""##), "{}", out);
+    }
+
+    #[test]
+    fn strips_external_css_references() {
+        let out = sanitize(concat!(
+            r##"<svg><style>@import "https://evil.test/a.css"; .a { fill: url(https://evil.test/p.svg#x) } .b { fill: url(#grad) }</style>"##,
+            r##"<rect style="background: url('//evil.test/t.png'); fill: red"/><rect fill="url(#grad)"/>"##,
+            r##"<rect style="fill: \75 rl(https://evil.test/e.png)"/></svg>"##,
+        ));
+        assert!(!out.contains("evil.test") && !out.contains("@import"), "{}", out);
+        assert!(out.contains("fill: url(#grad)") && out.contains(r##"fill="url(#grad)""##), "{}", out);
+        assert!(out.contains("fill: red"), "{}", out);
+    }
+
+    #[test]
+    fn inlines_use_references() {
+        let document = Html::parse_document(concat!(
+            r##"<svg style="display:none"><symbol id="logo" viewBox="0 0 10 10"><path d="M0 0h10"/></symbol></svg>"##,
+            r##"<svg width="20" height="20"><use href="#logo" width="20" height="20" fill="red"/></svg>"##,
+        ));
+        let svgs = extract_inline_svgs(&document);
+        assert_eq!(svgs.len(), 1);
+        let markup = &svgs[0].markup;
+        assert!(markup.contains(r##"<svg viewBox="0 0 10 10" width="20" height="20" fill="red"><path d="M0 0h10">"##), "{}", markup);
+        assert!(!markup.contains("<use"), "{}", markup);
+    }
+
+    #[test]
+    fn stops_recursive_use() {
+        let out = sanitize(r##"<svg><g id="loop"><rect/><use href="#loop"/></g></svg>"##);
+        assert_eq!(out.matches("<rect>").count(), MAX_USE_DEPTH + 1, "{}", out);
+        assert!(!out.contains("<use"), "{}", out);
+        // References that point outside the document stay as written
+        let external = sanitize(r##"<svg><use href="icons.svg#a"/></svg>"##);
+        assert!(external.contains(r##"<use href="icons.svg#a">"##), "{}", external);
+    }
+}
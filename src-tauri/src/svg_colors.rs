@@ -0,0 +1,161 @@
+use resvg::usvg;
+use serde::{Serialize, Deserialize};
+
+use crate::color::Rgb;
+use crate::convert;
+
+/// Colors reported per SVG
+const MAX_COLORS: usize = 8;
+
+/// A color painted by an SVG, weighted by the area it covers
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct SvgColor {
+    pub(crate) hex: String,
+    pub(crate) rgb: Rgb,
+    /// Share of the painted area (bounding boxes of fills and strokes), 0–1
+    pub(crate) share: f32,
+    /// The SVG it was painted in: file URL, or the inline SVG's id
+    pub(crate) asset: String,
+}
+
+fn area(rect: usvg::Rect) -> f32 {
+    rect.width() * rect.height()
+}
+
+/// Colors of a paint: the solid color, or each gradient stop sharing the area equally
+fn paint_colors(paint: &usvg::Paint) -> Vec<(usvg::Color, f32)> {
+    let stops = match paint {
+        usvg::Paint::Color(color) => return vec![(*color, 1.0)],
+        usvg::Paint::LinearGradient(gradient) => gradient.stops(),
+        usvg::Paint::RadialGradient(gradient) => gradient.stops(),
+        usvg::Paint::Pattern(_) => return Vec::new(),
+    };
+    let share = 1.0 / stops.len().max(1) as f32;
+    stops.iter().map(|stop| (stop.color(), stop.opacity().get() * share)).collect()
+}
+
+fn add(weights: &mut Vec<(Rgb, f32)>, color: usvg::Color, weight: f32) {
+    if weight <= 0.0 {
+        return;
+    }
+    let rgb = Rgb::new(color.red, color.green, color.blue);
+    match weights.iter_mut().find(|(existing, _)| *existing == rgb) {
+        Some((_, total)) => *total += weight,
+        None => weights.push((rgb, weight)),
+    }
+}
+
+/// Accumulate paint area per color. Areas are absolute bounding boxes, so transforms count;
+/// a stroke is the ring between its stroke box and the fill box (a line's box has no area).
+fn walk(group: &usvg::Group, opacity: f32, weights: &mut Vec<(Rgb, f32)>) {
+    let opacity = opacity * group.opacity().get();
+    if opacity <= 0.0 {
+        return;
+    }
+    for node in group.children() {
+        match node {
+            usvg::Node::Group(child) => walk(child, opacity, weights),
+            usvg::Node::Text(text) => walk(text.flattened(), opacity, weights),
+            usvg::Node::Path(path) if path.is_visible() => {
+                let fill_area = area(path.abs_bounding_box());
+                if let Some(fill) = path.fill() {
+                    for (color, share) in paint_colors(fill.paint()) {
+                        add(weights, color, fill_area * share * fill.opacity().get() * opacity);
+                    }
+                }
+                if let Some(stroke) = path.stroke() {
+                    let stroke_area = (area(path.abs_stroke_bounding_box()) - fill_area).max(0.0);
+                    for (color, share) in paint_colors(stroke.paint()) {
+                        add(weights, color, stroke_area * share * stroke.opacity().get() * opacity);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Colors an SVG paints with, largest area first. `usvg` resolves what attribute scanning can't:
+/// `<style>` rules, inheritance, `currentColor`, `<use>` references and gradient stops.
+/// Markup `usvg` can't parse has no colors.
+pub(crate) fn svg_colors(markup: &[u8], asset: &str) -> Vec<SvgColor> {
+    let options = usvg::Options { fontdb: convert::fontdb(), ..Default::default() };
+    let Ok(tree) = usvg::Tree::from_data(markup, &options) else { return Vec::new() };
+    let mut weights: Vec<(Rgb, f32)> = Vec::new();
+    walk(tree.root(), 1.0, &mut weights);
+
+    let total: f32 = weights.iter().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    weights.sort_by(|a, b| b.1.total_cmp(&a.1));
+    weights
+        .into_iter()
+        .take(MAX_COLORS)
+        .map(|(rgb, weight)| SvgColor { hex: rgb.to_hex(), rgb, share: weight / total, asset: asset.to_string() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Colors and their shares rounded to hundredths
+    fn shares(body: &str) -> Vec<(String, f32)> {
+        let markup = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200">{}</svg>"#, body);
+        svg_colors(markup.as_bytes(), "logo.svg").into_iter().map(|color| (color.hex, (color.share * 100.0).round() / 100.0)).collect()
+    }
+
+    fn expected(pairs: &[(&str, f32)]) -> Vec<(String, f32)> {
+        pairs.iter().map(|(hex, share)| (hex.to_string(), *share)).collect()
+    }
+
+    #[test]
+    fn larger_shapes_outweigh_smaller_ones() {
+        let colors = shares(r##"<rect x="50" width="10" height="10" fill="#0000ff"/><rect width="30" height="30" fill="#ff0000"/>"##);
+        assert_eq!(colors, expected(&[("#ff0000", 0.9), ("#0000ff", 0.1)]));
+    }
+
+    #[test]
+    fn reads_fills_from_style_rules() {
+        let colors = shares(r#"<style>.mark { fill: #00ff00 }</style><rect class="mark" width="10" height="10"/>"#);
+        assert_eq!(colors, expected(&[("#00ff00", 1.0)]));
+    }
+
+    #[test]
+    fn splits_gradients_between_stops() {
+        let colors = shares(
+            r##"<defs><linearGradient id="g"><stop offset="0" stop-color="#ff0000"/><stop offset="1" stop-color="#0000ff"/></linearGradient></defs>
+               <rect width="10" height="10" fill="url(#g)"/>"##,
+        );
+        assert_eq!(colors, expected(&[("#ff0000", 0.5), ("#0000ff", 0.5)]));
+    }
+
+    #[test]
+    fn strokes_count_their_ring() {
+        // The stroke box is 12×12 around a 10×10 path: a ring of 44, not 144
+        let colors = shares(
+            r##"<rect x="1" y="1" width="10" height="10" fill="none" stroke="#000000" stroke-width="2"/>
+               <rect x="50" width="10" height="10" fill="#ff0000"/>"##,
+        );
+        assert_eq!(colors, expected(&[("#ff0000", 0.69), ("#000000", 0.31)]));
+    }
+
+    #[test]
+    fn applies_opacity_and_fill_opacity() {
+        let colors = shares(
+            r##"<rect width="10" height="10" fill="#00ff00"/>
+               <rect x="20" width="10" height="10" fill="#ff0000" opacity="0.5"/>
+               <g opacity="0.5"><rect x="40" width="10" height="10" fill="#0000ff" fill-opacity="0.5"/></g>"##,
+        );
+        assert_eq!(colors, expected(&[("#00ff00", 0.57), ("#ff0000", 0.29), ("#0000ff", 0.14)]));
+    }
+
+    #[test]
+    fn ignores_unpainted_shapes() {
+        let colors = shares(r##"<rect width="100" height="100" fill="none"/><rect x="150" width="10" height="10" fill="#00ff00"/>"##);
+        assert_eq!(colors, expected(&[("#00ff00", 1.0)]));
+        assert!(shares(r#"<rect width="10" height="10" fill="none"/>"#).is_empty());
+        assert!(svg_colors(b"<svg", "broken.svg").is_empty());
+    }
+}
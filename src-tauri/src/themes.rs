@@ -0,0 +1,313 @@
+use scraper::{Html, Selector};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+use crate::color::{self, Rgb};
+use crate::css::CssRule;
+use crate::palette::{self, PaletteColor};
+
+/// Class names that switch a subtree to the dark theme
+const DARK_CLASSES: &[&str] = &["dark", "dark-mode", "dark-theme", "theme-dark", "mode-dark"];
+/// Selectors that scope page-wide custom properties
+const ROOT_SELECTORS: &[&str] = &[":root", "html", "body", ":host"];
+/// Properties whose values are read as colors
+const COLOR_PROPERTIES: &[&str] = &[
+    "color", "background-color", "background", "border-color", "outline-color", "fill", "stroke", "accent-color", "caret-color",
+];
+/// `var()` references followed before giving up (guards against cycles)
+const MAX_VAR_DEPTH: usize = 8;
+/// Colors kept in the dark palette
+const MAX_DARK_COLORS: usize = 24;
+
+/// A color custom property as one color scheme declares it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ColorToken {
+    /// Custom property name, e.g. "--background"
+    pub(crate) name: String,
+    /// Value as written, e.g. "var(--slate-900)" or "222 47% 11%"
+    pub(crate) value: String,
+    /// Resolved color
+    pub(crate) hex: String,
+}
+
+/// Color custom properties for the light (default) and dark schemes. Dark only lists
+/// properties the dark theme overrides.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct ColorTokens {
+    pub(crate) light: Vec<ColorToken>,
+    pub(crate) dark: Vec<ColorToken>,
+}
+
+/// A `<meta name="theme-color">` value and the color scheme its `media` targets
+#[derive(Clone, Debug)]
+pub(crate) struct ThemeColor {
+    pub(crate) rgb: Rgb,
+    pub(crate) dark: bool,
+}
+
+/// Whitespace-free lowercase text for matching media queries and attribute selectors
+fn squash(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase()
+}
+
+fn dark_media(media: &str) -> bool {
+    squash(media).contains("prefers-color-scheme:dark")
+}
+
+/// `<meta name="theme-color">` tags; those without a `media` attribute apply to the light scheme
+pub(crate) fn theme_colors(document: &Html) -> Vec<ThemeColor> {
+    let Ok(selector) = Selector::parse("meta[name='theme-color'][content]") else { return Vec::new() };
+    document
+        .select(&selector)
+        .filter_map(|el| {
+            let rgb = color::parse_css_color(el.value().attr("content")?)?;
+            Some(ThemeColor { rgb, dark: el.value().attr("media").is_some_and(dark_media) })
+        })
+        .collect()
+}
+
+/// Declared palette entries for the theme-color metas of one scheme, tagged "theme-color"
+pub(crate) fn theme_color_entries(theme_colors: &[ThemeColor], dark: bool) -> Vec<PaletteColor> {
+    theme_colors
+        .iter()
+        .filter(|theme| theme.dark == dark)
+        .map(|theme| PaletteColor::new(theme.rgb, "theme-color", true))
+        .collect()
+}
+
+/// `.dark`, `html.dark-mode`, `[data-theme="dark"]`, `:root[data-color-scheme=dark] .card`, ...
+fn dark_selector(selector: &str) -> bool {
+    let lower = selector.to_lowercase();
+    let classes = lower.split('.').skip(1).map(|part| {
+        let end = part.find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_')).unwrap_or(part.len());
+        &part[..end]
+    });
+    if classes.into_iter().any(|class| DARK_CLASSES.contains(&class)) {
+        return true;
+    }
+    lower.split('[').skip(1).filter_map(|part| part.split(']').next()).any(|attribute| {
+        let attribute = squash(attribute);
+        let Some((name, value)) = attribute.split_once('=') else { return false };
+        let name = name.trim_end_matches(['~', '|', '^', '$', '*']);
+        let themed = name == "theme" || (name.starts_with("data-") && ["theme", "mode", "scheme"].iter().any(|w| name.contains(w)));
+        themed && value.trim_matches(['"', '\'']) == "dark"
+    })
+}
+
+/// A rule applies to the dark scheme when a dark media query wraps it or every selector is dark-scoped
+fn is_dark(rule: &CssRule) -> bool {
+    rule.conditions.iter().any(|condition| dark_media(condition))
+        || (!rule.selectors.starts_with('@') && rule.selectors.split(',').all(dark_selector))
+}
+
+/// Page-wide scope: `:root`, `html`, `body`, optionally narrowed to a light theme (`:root.light`)
+fn root_selector(selector: &str) -> bool {
+    let selector = selector.trim().to_lowercase();
+    ROOT_SELECTORS.iter().any(|root| {
+        selector.strip_prefix(root).is_some_and(|rest| rest.is_empty() || (rest.starts_with(['.', '[', ':']) && !rest.contains(' ')))
+    }) || matches!(selector.as_str(), ".light" | "[data-theme=light]" | "[data-theme=\"light\"]")
+}
+
+/// Resolve `var(--name, fallback)` references against the scheme's custom properties
+fn resolve(value: &str, variables: &HashMap<String, String>, depth: usize) -> String {
+    let Some(start) = value.find("var(") else { return value.to_string() };
+    if depth >= MAX_VAR_DEPTH {
+        return value.to_string();
+    }
+    let inner_start = start + 4;
+    let mut level = 1;
+    let mut end = None;
+    for (i, c) in value[inner_start..].char_indices() {
+        match c {
+            '(' => level += 1,
+            ')' => {
+                level -= 1;
+                if level == 0 {
+                    end = Some(inner_start + i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let Some(end) = end else { return value.to_string() };
+    let inner = &value[inner_start..end];
+    let (name, fallback) = match inner.split_once(',') {
+        Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+        None => (inner.trim(), None),
+    };
+    let replacement = match variables.get(name) {
+        Some(found) => resolve(found, variables, depth + 1),
+        None => fallback.map(|f| resolve(f, variables, depth + 1)).unwrap_or_default(),
+    };
+    // Later references in the same value are siblings, not a level deeper
+    let rest = resolve(&value[end + 1..], variables, depth);
+    format!("{}{}{}", &value[..start], replacement, rest)
+}
+
+/// Custom properties of the default (light) scheme's page-wide scope, for resolving `var()` elsewhere
+pub(crate) fn root_variables(rules: &[CssRule]) -> HashMap<String, String> {
+    collect_variables(rules.iter().filter(|rule| !is_dark(rule) && rule.selectors.split(',').any(root_selector)))
+        .into_iter()
+        .collect()
+}
+
+/// Substitute `var()` references in a declaration value
+pub(crate) fn resolve_vars(value: &str, variables: &HashMap<String, String>) -> String {
+    resolve(value, variables, 0)
+}
+
+/// A color value, also accepting the bare channel lists design systems keep in tokens so they
+/// can add alpha later: `222 47% 11%` (HSL, shadcn/ui) and `59 130 246` (RGB)
+fn token_color(value: &str) -> Option<Rgb> {
+    if let Some(rgb) = color::parse_css_color(value) {
+        return Some(rgb);
+    }
+    let parts: Vec<&str> = value.split([' ', ',']).filter(|p| !p.is_empty()).collect();
+    if parts.len() != 3 || !parts.iter().all(|p| p.trim_end_matches(['%']).trim_end_matches("deg").parse::<f32>().is_ok()) {
+        return None;
+    }
+    if parts[1].ends_with('%') && parts[2].ends_with('%') {
+        color::parse_css_color(&format!("hsl({})", parts.join(" ")))
+    } else if parts.iter().all(|p| !p.ends_with('%') && !p.ends_with("deg")) {
+        color::parse_css_color(&format!("rgb({})", parts.join(" ")))
+    } else {
+        None
+    }
+}
+
+/// Custom properties in declaration order; later declarations of a name replace earlier ones
+fn collect_variables<'a>(rules: impl Iterator<Item = &'a CssRule>) -> Vec<(String, String)> {
+    let mut variables: Vec<(String, String)> = Vec::new();
+    for rule in rules {
+        for (name, value) in rule.declarations.iter().filter(|(name, _)| name.starts_with("--")) {
+            match variables.iter_mut().find(|(existing, _)| existing == name) {
+                Some(entry) => entry.1 = value.clone(),
+                None => variables.push((name.clone(), value.clone())),
+            }
+        }
+    }
+    variables
+}
+
+fn color_tokens(declared: &[(String, String)], variables: &HashMap<String, String>) -> Vec<ColorToken> {
+    declared
+        .iter()
+        .filter_map(|(name, value)| {
+            let rgb = token_color(&resolve(value, variables, 0))?;
+            Some(ColorToken { name: name.clone(), value: value.clone(), hex: rgb.to_hex() })
+        })
+        .collect()
+}
+
+/// Light and dark color tokens, plus the dark palette: dark theme-color metas first, then the
+/// colors dark-scheme rules paint with (properties and overridden tokens), most used first
+pub(crate) fn extract_schemes(rules: &[CssRule], theme_colors: &[ThemeColor]) -> (ColorTokens, Vec<PaletteColor>) {
+    let (dark_rules, light_rules): (Vec<&CssRule>, Vec<&CssRule>) = rules.iter().partition(|rule| is_dark(rule));
+    let light = collect_variables(light_rules.into_iter().filter(|rule| rule.selectors.split(',').any(root_selector)));
+    let dark = collect_variables(dark_rules.iter().copied());
+    let light_variables: HashMap<String, String> = light.iter().cloned().collect();
+    let mut dark_variables = light_variables.clone();
+    dark_variables.extend(dark.iter().cloned());
+
+    let tokens = ColorTokens { light: color_tokens(&light, &light_variables), dark: color_tokens(&dark, &dark_variables) };
+
+    // Dark palette: every color a dark rule paints with, ranked by how often it appears
+    let mut counts: Vec<(Rgb, usize)> = Vec::new();
+    let painted = dark_rules.iter().flat_map(|rule| {
+        rule.declarations.iter().filter(|(name, _)| COLOR_PROPERTIES.contains(&name.as_str()) || name.starts_with("--"))
+    });
+    for (_, value) in painted {
+        let Some(rgb) = token_color(&resolve(value, &dark_variables, 0)) else { continue };
+        match counts.iter_mut().find(|(existing, _)| *existing == rgb) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((rgb, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let painted = counts.into_iter().map(|(rgb, _)| PaletteColor::new(rgb, "css-dark", false)).collect();
+    let mut dark_palette = palette::build_palette(theme_color_entries(theme_colors, true), painted, &[]);
+    dark_palette.truncate(MAX_DARK_COLORS);
+
+    (tokens, dark_palette)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css;
+
+    fn variables(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn dark_selectors() {
+        for selector in [".dark", "html.dark-mode", ".theme-dark .card", "[data-theme=\"dark\"]", ":root[data-color-scheme=dark] .card", "[data-mode = 'dark']", "[theme~=dark]"] {
+            assert!(dark_selector(selector), "{} should be dark", selector);
+        }
+        for selector in [".darker", ".light", "[data-theme=light]", "[title=dark]", ".card", "a:hover"] {
+            assert!(!dark_selector(selector), "{} should not be dark", selector);
+        }
+    }
+
+    #[test]
+    fn root_selectors() {
+        for selector in [":root", "html", " body ", ":host", ":root.light", "html[data-theme=light]", ".light", "[data-theme=\"light\"]"] {
+            assert!(root_selector(selector), "{} should be page-wide", selector);
+        }
+        for selector in [":root .card", "html body", "bodyx", ".card", "main"] {
+            assert!(!root_selector(selector), "{} should not be page-wide", selector);
+        }
+    }
+
+    #[test]
+    fn token_colors_accept_bare_channel_lists() {
+        assert_eq!(token_color("#3b82f6").map(Rgb::to_hex).as_deref(), Some("#3b82f6"));
+        assert_eq!(token_color("59 130 246").map(Rgb::to_hex).as_deref(), Some("#3b82f6"));
+        assert_eq!(token_color("59, 130, 246").map(Rgb::to_hex).as_deref(), Some("#3b82f6"));
+        assert_eq!(token_color("222 47% 11%").map(Rgb::to_hex).as_deref(), Some("#0f1729"));
+        assert_eq!(token_color("222deg 47% 11%").map(Rgb::to_hex).as_deref(), Some("#0f1729"));
+        assert_eq!(token_color("1rem 2rem 3rem"), None);
+        assert_eq!(token_color("10% 20 30"), None);
+        assert_eq!(token_color("4 8"), None);
+    }
+
+    #[test]
+    fn resolves_nested_references_and_fallbacks() {
+        let vars = variables(&[("--blue-500", "#3b82f6"), ("--primary", "var(--blue-500)"), ("--ring", "var(--primary)")]);
+        assert_eq!(resolve_vars("var(--ring)", &vars), "#3b82f6");
+        assert_eq!(resolve_vars("var(--missing, var(--primary))", &vars), "#3b82f6");
+        assert_eq!(resolve_vars("var(--missing, rgb(0, 0, 0))", &vars), "rgb(0, 0, 0)");
+        assert_eq!(resolve_vars("1px solid var(--primary)", &vars), "1px solid #3b82f6");
+        assert_eq!(resolve_vars("var(--missing)", &vars), "");
+    }
+
+    #[test]
+    fn stops_at_reference_cycles() {
+        let vars = variables(&[("--a", "var(--b)"), ("--b", "var(--a)")]);
+        assert_eq!(resolve_vars("var(--a)", &vars), "var(--a)");
+    }
+
+    #[test]
+    fn resolves_every_sibling_reference() {
+        let vars = variables(&[("--x", "1")]);
+        let value = ["var(--x)"; MAX_VAR_DEPTH + 4].join(" ");
+        assert_eq!(resolve_vars(&value, &vars), ["1"; MAX_VAR_DEPTH + 4].join(" "));
+    }
+
+    #[test]
+    fn splits_light_and_dark_tokens() {
+        let rules = css::parse_rules(
+            ":root { --background: 0 0% 100%; --primary: var(--blue); --blue: #3b82f6; --radius: 4px }
+             .dark { --background: 222 47% 11%; color: #f8fafc }
+             @media (prefers-color-scheme: dark) { a { color: #f8fafc } }",
+        );
+        let (tokens, dark_palette) = extract_schemes(&rules, &[]);
+        let light: Vec<(&str, &str)> = tokens.light.iter().map(|t| (t.name.as_str(), t.hex.as_str())).collect();
+        assert_eq!(light, [("--background", "#ffffff"), ("--primary", "#3b82f6"), ("--blue", "#3b82f6")]);
+        let dark: Vec<(&str, &str)> = tokens.dark.iter().map(|t| (t.name.as_str(), t.hex.as_str())).collect();
+        assert_eq!(dark, [("--background", "#0f1729")]);
+        assert_eq!(dark_palette.first().map(|c| c.hex.as_str()), Some("#f8fafc"));
+    }
+}
@@ -0,0 +1,363 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+use crate::css::CssRule;
+use crate::themes;
+
+/// Text roles reported, with the selectors that style them in stylesheets
+const ROLES: &[(&str, &[&str])] = &[
+    ("body", &["body"]),
+    ("h1", &["h1", ".h1"]),
+    ("h2", &["h2", ".h2"]),
+    ("h3", &["h3", ".h3"]),
+    ("h4", &["h4", ".h4"]),
+    ("h5", &["h5", ".h5"]),
+    ("h6", &["h6", ".h6"]),
+    ("button", &["button", ".btn", ".button", "[type=submit]", "input[type=submit]"]),
+    ("link", &["a"]),
+];
+/// Families that name a category or the OS UI font rather than a typeface
+pub(crate) const GENERIC_FAMILIES: &[&str] = &[
+    "serif", "sans-serif", "monospace", "cursive", "fantasy", "system-ui", "ui-sans-serif", "ui-serif", "ui-monospace",
+    "ui-rounded", "emoji", "math", "fangsong", "-apple-system", "blinkmacsystemfont",
+];
+/// Root font size browsers default to
+const DEFAULT_ROOT_PX: f32 = 16.0;
+
+/// Computed text style of one role as sampled in the browser
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct BrowserTextStyle {
+    pub(crate) role: String,
+    #[serde(default)]
+    pub(crate) font_family: String,
+    #[serde(default)]
+    pub(crate) font_size: String,
+    #[serde(default)]
+    pub(crate) font_weight: String,
+    #[serde(default)]
+    pub(crate) line_height: String,
+    #[serde(default)]
+    pub(crate) letter_spacing: String,
+    #[serde(default)]
+    pub(crate) text_transform: String,
+    /// Sampled elements sharing this style
+    #[serde(default)]
+    pub(crate) count: usize,
+}
+
+/// Resolved text style of one role
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct TextStyle {
+    /// "body", "h1"–"h6", "button" or "link"
+    pub(crate) role: String,
+    /// First family in the stack that names a typeface
+    pub(crate) family: String,
+    /// The full `font-family` stack, unquoted
+    pub(crate) stack: Vec<String>,
+    /// As computed ("32px") or declared ("2rem")
+    pub(crate) size: String,
+    pub(crate) size_px: Option<f32>,
+    pub(crate) weight: Option<u16>,
+    pub(crate) line_height: String,
+    pub(crate) letter_spacing: String,
+    pub(crate) text_transform: String,
+    /// "browser" (computed styles) or "css" (stylesheet rules, when the page had no such element)
+    pub(crate) source: String,
+}
+
+/// The page's type system: one style per role plus the heading scale it implies
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct Typography {
+    pub(crate) styles: Vec<TextStyle>,
+    /// Body size in px
+    pub(crate) base_size_px: Option<f32>,
+    /// Median ratio between consecutive heading sizes (1.25 = major third)
+    pub(crate) scale_ratio: Option<f32>,
+}
+
+/// Split a `font-family` value into unquoted names
+pub(crate) fn split_stack(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|family| family.trim().trim_matches(['"', '\'']).trim().to_string())
+        .filter(|family| !family.is_empty())
+        .collect()
+}
+
+pub(crate) fn is_generic(family: &str) -> bool {
+    GENERIC_FAMILIES.contains(&family.to_lowercase().as_str())
+}
+
+/// `bold` → 700, `"600"` → 600; relative keywords (`bolder`) can't be resolved
+fn parse_weight(value: &str) -> Option<u16> {
+    match value.trim().to_lowercase().as_str() {
+        "normal" => Some(400),
+        "bold" => Some(700),
+        number => number.parse::<f32>().ok().filter(|w| (1.0..=1000.0).contains(w)).map(|w| w.round() as u16),
+    }
+}
+
+/// Pixel size of a `px`, `rem`, `em` or `%` length; `calc()`, `clamp()` and keywords give `None`
+fn parse_px(value: &str, root_px: f32, parent_px: f32) -> Option<f32> {
+    let value = value.trim().to_lowercase();
+    let number = |suffix: &str| value.strip_suffix(suffix).and_then(|n| n.trim().parse::<f32>().ok());
+    if let Some(px) = number("px") {
+        Some(px)
+    } else if let Some(rem) = number("rem") {
+        Some(rem * root_px)
+    } else if let Some(em) = number("em") {
+        Some(em * parent_px)
+    } else {
+        number("%").map(|pct| pct / 100.0 * parent_px)
+    }
+}
+
+fn is_size_token(token: &str) -> bool {
+    let size = token.split('/').next().unwrap_or("");
+    // A bare number is a weight; sizes always carry a unit
+    (size.starts_with(|c: char| c.is_ascii_digit() || c == '.') && size.parse::<f32>().is_err())
+        || ["xx-small", "x-small", "small", "medium", "large", "x-large", "xx-large", "smaller", "larger"].contains(&size)
+}
+
+/// Longhands of a `font` shorthand: `italic 700 2rem/1.2 "Brand Sans", sans-serif`
+fn expand_font_shorthand(value: &str) -> Vec<(&'static str, String)> {
+    let mut longhands = Vec::new();
+    let mut rest = value.trim();
+    while let Some(token) = rest.split_whitespace().next() {
+        let after = rest[token.len()..].trim_start();
+        if !is_size_token(&token.to_lowercase()) {
+            if let Some(weight) = parse_weight(token) {
+                longhands.push(("font-weight", weight.to_string()));
+            }
+            rest = after;
+            continue;
+        }
+        let (size, mut line_height) = match token.split_once('/') {
+            Some((size, line_height)) => (size, line_height.to_string()),
+            None => (token, String::new()),
+        };
+        rest = after;
+        if let Some(slashed) = rest.strip_prefix('/') {
+            let slashed = slashed.trim_start();
+            let lh = slashed.split_whitespace().next().unwrap_or("");
+            line_height = lh.to_string();
+            rest = slashed[lh.len()..].trim_start();
+        } else if line_height.is_empty() && token.ends_with('/') {
+            let lh = rest.split_whitespace().next().unwrap_or("");
+            line_height = lh.to_string();
+            rest = rest[lh.len()..].trim_start();
+        }
+        longhands.push(("font-size", size.trim_end_matches('/').to_string()));
+        if !line_height.is_empty() {
+            longhands.push(("line-height", line_height));
+        }
+        if !rest.is_empty() {
+            longhands.push(("font-family", rest.to_string()));
+        }
+        break;
+    }
+    longhands
+}
+
+/// Declared font properties of one role, later rules winning. Rules inside `@media` are skipped
+/// so breakpoint overrides don't replace the base style.
+fn declared_style(rules: &[CssRule], selectors: &[&str], variables: &HashMap<String, String>) -> HashMap<&'static str, String> {
+    let mut style: HashMap<&'static str, String> = HashMap::new();
+    let matching = rules.iter().filter(|rule| {
+        !rule.conditions.iter().any(|c| c.to_lowercase().starts_with("@media"))
+            && rule.selectors.split(',').any(|s| selectors.contains(&s.trim().to_lowercase().as_str()))
+    });
+    for rule in matching {
+        for (name, value) in &rule.declarations {
+            let value = themes::resolve_vars(value, variables);
+            if name == "font" {
+                style.extend(expand_font_shorthand(&value));
+                continue;
+            }
+            let property = ["font-family", "font-size", "font-weight", "line-height", "letter-spacing", "text-transform"]
+                .into_iter()
+                .find(|p| p == name);
+            if let Some(property) = property {
+                style.insert(property, value);
+            }
+        }
+    }
+    style
+}
+
+fn text_style(role: &str, family_stack: &str, size: &str, size_px: Option<f32>, weight: &str, rest: [&str; 3], source: &str) -> TextStyle {
+    let stack = split_stack(family_stack);
+    let family = stack.iter().find(|f| !is_generic(f)).or(stack.first()).cloned().unwrap_or_default();
+    TextStyle {
+        role: role.to_string(),
+        family,
+        stack,
+        size: size.to_string(),
+        size_px,
+        weight: parse_weight(weight),
+        line_height: rest[0].to_string(),
+        letter_spacing: rest[1].to_string(),
+        text_transform: rest[2].to_string(),
+        source: source.to_string(),
+    }
+}
+
+/// Role styles declared in stylesheets. Headings, buttons and links without a family of their own
+/// inherit the body's; `inherit` values resolve to the body's too.
+pub(crate) fn from_stylesheets(rules: &[CssRule]) -> Vec<TextStyle> {
+    let variables = themes::root_variables(rules);
+    let root = declared_style(rules, &["html", ":root"], &variables);
+    let root_px = root.get("font-size").and_then(|size| parse_px(size, DEFAULT_ROOT_PX, DEFAULT_ROOT_PX)).unwrap_or(DEFAULT_ROOT_PX);
+    let body = declared_style(rules, &["body"], &variables);
+    let body_px = body.get("font-size").and_then(|size| parse_px(size, root_px, root_px)).unwrap_or(root_px);
+
+    ROLES
+        .iter()
+        .filter_map(|(role, selectors)| {
+            let declared = declared_style(rules, selectors, &variables);
+            if declared.is_empty() {
+                return None;
+            }
+            let get = |property: &str| {
+                let inherited = || body.get(property).or(root.get(property)).cloned().unwrap_or_default();
+                match declared.get(property) {
+                    Some(value) if value.eq_ignore_ascii_case("inherit") => inherited(),
+                    Some(value) => value.clone(),
+                    None if property == "font-family" => inherited(),
+                    None => String::new(),
+                }
+            };
+            let size = get("font-size");
+            let parent_px = if *role == "body" { root_px } else { body_px };
+            let size_px = parse_px(&size, root_px, parent_px);
+            let rest = [get("line-height"), get("letter-spacing"), get("text-transform")];
+            Some(text_style(role, &get("font-family"), &size, size_px, &get("font-weight"), [&rest[0], &rest[1], &rest[2]], "css"))
+        })
+        .collect()
+}
+
+/// One style per role: computed styles from the browser, stylesheet declarations for roles it
+/// didn't sample. The scale ratio is the median step between headings that grow in size.
+pub(crate) fn build_typography(browser: &[BrowserTextStyle], declared: Vec<TextStyle>) -> Typography {
+    let mut styles: Vec<TextStyle> = Vec::new();
+    for (role, _) in ROLES {
+        if let Some(sampled) = browser.iter().find(|style| style.role == *role) {
+            let size_px = parse_px(&sampled.font_size, DEFAULT_ROOT_PX, DEFAULT_ROOT_PX);
+            let rest = [sampled.line_height.as_str(), sampled.letter_spacing.as_str(), sampled.text_transform.as_str()];
+            styles.push(text_style(role, &sampled.font_family, &sampled.font_size, size_px, &sampled.font_weight, rest, "browser"));
+        } else if let Some(style) = declared.iter().find(|style| style.role == *role) {
+            styles.push(style.clone());
+        }
+    }
+
+    let size = |role: &str| styles.iter().find(|style| style.role == role).and_then(|style| style.size_px);
+    let mut ratios: Vec<f32> = ["h1", "h2", "h3", "h4", "h5", "h6"]
+        .windows(2)
+        .filter_map(|pair| Some(size(pair[0])? / size(pair[1])?))
+        .filter(|ratio| *ratio > 1.01)
+        .collect();
+    ratios.sort_by(|a, b| a.total_cmp(b));
+    let scale_ratio = ratios.get(ratios.len() / 2).map(|ratio| (ratio * 1000.0).round() / 1000.0);
+
+    Typography { base_size_px: size("body"), scale_ratio, styles }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css;
+
+    fn longhands(value: &str) -> Vec<(&'static str, String)> {
+        let mut longhands = expand_font_shorthand(value);
+        longhands.sort();
+        longhands
+    }
+
+    fn expected(pairs: &[(&'static str, &str)]) -> Vec<(&'static str, String)> {
+        let mut pairs: Vec<(&'static str, String)> = pairs.iter().map(|(name, value)| (*name, value.to_string())).collect();
+        pairs.sort();
+        pairs
+    }
+
+    fn browser(role: &str, font_size: &str) -> BrowserTextStyle {
+        BrowserTextStyle {
+            role: role.to_string(),
+            font_family: "Inter, sans-serif".to_string(),
+            font_size: font_size.to_string(),
+            font_weight: "400".to_string(),
+            line_height: String::new(),
+            letter_spacing: String::new(),
+            text_transform: String::new(),
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn expands_font_shorthand_line_heights() {
+        let full = expected(&[("font-weight", "700"), ("font-size", "2rem"), ("line-height", "1.2"), ("font-family", "\"Brand Sans\", sans-serif")]);
+        for value in [
+            "italic 700 2rem/1.2 \"Brand Sans\", sans-serif",
+            "italic 700 2rem /1.2 \"Brand Sans\", sans-serif",
+            "italic 700 2rem/ 1.2 \"Brand Sans\", sans-serif",
+            "italic 700 2rem / 1.2 \"Brand Sans\", sans-serif",
+        ] {
+            assert_eq!(longhands(value), full, "{}", value);
+        }
+        assert_eq!(longhands("bold 16px Inter"), expected(&[("font-weight", "700"), ("font-size", "16px"), ("font-family", "Inter")]));
+        assert_eq!(longhands("large serif"), expected(&[("font-size", "large"), ("font-family", "serif")]));
+        assert_eq!(longhands("600 Inter"), expected(&[("font-weight", "600")]));
+    }
+
+    #[test]
+    fn converts_lengths_to_px() {
+        assert_eq!(parse_px("24px", 16.0, 18.0), Some(24.0));
+        assert_eq!(parse_px("2rem", 10.0, 18.0), Some(20.0));
+        assert_eq!(parse_px("2em", 10.0, 18.0), Some(36.0));
+        assert_eq!(parse_px("150%", 10.0, 18.0), Some(27.0));
+        assert_eq!(parse_px(" 1.5REM ", 16.0, 16.0), Some(24.0));
+        assert_eq!(parse_px("clamp(1rem, 2vw, 2rem)", 16.0, 16.0), None);
+        assert_eq!(parse_px("large", 16.0, 16.0), None);
+    }
+
+    #[test]
+    fn sizes_resolve_against_the_root_and_body() {
+        let rules = css::parse_rules(
+            "html { font-size: 62.5% }
+             body { font: 1.6rem/1.5 Inter, sans-serif }
+             h1 { font-size: 2em; font-weight: bold }
+             h2 { font-size: 3rem; font-family: inherit }
+             @media (min-width: 768px) { h1 { font-size: 4em } }",
+        );
+        let styles = from_stylesheets(&rules);
+        let style = |role: &str| styles.iter().find(|s| s.role == role).unwrap();
+        assert_eq!(style("body").size_px, Some(16.0));
+        assert_eq!(style("body").line_height, "1.5");
+        assert_eq!(style("h1").size_px, Some(32.0));
+        assert_eq!(style("h1").weight, Some(700));
+        assert_eq!(style("h1").family, "Inter");
+        assert_eq!(style("h2").size_px, Some(30.0));
+        assert_eq!(style("h2").stack, ["Inter", "sans-serif"]);
+        assert!(styles.iter().all(|s| s.source == "css"));
+    }
+
+    #[test]
+    fn scale_ratio_is_the_median_heading_step() {
+        let sampled = [browser("body", "16px"), browser("h1", "40px"), browser("h2", "32px"), browser("h3", "24px"), browser("h4", "20px")];
+        let typography = build_typography(&sampled, Vec::new());
+        // Steps 1.25, 1.333 and 1.2
+        assert_eq!(typography.scale_ratio, Some(1.25));
+        assert_eq!(typography.base_size_px, Some(16.0));
+        assert_eq!(typography.styles.iter().map(|s| s.role.as_str()).collect::<Vec<_>>(), ["body", "h1", "h2", "h3", "h4"]);
+    }
+
+    #[test]
+    fn scale_ratio_ignores_flat_steps_and_fills_in_declared_roles() {
+        let sampled = [browser("h1", "20px"), browser("h2", "20px")];
+        let declared = from_stylesheets(&css::parse_rules("h3 { font-size: 16px } button { font-weight: 600 }"));
+        let typography = build_typography(&sampled, declared);
+        assert_eq!(typography.scale_ratio, Some(1.25));
+        let sources: Vec<(&str, &str)> = typography.styles.iter().map(|s| (s.role.as_str(), s.source.as_str())).collect();
+        assert_eq!(sources, [("h1", "browser"), ("h2", "browser"), ("h3", "css"), ("button", "css")]);
+        assert_eq!(build_typography(&[browser("h1", "20px")], Vec::new()).scale_ratio, None);
+    }
+}
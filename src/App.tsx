@@ -1,45 +1,26 @@
 import { useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Sparkles, AlertCircle } from "lucide-react";
+import { Sparkles, AlertCircle, Settings as SettingsIcon } from "lucide-react";
 import { Input } from "./components/Input";
 import { Results } from "./components/Results";
+import { Settings } from "./components/Settings";
 import { motion, AnimatePresence } from "framer-motion";
-
-interface ImageInfo {
-  src: string;
-  alt: string;
-  width: number;
-  height: number;
-}
-
-interface TextBlock {
-  tag: string;
-  text: string;
-}
-
-interface AnalysisData {
-  colors: string[];
-  fonts: string[];
-  images: ImageInfo[];
-  text_content: TextBlock[];
-  metadata: {
-    title: string;
-    description: string;
-    favicon: string;
-  };
-}
+import type { AnalysisResult, AnalyzeOptions } from "./types";
 
 function App() {
-  const [data, setData] = useState<AnalysisData | null>(null);
+  const [data, setData] = useState<AnalysisResult | null>(null);
+  const [pageUrl, setPageUrl] = useState("");
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [settingsOpen, setSettingsOpen] = useState(false);
 
-  const handleAnalyze = async (url: string) => {
+  const handleAnalyze = async (url: string, options: AnalyzeOptions) => {
     setLoading(true);
     setError(null);
     try {
-      const result = await invoke<AnalysisData>("analyze_page", { url });
+      const result = await invoke<AnalysisResult>("analyze_page", { url, options });
       setData(result);
+      setPageUrl(url);
     } catch (err) {
       console.error(err);
       setError(String(err));
@@ -82,8 +63,18 @@ function App() {
             </div>
             <span className="text-lg font-bold text-white tracking-tight">BrandSnap</span>
           </div>
-          <div className="text-sm text-gray-500">Brand Extraction Tool</div>
+          <div className="flex items-center gap-4">
+            <div className="text-sm text-gray-500">Brand Extraction Tool</div>
+            <button
+              onClick={() => setSettingsOpen(!settingsOpen)}
+              className="text-gray-500 hover:text-white transition-colors p-2 rounded-lg hover:bg-white/5"
+              title="Settings"
+            >
+              <SettingsIcon className="w-4 h-4" />
+            </button>
+          </div>
         </nav>
+        <Settings open={settingsOpen} onClose={() => setSettingsOpen(false)} />
 
         {/* Page Body */}
         <div className="flex-1 w-full flex flex-col">
@@ -167,7 +158,7 @@ function App() {
                     </div>
                   </div>
                 )}
-                <Results data={data} onReset={handleReset} />
+                <Results data={data} pageUrl={pageUrl} onReset={handleReset} />
               </motion.div>
             )}
           </AnimatePresence>
@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AssetRequest, ConvertOptions, PaletteColor } from "./types";
+
+/* ── Typed wrappers for the Tauri commands ── */
+
+export async function openInBrowser(url: string) {
+    try {
+        await invoke("open_in_browser", { url });
+    } catch (e) {
+        console.error("Failed to open URL:", e);
+    }
+}
+
+/** Download into the download folder, optionally converted; resolves to the saved path */
+export function downloadImage(url: string, convert?: ConvertOptions) {
+    return invoke<string>("download_image", { url, convert });
+}
+
+/** Save a captured inline SVG, optionally rasterized; resolves to the saved path */
+export function saveSvg(markup: string, name: string, convert?: ConvertOptions) {
+    return invoke<string>("save_svg", { markup, name, convert });
+}
+
+/** Bundle assets into one ZIP in the download folder, sorted by kind, with the palette's print values in its manifest; resolves to the archive path */
+export function downloadAssets(assets: AssetRequest[], pageUrl: string, palette: PaletteColor[]) {
+    return invoke<string>("download_assets", { assets, pageUrl, palette, asZip: true });
+}
+
+/** Current download folder */
+export function getDownloadDir() {
+    return invoke<string>("get_download_dir");
+}
+
+/** Change the download folder (`null` resets to the OS default); resolves to the folder now in use */
+export function setDownloadDir(path: string | null) {
+    return invoke<string>("set_download_dir", { path });
+}
+
+/** Load a spot-color table (CSV or JSON) palettes are matched against; `null` clears it. Resolves to the number of colors loaded. */
+export function setSpotColors(path: string | null) {
+    return invoke<number>("set_spot_colors", { path });
+}
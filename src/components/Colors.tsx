@@ -0,0 +1,207 @@
+import { motion } from "framer-motion";
+import { Blend, Check, Copy, EyeOff, Moon, SwatchBook, Variable } from "lucide-react";
+import type { AnalysisResult, ColorToken, CopyFn } from "../types";
+import { SectionHeader } from "./SectionHeader";
+
+export const PALETTE_SOURCES: Record<string, string> = {
+    "manifest-theme": "Manifest theme",
+    "manifest-background": "Manifest background",
+    "theme-color": "Theme color meta",
+    text: "Brand guidelines",
+    svg: "SVG artwork",
+    logo: "Logo artwork",
+    computed: "Page styles",
+    "css-dark": "Dark mode styles",
+};
+
+interface ColorsTabProps {
+    data: AnalysisResult;
+    copyToClipboard: CopyFn;
+    copiedId: string | null;
+}
+
+function PairSwatch({ colors }: { colors: [string, string] }) {
+    return (
+        <div className="flex rounded-md overflow-hidden ring-1 ring-white/10">
+            {colors.map((hex, i) => (
+                <span key={i} className="w-8 h-8" style={{ backgroundColor: hex }} title={hex} />
+            ))}
+        </div>
+    );
+}
+
+function TokenColumn({ title, tokens, copyToClipboard }: { title: string; tokens: ColorToken[]; copyToClipboard: CopyFn }) {
+    return (
+        <div>
+            <p className="text-xs font-semibold text-gray-400 mb-2">{title} · {tokens.length}</p>
+            <div className="space-y-1">
+                {tokens.map(token => (
+                    <button
+                        key={token.name}
+                        onClick={() => copyToClipboard(`${token.name}: ${token.value};`)}
+                        title={token.value}
+                        className="w-full flex items-center gap-3 px-2 py-1.5 rounded-lg hover:bg-white/5 text-left transition-colors"
+                    >
+                        <span className="w-4 h-4 rounded ring-1 ring-white/10 shrink-0" style={{ backgroundColor: token.hex }} />
+                        <span className="text-xs font-mono text-gray-300 truncate flex-1">{token.name}</span>
+                        <span className="text-[10px] font-mono text-gray-600">{token.hex}</span>
+                    </button>
+                ))}
+            </div>
+        </div>
+    );
+}
+
+/* ── Colors Tab: published color specs, generated tint/shade scales, dark scheme and accessibility checks ── */
+export function ColorsTab({ data, copyToClipboard, copiedId }: ColorsTabProps) {
+    const specs = data.color_specs || [];
+    const scales = data.color_scales || [];
+    const conflicts = data.cvd_conflicts || [];
+    const darkPalette = data.dark_palette || [];
+    const tokens = data.color_tokens || { light: [], dark: [] };
+
+    return (
+        <div className="p-8 space-y-10">
+            <section>
+                <SectionHeader icon={SwatchBook} tone="pink" title="Color Specs" subtitle={`${specs.length} colors published on the page`} />
+                <div className="grid grid-cols-1 md:grid-cols-2 xl:grid-cols-3 gap-4">
+                    {specs.map((spec, i) => (
+                        <motion.div
+                            key={spec.hex}
+                            initial={{ opacity: 0, y: 10 }}
+                            animate={{ opacity: 1, y: 0 }}
+                            transition={{ delay: 0.04 * i }}
+                            className="flex gap-4 bg-white/[0.03] border border-white/5 rounded-xl p-4"
+                        >
+                            <button
+                                onClick={() => copyToClipboard(spec.hex, `spec-${i}`)}
+                                className="group w-16 h-16 shrink-0 rounded-lg ring-1 ring-white/10 flex items-center justify-center"
+                                style={{ backgroundColor: spec.hex }}
+                                title="Copy hex"
+                            >
+                                {copiedId === `spec-${i}` ? (
+                                    <Check className="w-4 h-4 text-green-400 drop-shadow-lg" />
+                                ) : (
+                                    <Copy className="w-4 h-4 text-white drop-shadow-lg opacity-0 group-hover:opacity-100 transition-opacity" />
+                                )}
+                            </button>
+                            <div className="min-w-0 flex-1">
+                                <p className="text-sm font-semibold text-white truncate">{spec.name || "Unnamed color"}</p>
+                                <p className="text-xs font-mono text-gray-400 mt-1">
+                                    {spec.hex} · rgb({spec.rgb.r}, {spec.rgb.g}, {spec.rgb.b})
+                                </p>
+                                {(spec.cmyk || spec.pantone) && (
+                                    <p className="text-xs font-mono text-gray-500 mt-0.5">
+                                        {[spec.cmyk && `cmyk(${spec.cmyk.join(", ")})`, spec.pantone].filter(Boolean).join(" · ")}
+                                    </p>
+                                )}
+                                <p className="text-[10px] text-gray-600 mt-1.5 truncate" title={spec.notations.join("\n")}>
+                                    As written: {spec.notations.join(" · ")}
+                                </p>
+                            </div>
+                        </motion.div>
+                    ))}
+                </div>
+                {specs.length === 0 && (
+                    <div className="text-center py-10 text-gray-600">No color specs found in the page text.</div>
+                )}
+            </section>
+
+            {scales.length > 0 && (
+                <section>
+                    <SectionHeader icon={Blend} tone="violet" title="Color Scales" subtitle="50–950 ramps built from the primary, secondary and accent colors">
+                        <div className="flex gap-2">
+                            {(["css", "tailwind"] as const).map(format => (
+                                <button
+                                    key={format}
+                                    onClick={() => copyToClipboard(data.scale_tokens[format], `tokens-${format}`)}
+                                    className="flex items-center gap-1.5 bg-white/5 hover:bg-white/10 border border-white/10 text-gray-300 text-xs px-3 py-1.5 rounded-lg transition-all"
+                                >
+                                    {copiedId === `tokens-${format}` ? <Check className="w-3 h-3 text-green-400" /> : <Copy className="w-3 h-3" />}
+                                    {format === "css" ? "CSS variables" : "Tailwind config"}
+                                </button>
+                            ))}
+                        </div>
+                    </SectionHeader>
+                    <div className="space-y-4">
+                        {scales.map(scale => (
+                            <div key={scale.role}>
+                                <p className="text-xs text-gray-400 mb-2">
+                                    <span className="font-semibold text-gray-200 capitalize">{scale.role}</span> · {scale.base} at {scale.base_step}
+                                </p>
+                                <div className="grid grid-cols-11 gap-1">
+                                    {scale.steps.map(step => (
+                                        <button
+                                            key={step.step}
+                                            onClick={() => copyToClipboard(step.hex, `${scale.role}-${step.step}`)}
+                                            title={`${step.hex} · ${step.contrast_white.toFixed(1)}:1 on white · ${step.contrast_black.toFixed(1)}:1 on black`}
+                                            className={`h-14 rounded-md flex flex-col items-center justify-center text-[10px] font-mono transition-transform hover:scale-105 ${step.step === scale.base_step ? "ring-2 ring-white/70" : ""}`}
+                                            style={{ backgroundColor: step.hex, color: step.contrast_white >= step.contrast_black ? "#fff" : "#000" }}
+                                        >
+                                            {copiedId === `${scale.role}-${step.step}` ? <Check className="w-3 h-3" /> : step.step}
+                                        </button>
+                                    ))}
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                </section>
+            )}
+
+            {darkPalette.length > 0 && (
+                <section>
+                    <SectionHeader icon={Moon} tone="blue" title="Dark Mode" subtitle={`${darkPalette.length} colors used by the dark color scheme`} />
+                    <div className="grid grid-cols-4 sm:grid-cols-6 md:grid-cols-8 lg:grid-cols-10 gap-3">
+                        {darkPalette.map((color, i) => (
+                            <button
+                                key={`${color.hex}-${color.source}`}
+                                onClick={() => copyToClipboard(color.hex, `dark-${i}`)}
+                                className="group flex flex-col items-center gap-1"
+                            >
+                                <div
+                                    className="w-full aspect-square rounded-lg ring-1 ring-white/10 group-hover:ring-white/30 flex items-center justify-center transition-all"
+                                    style={{ backgroundColor: color.hex }}
+                                >
+                                    {copiedId === `dark-${i}` && <Check className="w-4 h-4 text-green-400 drop-shadow-lg" />}
+                                </div>
+                                <span className="text-[10px] font-mono text-gray-500">{color.hex}</span>
+                                <span className="text-[10px] text-gray-600 truncate w-full text-center">{PALETTE_SOURCES[color.source] || color.source}</span>
+                            </button>
+                        ))}
+                    </div>
+                </section>
+            )}
+
+            {(tokens.light.length > 0 || tokens.dark.length > 0) && (
+                <section>
+                    <SectionHeader icon={Variable} tone="cyan" title="Color Tokens" subtitle="Custom properties per color scheme; click to copy a declaration" />
+                    <div className="grid grid-cols-1 md:grid-cols-2 gap-6">
+                        <TokenColumn title="Light" tokens={tokens.light} copyToClipboard={copyToClipboard} />
+                        <TokenColumn title="Dark overrides" tokens={tokens.dark} copyToClipboard={copyToClipboard} />
+                    </div>
+                </section>
+            )}
+
+            {conflicts.length > 0 && (
+                <section>
+                    <SectionHeader icon={EyeOff} tone="rose" title="Color Vision Conflicts" subtitle="Palette pairs that become hard to tell apart" />
+                    <div className="space-y-2">
+                        {conflicts.map((conflict, i) => (
+                            <div key={i} className="flex items-center gap-4 bg-white/[0.03] border border-white/5 rounded-xl px-4 py-3">
+                                <PairSwatch colors={conflict.colors} />
+                                <span className="text-gray-600">→</span>
+                                <PairSwatch colors={conflict.simulated} />
+                                <div className="flex-1 min-w-0">
+                                    <p className="text-sm text-gray-200 capitalize">{conflict.condition}</p>
+                                    <p className="text-xs font-mono text-gray-500 mt-0.5">
+                                        {conflict.colors.join(" / ")} · ΔE {conflict.delta_e.toFixed(1)} → {conflict.simulated_delta_e.toFixed(1)}
+                                    </p>
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                </section>
+            )}
+        </div>
+    );
+}
@@ -0,0 +1,40 @@
+import type { ConvertOptions } from "../types";
+
+/* ── Conversions offered for single downloads; "original" keeps the file as served ── */
+export const CONVERT_CHOICES: { id: string; label: string; options?: ConvertOptions }[] = [
+    { id: "original", label: "Original" },
+    { id: "png", label: "PNG", options: { format: "png" } },
+    { id: "jpeg", label: "JPEG", options: { format: "jpeg", quality: 90 } },
+    { id: "webp", label: "WebP", options: { format: "webp" } },
+    { id: "favicon", label: "Favicon set", options: { preset: "favicon" } },
+    { id: "og", label: "Social card (1200×630)", options: { preset: "og" } },
+    { id: "avatar", label: "Square avatar", options: { preset: "avatar" } },
+];
+
+export function convertOptions(choice: string) {
+    return CONVERT_CHOICES.find(c => c.id === choice)?.options;
+}
+
+interface ConvertSelectProps {
+    value: string;
+    onChange: (choice: string) => void;
+}
+
+export function ConvertSelect({ value, onChange }: ConvertSelectProps) {
+    return (
+        <label className="flex items-center gap-2 text-xs text-gray-500">
+            Save as
+            <select
+                value={value}
+                onChange={(e) => onChange(e.target.value)}
+                className="bg-white/5 border border-white/10 rounded-lg px-2 py-1.5 text-xs text-gray-300 outline-none focus:border-cyan-500/40"
+            >
+                {CONVERT_CHOICES.map(choice => (
+                    <option key={choice.id} value={choice.id} className="bg-gray-900">
+                        {choice.label}
+                    </option>
+                ))}
+            </select>
+        </label>
+    );
+}
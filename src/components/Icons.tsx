@@ -0,0 +1,194 @@
+import { useState } from "react";
+import { motion } from "framer-motion";
+import { Check, Download, ExternalLink, Layers, Loader2, PenTool, Star, Stamp } from "lucide-react";
+import { downloadImage, openInBrowser, saveSvg } from "../commands";
+import type { AnalysisResult, IconInfo } from "../types";
+import { progressLabel, useDownloadProgress } from "../useDownloadProgress";
+import { ConvertSelect, convertOptions } from "./ConvertSelect";
+import { SectionHeader } from "./SectionHeader";
+
+function iconSize(icon: IconInfo) {
+    if (icon.width > 0) return `${icon.width}×${icon.height}`;
+    if (icon.declared_width > 0) return `${icon.declared_width}×${icon.declared_height}`;
+    return icon.format === "svg" ? "scalable" : "unknown size";
+}
+
+function svgDataUrl(markup: string) {
+    return `data:image/svg+xml;charset=utf-8,${encodeURIComponent(markup)}`;
+}
+
+/* ── Icons Tab: favicons, touch icons, manifest icons and inline SVGs ── */
+interface IconsTabProps {
+    data: AnalysisResult;
+    /** Shared with the Images tab */
+    convertChoice: string;
+    onConvertChoice: (choice: string) => void;
+}
+
+export function IconsTab({ data, convertChoice, onConvertChoice }: IconsTabProps) {
+    const [savedId, setSavedId] = useState<string | null>(null);
+    const downloads = useDownloadProgress();
+    const icons = data.icons || [];
+    const svgs = data.inline_svgs || [];
+    const sprites = data.icon_sets?.sprites || [];
+    const iconFonts = data.icon_sets?.icon_fonts || [];
+
+    const save = async (run: () => Promise<string>, id: string) => {
+        try {
+            await run();
+            setSavedId(id);
+            setTimeout(() => setSavedId(null), 2000);
+        } catch (e) {
+            console.error("Download failed:", e);
+        }
+    };
+
+    return (
+        <div className="p-8 space-y-10">
+            <section>
+                <SectionHeader icon={Stamp} tone="amber" title="Site Icons" subtitle={`${icons.length} icons, best first`}>
+                    <ConvertSelect value={convertChoice} onChange={onConvertChoice} />
+                </SectionHeader>
+                <div className="grid grid-cols-2 sm:grid-cols-3 md:grid-cols-4 lg:grid-cols-6 gap-4">
+                    {icons.map((icon, i) => (
+                        <motion.div
+                            key={`${icon.src}-${icon.rel}`}
+                            initial={{ opacity: 0, scale: 0.9 }}
+                            animate={{ opacity: 1, scale: 1 }}
+                            transition={{ delay: 0.03 * i }}
+                            className="group relative bg-white/[0.03] border border-white/5 rounded-xl p-4 flex flex-col items-center gap-3 hover:border-white/15 transition-all"
+                        >
+                            {i === 0 && (
+                                <span className="absolute top-2 left-2 flex items-center gap-1 text-[10px] text-amber-300 bg-amber-500/15 px-1.5 py-0.5 rounded-md">
+                                    <Star className="w-3 h-3" /> Favicon
+                                </span>
+                            )}
+                            <div className="w-16 h-16 flex items-center justify-center rounded-lg bg-white/5" style={icon.color ? { backgroundColor: icon.color } : undefined}>
+                                <img src={icon.src} alt="" className="max-w-full max-h-full object-contain" />
+                            </div>
+                            <div className="w-full text-center">
+                                <p className="text-xs text-gray-300 truncate">{icon.rel}</p>
+                                <p className="text-[10px] text-gray-500 mt-0.5">
+                                    {[iconSize(icon), icon.format.toUpperCase()].filter(Boolean).join(" · ")}
+                                </p>
+                            </div>
+                            <button
+                                onClick={() => save(() => downloadImage(icon.src, convertOptions(convertChoice)), `icon-${i}`)}
+                                className="flex items-center gap-1.5 bg-white/5 hover:bg-white/15 text-gray-300 text-xs px-3 py-1.5 rounded-lg transition-all"
+                            >
+                                {downloads[icon.src] ? <Loader2 className="w-3 h-3 animate-spin" /> : savedId === `icon-${i}` ? <Check className="w-3 h-3 text-green-400" /> : <Download className="w-3 h-3" />}
+                                {downloads[icon.src] ? progressLabel(downloads[icon.src]) : savedId === `icon-${i}` ? "Saved!" : "Download"}
+                            </button>
+                        </motion.div>
+                    ))}
+                </div>
+                {icons.length === 0 && (
+                    <div className="text-center py-10 text-gray-600">No site icons found.</div>
+                )}
+            </section>
+
+            {svgs.length > 0 && (
+                <section>
+                    <SectionHeader icon={PenTool} tone="violet" title="Inline SVGs" subtitle={`${svgs.length} marks drawn directly in the page`} />
+                    <div className="grid grid-cols-2 sm:grid-cols-3 md:grid-cols-4 lg:grid-cols-6 gap-4">
+                        {svgs.map((svg, i) => (
+                            <motion.div
+                                key={svg.id}
+                                initial={{ opacity: 0, scale: 0.9 }}
+                                animate={{ opacity: 1, scale: 1 }}
+                                transition={{ delay: 0.03 * i }}
+                                className="relative bg-white/[0.03] border border-white/5 rounded-xl p-4 flex flex-col items-center gap-3 hover:border-white/15 transition-all"
+                            >
+                                {svg.logo && (
+                                    <span className="absolute top-2 left-2 flex items-center gap-1 text-[10px] text-violet-300 bg-violet-500/15 px-1.5 py-0.5 rounded-md">
+                                        <Star className="w-3 h-3" /> Logo
+                                    </span>
+                                )}
+                                <div className="w-full h-16 flex items-center justify-center rounded-lg bg-white/5 p-2">
+                                    <img src={svgDataUrl(svg.markup)} alt={svg.label} className="max-w-full max-h-full object-contain" />
+                                </div>
+                                <div className="w-full text-center">
+                                    <p className="text-xs text-gray-300 truncate">{svg.label || svg.id}</p>
+                                    <p className="text-[10px] text-gray-500 mt-0.5">
+                                        {svg.width > 0 ? `${svg.width}×${svg.height}` : svg.view_box || "scalable"}
+                                    </p>
+                                    {svg.colors?.length > 0 && (
+                                        <div className="flex h-2 mt-2 rounded-full overflow-hidden">
+                                            {svg.colors.map(color => (
+                                                <span
+                                                    key={color.hex}
+                                                    title={`${color.hex} · ${Math.round(color.share * 100)}%`}
+                                                    style={{ backgroundColor: color.hex, flexGrow: color.share }}
+                                                />
+                                            ))}
+                                        </div>
+                                    )}
+                                </div>
+                                <button
+                                    onClick={() => save(() => saveSvg(svg.markup, svg.label || svg.id, convertOptions(convertChoice)), `svg-${i}`)}
+                                    className="flex items-center gap-1.5 bg-white/5 hover:bg-white/15 text-gray-300 text-xs px-3 py-1.5 rounded-lg transition-all"
+                                >
+                                    {savedId === `svg-${i}` ? <Check className="w-3 h-3 text-green-400" /> : <Download className="w-3 h-3" />}
+                                    {savedId === `svg-${i}` ? "Saved!" : convertChoice === "original" ? "Save SVG" : "Save"}
+                                </button>
+                            </motion.div>
+                        ))}
+                    </div>
+                </section>
+            )}
+
+            {(sprites.length > 0 || iconFonts.length > 0) && (
+                <section>
+                    <SectionHeader icon={Layers} tone="cyan" title="Icon Systems" subtitle="Sprite sheets and icon fonts used for UI icons" />
+                    <div className="space-y-2">
+                        {sprites.map((sprite, i) => (
+                            <div key={`sprite-${i}`} className="flex items-center gap-4 bg-white/[0.03] border border-white/5 rounded-xl px-4 py-3">
+                                <div className="flex-1 min-w-0">
+                                    <p className="text-sm text-gray-200">
+                                        SVG sprite <span className="text-gray-500">· {sprite.inline ? "embedded in page" : sprite.url.split("/").pop()}</span>
+                                    </p>
+                                    <p className="text-xs text-gray-500 mt-0.5">
+                                        {sprite.symbols.length} symbols · {sprite.referenced.length} used
+                                    </p>
+                                    {sprite.symbols.length > 0 && (
+                                        <p className="text-[10px] font-mono text-gray-600 mt-1 truncate">
+                                            {sprite.symbols.map(symbol => symbol.id).join(", ")}
+                                        </p>
+                                    )}
+                                </div>
+                                {!sprite.inline && (
+                                    <button
+                                        onClick={() => openInBrowser(sprite.url)}
+                                        className="flex items-center gap-1.5 bg-white/5 hover:bg-white/15 text-gray-300 text-xs px-3 py-1.5 rounded-lg transition-all"
+                                    >
+                                        <ExternalLink className="w-3 h-3" /> Open
+                                    </button>
+                                )}
+                            </div>
+                        ))}
+                        {iconFonts.map((font, i) => (
+                            <div key={`font-${i}`} className="flex items-center gap-4 bg-white/[0.03] border border-white/5 rounded-xl px-4 py-3">
+                                <div className="flex-1 min-w-0">
+                                    <p className="text-sm text-gray-200">
+                                        {font.name} <span className="text-gray-500 font-mono">· .{font.prefix}-*</span>
+                                    </p>
+                                    <p className="text-xs text-gray-500 mt-0.5">
+                                        {[`${font.glyph_count} glyphs`, font.font_family].filter(Boolean).join(" · ")}
+                                    </p>
+                                </div>
+                                {font.stylesheet && (
+                                    <button
+                                        onClick={() => openInBrowser(font.stylesheet)}
+                                        className="flex items-center gap-1.5 bg-white/5 hover:bg-white/15 text-gray-300 text-xs px-3 py-1.5 rounded-lg transition-all"
+                                    >
+                                        <ExternalLink className="w-3 h-3" /> Stylesheet
+                                    </button>
+                                )}
+                            </div>
+                        ))}
+                    </div>
+                </section>
+            )}
+        </div>
+    );
+}
@@ -0,0 +1,188 @@
+import { motion } from "framer-motion";
+import { AppWindow, AtSign, Check, Copy, ExternalLink, Mail, MapPin, Phone } from "lucide-react";
+import type { LucideIcon } from "lucide-react";
+import { openInBrowser } from "../commands";
+import type { AnalysisResult, CopyFn } from "../types";
+import { SectionHeader } from "./SectionHeader";
+
+const SOURCE_LABELS: Record<string, string> = {
+    "json-ld": "Structured data",
+    "twitter-meta": "Twitter card",
+    link: "Page link",
+};
+
+interface ContactRowProps {
+    icon: LucideIcon;
+    value: string;
+    detail?: string;
+    source: string;
+    id: string;
+    copyToClipboard: CopyFn;
+    copiedId: string | null;
+}
+
+function ContactRow({ icon: Icon, value, detail, source, id, copyToClipboard, copiedId }: ContactRowProps) {
+    return (
+        <div
+            onClick={() => copyToClipboard(value, id)}
+            className="group flex items-start gap-3 bg-white/[0.02] hover:bg-white/[0.05] border border-white/5 hover:border-white/10 rounded-xl p-4 transition-all cursor-pointer"
+        >
+            <Icon className="w-4 h-4 text-gray-500 mt-0.5 shrink-0" />
+            <div className="flex-1 min-w-0">
+                <p className="text-sm text-gray-200 break-words whitespace-pre-line">{value}</p>
+                {detail && <p className="text-xs font-mono text-cyan-400/70 mt-1">{detail}</p>}
+                <p className="text-[11px] text-gray-600 mt-1 truncate">{source}</p>
+            </div>
+            {copiedId === id ? (
+                <Check className="w-4 h-4 text-green-400 shrink-0" />
+            ) : (
+                <Copy className="w-4 h-4 text-gray-600 group-hover:text-gray-300 transition-colors shrink-0" />
+            )}
+        </div>
+    );
+}
+
+interface IdentityTabProps {
+    data: AnalysisResult;
+    copyToClipboard: CopyFn;
+    copiedId: string | null;
+}
+
+/* ── Identity Tab: where else the brand lives ── */
+export function IdentityTab({ data, copyToClipboard, copiedId }: IdentityTabProps) {
+    const profiles = data.social_profiles || [];
+    const emails = data.contacts?.emails || [];
+    const phones = data.contacts?.phones || [];
+    const addresses = data.contacts?.addresses || [];
+    const contactCount = emails.length + phones.length + addresses.length;
+    const manifest = data.manifest;
+    return (
+        <div className="p-8 space-y-10">
+            <section>
+                <SectionHeader icon={AtSign} tone="cyan" title="Social Profiles" subtitle={`${profiles.length} accounts found`} />
+                <div className="grid grid-cols-1 md:grid-cols-2 xl:grid-cols-3 gap-3">
+                    {profiles.map((profile, i) => (
+                        <motion.div
+                            key={profile.url}
+                            initial={{ opacity: 0, y: 10 }}
+                            animate={{ opacity: 1, y: 0 }}
+                            transition={{ delay: 0.03 * i }}
+                            className="group flex items-center gap-4 bg-white/[0.03] hover:bg-white/[0.06] border border-white/5 hover:border-white/10 rounded-xl p-4 transition-all"
+                        >
+                            <div className="flex-1 min-w-0">
+                                <p className="text-xs text-gray-500 uppercase tracking-wider font-semibold">{profile.platform}</p>
+                                <p className="text-white font-medium truncate">@{profile.handle}</p>
+                                <p className="text-[11px] text-gray-600 mt-0.5">{SOURCE_LABELS[profile.source] || profile.source}</p>
+                            </div>
+                            <button
+                                onClick={() => copyToClipboard(profile.url, `social-${i}`)}
+                                className="p-2 rounded-lg text-gray-500 hover:text-white hover:bg-white/10 transition-all"
+                                title="Copy URL"
+                            >
+                                {copiedId === `social-${i}` ? <Check className="w-4 h-4 text-green-400" /> : <Copy className="w-4 h-4" />}
+                            </button>
+                            <button
+                                onClick={() => openInBrowser(profile.url)}
+                                className="p-2 rounded-lg text-gray-500 hover:text-white hover:bg-white/10 transition-all"
+                                title="Open"
+                            >
+                                <ExternalLink className="w-4 h-4" />
+                            </button>
+                        </motion.div>
+                    ))}
+                </div>
+                {profiles.length === 0 && (
+                    <div className="text-center py-10 text-gray-600">No social profiles found.</div>
+                )}
+            </section>
+
+            <section>
+                <SectionHeader icon={Mail} tone="emerald" title="Contact Details" subtitle={`${contactCount} found`} />
+                <div className="grid grid-cols-1 lg:grid-cols-3 gap-3">
+                    <div className="space-y-2">
+                        {emails.map((email, i) => (
+                            <ContactRow key={email.address} icon={Mail} value={email.address} source={email.source} id={`email-${i}`} copyToClipboard={copyToClipboard} copiedId={copiedId} />
+                        ))}
+                    </div>
+                    <div className="space-y-2">
+                        {phones.map((phone, i) => (
+                            <ContactRow
+                                key={phone.display}
+                                icon={Phone}
+                                value={phone.display}
+                                detail={phone.e164 && phone.e164 !== phone.display ? phone.e164 : undefined}
+                                source={phone.source}
+                                id={`phone-${i}`}
+                                copyToClipboard={copyToClipboard}
+                                copiedId={copiedId}
+                            />
+                        ))}
+                    </div>
+                    <div className="space-y-2">
+                        {addresses.map((address, i) => (
+                            <ContactRow key={address.formatted} icon={MapPin} value={address.formatted} source={address.source} id={`address-${i}`} copyToClipboard={copyToClipboard} copiedId={copiedId} />
+                        ))}
+                    </div>
+                </div>
+                {contactCount === 0 && (
+                    <div className="text-center py-10 text-gray-600">No emails, phone numbers or addresses found.</div>
+                )}
+            </section>
+
+            <section>
+                <SectionHeader icon={AppWindow} tone="violet" title="Web App Manifest" subtitle={manifest ? manifest.url : "No manifest linked"}>
+                    {manifest && (
+                        <button
+                            onClick={() => openInBrowser(manifest.url)}
+                            className="flex items-center gap-1.5 text-xs text-gray-500 hover:text-gray-300 transition-colors px-2 py-1 rounded-lg hover:bg-white/5"
+                        >
+                            <ExternalLink className="w-3 h-3" />
+                            Open
+                        </button>
+                    )}
+                </SectionHeader>
+                {manifest && (
+                    <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+                        <div className="bg-white/[0.03] border border-white/5 rounded-xl p-5 space-y-3">
+                            <div>
+                                <p className="text-xs text-gray-500 uppercase tracking-wider mb-1 font-semibold">Name</p>
+                                <p className="text-white font-medium">{manifest.name || "—"}</p>
+                            </div>
+                            <div>
+                                <p className="text-xs text-gray-500 uppercase tracking-wider mb-1 font-semibold">Short Name</p>
+                                <p className="text-white font-medium">{manifest.short_name || "—"}</p>
+                            </div>
+                            <div className="flex gap-6">
+                                {([["Theme", manifest.theme_color], ["Background", manifest.background_color]] as const).map(([label, value]) => value && (
+                                    <button
+                                        key={label}
+                                        onClick={() => copyToClipboard(value, `manifest-${label}`)}
+                                        className="flex items-center gap-2 text-left"
+                                    >
+                                        <span className="w-6 h-6 rounded-md ring-1 ring-white/10" style={{ backgroundColor: value }} />
+                                        <span>
+                                            <span className="block text-[11px] text-gray-500">{label}</span>
+                                            <span className="block text-xs font-mono text-gray-300">{copiedId === `manifest-${label}` ? "Copied!" : value}</span>
+                                        </span>
+                                    </button>
+                                ))}
+                            </div>
+                        </div>
+                        <div className="bg-white/[0.03] border border-white/5 rounded-xl p-5">
+                            <p className="text-xs text-gray-500 uppercase tracking-wider mb-3 font-semibold">{manifest.icons.length} Icons</p>
+                            <div className="flex flex-wrap gap-3">
+                                {manifest.icons.map(icon => (
+                                    <div key={`${icon.src}-${icon.sizes}-${icon.purpose}`} className="flex flex-col items-center gap-1" title={icon.src}>
+                                        <img src={icon.src} alt="" className="w-12 h-12 object-contain rounded-lg bg-white/5" />
+                                        <span className="text-[10px] text-gray-500">{icon.sizes || "any"}</span>
+                                        {icon.purpose && icon.purpose !== "any" && <span className="text-[10px] text-gray-600">{icon.purpose}</span>}
+                                    </div>
+                                ))}
+                            </div>
+                        </div>
+                    </div>
+                )}
+            </section>
+        </div>
+    );
+}
@@ -1,14 +1,16 @@
 import { Search, Loader2 } from "lucide-react";
 import { useState } from "react";
 import { motion } from "framer-motion";
+import type { AnalyzeOptions } from "../types";
 
 interface InputProps {
-    onAnalyze: (url: string) => void;
+    onAnalyze: (url: string, options: AnalyzeOptions) => void;
     loading: boolean;
 }
 
 export function Input({ onAnalyze, loading }: InputProps) {
     const [url, setUrl] = useState("");
+    const [contentDedupe, setContentDedupe] = useState(false);
 
     const handleSubmit = (e: React.FormEvent) => {
         e.preventDefault();
@@ -17,7 +19,7 @@ export function Input({ onAnalyze, loading }: InputProps) {
             if (!url.startsWith("http")) {
                 formattedUrl = "https://" + url;
             }
-            onAnalyze(formattedUrl);
+            onAnalyze(formattedUrl, { content_dedupe: contentDedupe });
         }
     };
 
@@ -58,6 +60,17 @@ export function Input({ onAnalyze, loading }: InputProps) {
                     </button>
                 </div>
             </div>
+            <label className="mt-4 flex items-center justify-center gap-2 text-sm text-gray-500 cursor-pointer select-none">
+                <input
+                    type="checkbox"
+                    checked={contentDedupe}
+                    onChange={(e) => setContentDedupe(e.target.checked)}
+                    disabled={loading}
+                    className="accent-cyan-500"
+                />
+                Detect duplicate images by content
+                <span className="text-gray-600">(slower, downloads a sample of images)</span>
+            </label>
         </motion.div>
     );
 }
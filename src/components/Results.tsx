@@ -1,44 +1,39 @@
 import { useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Copy, Type, ArrowLeft, Download, Palette, Globe, Image, FileText, Check, ExternalLink, Search } from "lucide-react";
+import { Copy, Type, ArrowLeft, Archive, Download, Loader2, Palette, Globe, Image, FileText, Check, ExternalLink, Search, Users, Stamp, SwatchBook, Heading } from "lucide-react";
 import { motion, AnimatePresence } from "framer-motion";
-
-interface ImageInfo {
-    src: string;
-    alt: string;
-    width: number;
-    height: number;
-}
-
-interface TextBlock {
-    tag: string;
-    text: string;
-}
-
-interface AnalysisResult {
-    colors: string[];
-    fonts: string[];
-    images: ImageInfo[];
-    text_content: TextBlock[];
-    metadata: {
-        title: string;
-        description: string;
-        favicon: string;
-    };
-}
+import { downloadAssets, downloadImage } from "../commands";
+import { progressLabel, useDownloadProgress } from "../useDownloadProgress";
+import type { AnalysisResult, AssetRequest, CopyFn, TextBlock } from "../types";
+import { ColorsTab, PALETTE_SOURCES } from "./Colors";
+import { ConvertSelect, convertOptions } from "./ConvertSelect";
+import { IconsTab } from "./Icons";
+import { IdentityTab } from "./Identity";
+import { TypographyTab } from "./Typography";
 
 interface ResultsProps {
     data: AnalysisResult;
+    /** The analyzed URL, recorded in the bundle manifest */
+    pageUrl: string;
     onReset: () => void;
 }
 
-type TabId = "brand" | "images" | "text";
+function formatBytes(bytes: number) {
+    if (bytes < 1024) return `${bytes} B`;
+    if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(0)} KB`;
+    return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+type TabId = "brand" | "colors" | "typography" | "identity" | "images" | "icons" | "text";
 
-export function Results({ data, onReset }: ResultsProps) {
+export function Results({ data, pageUrl, onReset }: ResultsProps) {
     const [activeTab, setActiveTab] = useState<TabId>("brand");
     const [copiedId, setCopiedId] = useState<string | null>(null);
     const [textFilter, setTextFilter] = useState("");
-    const [imageFilter, setImageFilter] = useState<"all" | "large" | "medium" | "small">("all");
+    const downloads = useDownloadProgress();
+    const [convertChoice, setConvertChoice] = useState("original");
+    const [bundleState, setBundleState] = useState<"idle" | "working" | "saved">("idle");
+    const [imageFilter, setImageFilter] = useState<"all" | "logos" | "large" | "medium" | "small">("all");
 
     const copyToClipboard = (text: string, id?: string) => {
         navigator.clipboard.writeText(text);
@@ -58,14 +53,38 @@ export function Results({ data, onReset }: ResultsProps) {
         URL.revokeObjectURL(url);
     };
 
+    const handleDownloadAll = async () => {
+        const assets: AssetRequest[] = [
+            ...(data.images || []).map(img => ({ kind: "image" as const, url: img.src, markup: "", name: "" })),
+            ...(data.icons || []).map(icon => ({ kind: "icon" as const, url: icon.src, markup: "", name: "" })),
+            ...(data.inline_svgs || []).map(svg => ({ kind: "svg" as const, url: "", markup: svg.markup, name: svg.label || svg.id })),
+            ...(data.font_families || []).flatMap(family => family.files.map(url => ({ kind: "font" as const, url, markup: "", name: "" }))),
+        ];
+        setBundleState("working");
+        try {
+            const savedPath = await downloadAssets(assets, pageUrl, data.palette || []);
+            console.log('Saved to:', savedPath);
+            setBundleState("saved");
+            setTimeout(() => setBundleState("idle"), 2000);
+        } catch (e) {
+            console.error('Bundle download failed:', e);
+            setBundleState("idle");
+        }
+    };
+
     const tabs = [
-        { id: "brand" as TabId, label: "Brand", icon: Palette, count: data.colors.length + data.fonts.length },
+        { id: "brand" as TabId, label: "Brand", icon: Palette, count: (data.palette?.length || 0) + data.fonts.length },
+        { id: "colors" as TabId, label: "Colors", icon: SwatchBook, count: (data.color_specs?.length || 0) + (data.color_scales?.length || 0) + (data.dark_palette?.length || 0) },
+        { id: "typography" as TabId, label: "Typography", icon: Heading, count: (data.typography?.styles.length || 0) + (data.font_families?.length || 0) },
+        { id: "identity" as TabId, label: "Identity", icon: Users, count: (data.social_profiles?.length || 0) + (data.contacts?.emails.length || 0) + (data.contacts?.phones.length || 0) + (data.contacts?.addresses.length || 0) + (data.manifest ? 1 : 0) },
         { id: "images" as TabId, label: "Images", icon: Image, count: data.images?.length || 0 },
+        { id: "icons" as TabId, label: "Icons", icon: Stamp, count: (data.icons?.length || 0) + (data.inline_svgs?.length || 0) },
         { id: "text" as TabId, label: "Text", icon: FileText, count: data.text_content?.length || 0 },
     ];
 
     const filteredImages = (data.images || []).filter(img => {
         if (imageFilter === "all") return true;
+        if (imageFilter === "logos") return img.kind === "logo";
         const area = img.width * img.height;
         if (imageFilter === "large") return area > 100000;
         if (imageFilter === "medium") return area > 10000 && area <= 100000;
@@ -109,13 +128,23 @@ export function Results({ data, onReset }: ResultsProps) {
                         <span className="text-white font-semibold text-lg truncate max-w-md">{data.metadata.title}</span>
                     </div>
                 </div>
-                <button
-                    onClick={handleExport}
-                    className="flex items-center gap-2 bg-gradient-to-r from-cyan-500 to-blue-500 hover:from-cyan-400 hover:to-blue-400 text-white px-5 py-2.5 rounded-xl font-semibold text-sm transition-all hover:scale-[1.02] shadow-lg shadow-cyan-500/20"
-                >
-                    <Download className="w-4 h-4" />
-                    Export Brand Sheet
-                </button>
+                <div className="flex items-center gap-3">
+                    <button
+                        onClick={handleDownloadAll}
+                        disabled={bundleState === "working"}
+                        className="flex items-center gap-2 bg-white/5 hover:bg-white/10 border border-white/10 text-gray-200 px-5 py-2.5 rounded-xl font-semibold text-sm transition-all disabled:opacity-60"
+                    >
+                        {bundleState === "working" ? <Loader2 className="w-4 h-4 animate-spin" /> : bundleState === "saved" ? <Check className="w-4 h-4 text-green-400" /> : <Archive className="w-4 h-4" />}
+                        {bundleState === "saved" ? "Saved!" : "Download all as ZIP"}
+                    </button>
+                    <button
+                        onClick={handleExport}
+                        className="flex items-center gap-2 bg-gradient-to-r from-cyan-500 to-blue-500 hover:from-cyan-400 hover:to-blue-400 text-white px-5 py-2.5 rounded-xl font-semibold text-sm transition-all hover:scale-[1.02] shadow-lg shadow-cyan-500/20"
+                    >
+                        <Download className="w-4 h-4" />
+                        Export Brand Sheet
+                    </button>
+                </div>
             </motion.div>
 
             {/* Tab Navigation */}
@@ -160,6 +189,18 @@ export function Results({ data, onReset }: ResultsProps) {
                         </motion.div>
                     )}
 
+                    {activeTab === "identity" && (
+                        <motion.div
+                            key="identity"
+                            initial={{ opacity: 0 }}
+                            animate={{ opacity: 1 }}
+                            exit={{ opacity: 0 }}
+                            className="w-full h-full"
+                        >
+                            <IdentityTab data={data} copyToClipboard={copyToClipboard} copiedId={copiedId} />
+                        </motion.div>
+                    )}
+
                     {activeTab === "images" && (
                         <motion.div
                             key="images"
@@ -180,8 +221,9 @@ export function Results({ data, onReset }: ResultsProps) {
                                             <p className="text-sm text-gray-500">{filteredImages.length} images found</p>
                                         </div>
                                     </div>
-                                    <div className="flex gap-2">
-                                        {(["all", "large", "medium", "small"] as const).map(f => (
+                                    <div className="flex items-center gap-2">
+                                        <ConvertSelect value={convertChoice} onChange={setConvertChoice} />
+                                        {(["all", "logos", "large", "medium", "small"] as const).map(f => (
                                             <button
                                                 key={f}
                                                 onClick={() => setImageFilter(f)}
@@ -217,6 +259,11 @@ export function Results({ data, onReset }: ResultsProps) {
                                                         (e.target as HTMLImageElement).style.display = 'none';
                                                     }}
                                                 />
+                                                {img.kind && (
+                                                    <span className="absolute top-2 left-2 bg-black/60 backdrop-blur-sm text-[10px] font-medium text-gray-200 px-1.5 py-0.5 rounded capitalize">
+                                                        {img.kind.replace("-", " ")}{img.kind === "logo" && ` · ${img.score}`}
+                                                    </span>
+                                                )}
                                                 {/* Hover overlay */}
                                                 <div className="absolute inset-0 bg-black/60 backdrop-blur-sm opacity-0 group-hover:opacity-100 transition-all duration-200 flex flex-col items-center justify-center gap-2">
                                                     <button
@@ -242,9 +289,9 @@ export function Results({ data, onReset }: ResultsProps) {
                                                     <button
                                                         onClick={async () => {
                                                             try {
-                                                                setCopiedId(`dl-${i}`);
-                                                                const savedPath = await invoke<string>('download_image', { url: img.src });
+                                                                const savedPath = await downloadImage(img.src, convertOptions(convertChoice));
                                                                 console.log('Saved to:', savedPath);
+                                                                setCopiedId(`dl-${i}`);
                                                                 setTimeout(() => setCopiedId(null), 2000);
                                                             } catch (e) {
                                                                 console.error('Download failed:', e);
@@ -253,15 +300,38 @@ export function Results({ data, onReset }: ResultsProps) {
                                                         }}
                                                         className="flex items-center gap-1.5 bg-cyan-500/40 hover:bg-cyan-500/60 text-white text-xs px-3 py-1.5 rounded-lg transition-all"
                                                     >
-                                                        {copiedId === `dl-${i}` ? <Check className="w-3 h-3" /> : <Download className="w-3 h-3" />}
-                                                        {copiedId === `dl-${i}` ? 'Saved!' : 'Download'}
+                                                        {downloads[img.src] ? <Loader2 className="w-3 h-3 animate-spin" /> : copiedId === `dl-${i}` ? <Check className="w-3 h-3" /> : <Download className="w-3 h-3" />}
+                                                        {downloads[img.src] ? progressLabel(downloads[img.src]) : copiedId === `dl-${i}` ? 'Saved!' : 'Download'}
                                                     </button>
                                                 </div>
                                             </div>
                                             <div className="p-2.5">
                                                 <p className="text-[10px] font-mono text-gray-500 truncate">{img.alt || new URL(img.src).pathname.split('/').pop() || 'image'}</p>
-                                                {img.width > 0 && (
-                                                    <p className="text-[10px] text-gray-600 mt-0.5">{img.width}×{img.height}</p>
+                                                {(img.width > 0 || img.format || img.file_size != null || img.source) && (
+                                                    <p
+                                                        className="text-[10px] text-gray-600 mt-0.5"
+                                                        title={[img.content_type, ...(img.variants || []).map(v => v.width ? `${v.width}w` : v.density ? `${v.density}x` : v.type)].filter(Boolean).join("\n") || undefined}
+                                                    >
+                                                        {[
+                                                            img.width > 0 ? `${img.width}×${img.height}` : "",
+                                                            img.format.toUpperCase(),
+                                                            img.file_size != null ? formatBytes(img.file_size) : "",
+                                                            img.variants?.length > 1 ? `${img.variants.length} sizes` : "",
+                                                            img.source && `from ${img.source}`,
+                                                        ].filter(Boolean).join(" · ")}
+                                                    </p>
+                                                )}
+                                                {img.dominant_colors?.length > 0 && (
+                                                    <div className="flex h-2 mt-2 rounded-full overflow-hidden">
+                                                        {img.dominant_colors.map(color => (
+                                                            <button
+                                                                key={color.hex}
+                                                                onClick={() => copyToClipboard(color.hex)}
+                                                                title={`${color.hex} · ${Math.round(color.share * 100)}%`}
+                                                                style={{ backgroundColor: color.hex, flexGrow: color.share }}
+                                                            />
+                                                        ))}
+                                                    </div>
                                                 )}
                                             </div>
                                         </motion.div>
@@ -278,6 +348,42 @@ export function Results({ data, onReset }: ResultsProps) {
                         </motion.div>
                     )}
 
+                    {activeTab === "colors" && (
+                        <motion.div
+                            key="colors"
+                            initial={{ opacity: 0 }}
+                            animate={{ opacity: 1 }}
+                            exit={{ opacity: 0 }}
+                            className="w-full h-full"
+                        >
+                            <ColorsTab data={data} copyToClipboard={copyToClipboard} copiedId={copiedId} />
+                        </motion.div>
+                    )}
+
+                    {activeTab === "typography" && (
+                        <motion.div
+                            key="typography"
+                            initial={{ opacity: 0 }}
+                            animate={{ opacity: 1 }}
+                            exit={{ opacity: 0 }}
+                            className="w-full h-full"
+                        >
+                            <TypographyTab data={data} copyToClipboard={copyToClipboard} copiedId={copiedId} />
+                        </motion.div>
+                    )}
+
+                    {activeTab === "icons" && (
+                        <motion.div
+                            key="icons"
+                            initial={{ opacity: 0 }}
+                            animate={{ opacity: 1 }}
+                            exit={{ opacity: 0 }}
+                            className="w-full h-full"
+                        >
+                            <IconsTab data={data} convertChoice={convertChoice} onConvertChoice={setConvertChoice} />
+                        </motion.div>
+                    )}
+
                     {activeTab === "text" && (
                         <motion.div
                             key="text"
@@ -371,7 +477,8 @@ export function Results({ data, onReset }: ResultsProps) {
 }
 
 /* ── Brand Tab (preserved from original layout) ── */
-function BrandTab({ data, copyToClipboard, copiedId }: { data: ResultsProps["data"]; copyToClipboard: (text: string, id?: string) => void; copiedId: string | null }) {
+function BrandTab({ data, copyToClipboard, copiedId }: { data: AnalysisResult; copyToClipboard: CopyFn; copiedId: string | null }) {
+    const palette = data.palette || [];
     return (
         <div className="w-full h-full grid grid-cols-1 lg:grid-cols-3 gap-0">
             {/* LEFT COLUMN: Metadata + Colors */}
@@ -416,27 +523,39 @@ function BrandTab({ data, copyToClipboard, copiedId }: { data: ResultsProps["dat
                             </div>
                             <div>
                                 <h2 className="text-xl font-bold text-white">Color Palette</h2>
-                                <p className="text-sm text-gray-500">{data.colors.length} colors extracted</p>
+                                <p className="text-sm text-gray-500">{palette.length} colors extracted · {palette.filter(c => c.declared).length} declared by the site</p>
                             </div>
                         </div>
                     </div>
 
                     <div className="grid grid-cols-3 sm:grid-cols-4 md:grid-cols-5 lg:grid-cols-6 xl:grid-cols-8 gap-4">
-                        {data.colors.map((color, i) => (
+                        {palette.map((color, i) => (
                             <motion.button
-                                key={i}
+                                key={`${color.hex}-${color.source}`}
                                 initial={{ opacity: 0, scale: 0.8 }}
                                 animate={{ opacity: 1, scale: 1 }}
                                 transition={{ delay: 0.05 * i }}
                                 whileHover={{ scale: 1.05, y: -3 }}
                                 whileTap={{ scale: 0.95 }}
-                                onClick={() => copyToClipboard(color, `color-${i}`)}
-                                className="group flex flex-col items-center gap-2"
+                                onClick={() => copyToClipboard(color.hex, `color-${i}`)}
+                                className="group flex flex-col items-center gap-1"
                             >
                                 <div
                                     className="w-full aspect-square rounded-xl shadow-lg ring-1 ring-white/10 hover:ring-white/30 transition-all relative overflow-hidden"
-                                    style={{ backgroundColor: color }}
+                                    style={{ backgroundColor: color.hex }}
+                                    title={[
+                                        color.cmyk && `cmyk(${color.cmyk.join(", ")})`,
+                                        color.nearest_css && `≈ ${color.nearest_css.name} (ΔE ${color.nearest_css.delta_e.toFixed(1)})`,
+                                    ].filter(Boolean).join("\n")}
                                 >
+                                    {color.role && (
+                                        <span className="absolute bottom-1.5 left-1.5 text-[9px] font-semibold uppercase tracking-wide text-white bg-black/40 px-1 rounded">
+                                            {color.role}
+                                        </span>
+                                    )}
+                                    {color.declared && (
+                                        <span className="absolute top-1.5 right-1.5 w-2 h-2 rounded-full bg-white ring-2 ring-black/30" title="Declared brand color" />
+                                    )}
                                     <div className="absolute inset-0 flex items-center justify-center opacity-0 group-hover:opacity-100 bg-black/30 backdrop-blur-sm transition-all duration-200">
                                         {copiedId === `color-${i}` ? (
                                             <Check className="w-5 h-5 text-green-400 drop-shadow-lg" />
@@ -445,14 +564,28 @@ function BrandTab({ data, copyToClipboard, copiedId }: { data: ResultsProps["dat
                                         )}
                                     </div>
                                 </div>
+                                {color.name && (
+                                    <span className="text-[11px] text-gray-300 truncate w-full text-center">{color.name}</span>
+                                )}
                                 <span className="text-[11px] font-mono text-gray-500 group-hover:text-gray-300 transition-colors truncate w-full text-center">
-                                    {color}
+                                    {color.hex}
                                 </span>
+                                <span className="text-[10px] text-gray-600 truncate w-full text-center" title={color.asset || undefined}>
+                                    {PALETTE_SOURCES[color.source] || color.source}
+                                </span>
+                                {color.nearest_spot && (
+                                    <span
+                                        className="text-[10px] text-amber-300/80 truncate w-full text-center"
+                                        title={`${color.nearest_spot.hex} · ΔE ${color.nearest_spot.delta_e.toFixed(1)}`}
+                                    >
+                                        ≈ {color.nearest_spot.name}
+                                    </span>
+                                )}
                             </motion.button>
                         ))}
                     </div>
 
-                    {data.colors.length === 0 && (
+                    {palette.length === 0 && (
                         <div className="text-center py-16 text-gray-600">No colors detected.</div>
                     )}
                 </motion.div>
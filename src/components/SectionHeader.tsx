@@ -0,0 +1,38 @@
+import { ReactNode } from "react";
+import type { LucideIcon } from "lucide-react";
+
+/* Tailwind only ships classes it can see, so every tone is spelled out */
+const TONES = {
+    emerald: "bg-emerald-500/10 text-emerald-400",
+    pink: "bg-pink-500/10 text-pink-400",
+    blue: "bg-blue-500/10 text-blue-400",
+    violet: "bg-violet-500/10 text-violet-400",
+    amber: "bg-amber-500/10 text-amber-400",
+    cyan: "bg-cyan-500/10 text-cyan-400",
+    rose: "bg-rose-500/10 text-rose-400",
+};
+
+interface SectionHeaderProps {
+    icon: LucideIcon;
+    title: string;
+    subtitle?: string;
+    tone: keyof typeof TONES;
+    children?: ReactNode;
+}
+
+export function SectionHeader({ icon: Icon, title, subtitle, tone, children }: SectionHeaderProps) {
+    return (
+        <div className="flex items-center justify-between mb-5">
+            <div className="flex items-center gap-3">
+                <div className={`w-10 h-10 rounded-xl flex items-center justify-center ${TONES[tone]}`}>
+                    <Icon className="w-5 h-5" />
+                </div>
+                <div>
+                    <h2 className="text-xl font-bold text-white">{title}</h2>
+                    {subtitle && <p className="text-sm text-gray-500">{subtitle}</p>}
+                </div>
+            </div>
+            {children}
+        </div>
+    );
+}
@@ -0,0 +1,135 @@
+import { useEffect, useState } from "react";
+import { AnimatePresence } from "framer-motion";
+import { Check, FolderDown, Pipette, RotateCcw, X } from "lucide-react";
+import { getDownloadDir, setDownloadDir, setSpotColors } from "../commands";
+import { GlassPanel } from "./GlassPanel";
+
+interface SettingsProps {
+    open: boolean;
+    onClose: () => void;
+}
+
+/* ── Settings: download folder and spot-color table, remembered across launches ── */
+export function Settings({ open, onClose }: SettingsProps) {
+    const [downloadDir, setDownloadDirInput] = useState("");
+    const [dirError, setDirError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+    const [spotPath, setSpotPath] = useState("");
+    const [spotStatus, setSpotStatus] = useState<string | null>(null);
+    const [spotError, setSpotError] = useState<string | null>(null);
+
+    useEffect(() => {
+        if (!open) return;
+        setDirError(null);
+        setSpotError(null);
+        getDownloadDir().then(setDownloadDirInput).catch(e => setDirError(String(e)));
+    }, [open]);
+
+    // The backend rejects folders and files that don't exist; the error is shown under the field
+    const apply = async (path: string | null) => {
+        setDirError(null);
+        try {
+            setDownloadDirInput(await setDownloadDir(path));
+            setSaved(true);
+            setTimeout(() => setSaved(false), 1500);
+        } catch (e) {
+            setDirError(String(e));
+        }
+    };
+
+    const loadSpotColors = async (path: string | null) => {
+        setSpotError(null);
+        try {
+            const count = await setSpotColors(path);
+            setSpotStatus(path ? `${count} spot colors loaded; applies to the next analysis` : "Spot colors cleared");
+            if (!path) setSpotPath("");
+        } catch (e) {
+            setSpotStatus(null);
+            setSpotError(String(e));
+        }
+    };
+
+    return (
+        <AnimatePresence>
+            {open && (
+                <div className="absolute right-8 top-16 z-20 w-[28rem]">
+                    <GlassPanel className="bg-gray-950/90 border-white/10 p-5 space-y-4">
+                        <div className="flex items-center justify-between">
+                            <h2 className="text-base font-bold text-white">Settings</h2>
+                            <button onClick={onClose} className="text-gray-500 hover:text-white transition-colors">
+                                <X className="w-4 h-4" />
+                            </button>
+                        </div>
+
+                        <div>
+                            <label className="flex items-center gap-2 text-xs font-medium text-gray-400 mb-2">
+                                <FolderDown className="w-3.5 h-3.5" /> Download folder
+                            </label>
+                            <form
+                                onSubmit={(e) => {
+                                    e.preventDefault();
+                                    apply(downloadDir);
+                                }}
+                                className="flex items-center gap-2"
+                            >
+                                <input
+                                    type="text"
+                                    value={downloadDir}
+                                    onChange={(e) => setDownloadDirInput(e.target.value)}
+                                    className="flex-1 bg-white/5 border border-white/10 rounded-lg px-3 py-2 text-sm text-white font-mono outline-none focus:border-cyan-500/40"
+                                />
+                                <button type="submit" className="flex items-center gap-1.5 bg-cyan-500/20 hover:bg-cyan-500/30 text-cyan-300 text-xs px-3 py-2 rounded-lg transition-all">
+                                    {saved ? <Check className="w-3 h-3" /> : null}
+                                    {saved ? "Saved" : "Save"}
+                                </button>
+                                <button
+                                    type="button"
+                                    onClick={() => apply(null)}
+                                    title="Use the system downloads folder"
+                                    className="bg-white/5 hover:bg-white/10 text-gray-400 p-2 rounded-lg transition-all"
+                                >
+                                    <RotateCcw className="w-3.5 h-3.5" />
+                                </button>
+                            </form>
+                            {dirError && <p className="text-xs text-red-300 mt-2">{dirError}</p>}
+                        </div>
+
+                        <div>
+                            <label className="flex items-center gap-2 text-xs font-medium text-gray-400 mb-2">
+                                <Pipette className="w-3.5 h-3.5" /> Spot-color table (CSV or JSON)
+                            </label>
+                            <form
+                                onSubmit={(e) => {
+                                    e.preventDefault();
+                                    loadSpotColors(spotPath);
+                                }}
+                                className="flex items-center gap-2"
+                            >
+                                <input
+                                    type="text"
+                                    value={spotPath}
+                                    onChange={(e) => setSpotPath(e.target.value)}
+                                    placeholder="/path/to/spot-colors.csv"
+                                    className="flex-1 bg-white/5 border border-white/10 rounded-lg px-3 py-2 text-sm text-white font-mono outline-none focus:border-cyan-500/40 placeholder:text-gray-600"
+                                />
+                                <button type="submit" disabled={!spotPath.trim()} className="bg-cyan-500/20 hover:bg-cyan-500/30 text-cyan-300 text-xs px-3 py-2 rounded-lg transition-all disabled:opacity-40">
+                                    Load
+                                </button>
+                                <button
+                                    type="button"
+                                    onClick={() => loadSpotColors(null)}
+                                    title="Clear the spot-color table"
+                                    className="bg-white/5 hover:bg-white/10 text-gray-400 p-2 rounded-lg transition-all"
+                                >
+                                    <X className="w-3.5 h-3.5" />
+                                </button>
+                            </form>
+                            {spotStatus && <p className="text-xs text-gray-500 mt-2">{spotStatus}</p>}
+                            {spotError && <p className="text-xs text-red-300 mt-2">{spotError}</p>}
+                        </div>
+                    </GlassPanel>
+                </div>
+            )}
+        </AnimatePresence>
+    );
+}
@@ -0,0 +1,157 @@
+import { motion } from "framer-motion";
+import { Check, Copy, ExternalLink, Heading, Type } from "lucide-react";
+import { openInBrowser } from "../commands";
+import type { AnalysisResult, CopyFn, FontFileInfo, TextStyle } from "../types";
+import { SectionHeader } from "./SectionHeader";
+
+interface TypographyTabProps {
+    data: AnalysisResult;
+    copyToClipboard: CopyFn;
+    copiedId: string | null;
+}
+
+/** The style as a CSS rule body, for copying */
+function styleCss(style: TextStyle) {
+    return [
+        `font-family: ${style.stack.map(family => (family.includes(" ") ? `"${family}"` : family)).join(", ")};`,
+        `font-size: ${style.size};`,
+        style.weight != null && `font-weight: ${style.weight};`,
+        style.line_height && `line-height: ${style.line_height};`,
+        style.letter_spacing && `letter-spacing: ${style.letter_spacing};`,
+        style.text_transform && style.text_transform !== "none" && `text-transform: ${style.text_transform};`,
+    ].filter(Boolean).join("\n");
+}
+
+const PROVIDERS: Record<string, string> = {
+    google: "Google Fonts",
+    adobe: "Adobe Fonts",
+    bunny: "Bunny Fonts",
+    fontshare: "Fontshare",
+    "self-hosted": "Self-hosted",
+};
+
+function FontFileRow({ info }: { info: FontFileInfo }) {
+    const maker = [info.foundry, info.vendor_id && `(${info.vendor_id})`].filter(Boolean).join(" ");
+    return (
+        <div className="text-[11px] text-gray-500 border-t border-white/5 pt-2 mt-2">
+            <p className="text-gray-300">
+                {info.full_name || info.family || info.url.split("/").pop()}
+                <span className="text-gray-600 font-mono"> · {[info.format.toUpperCase(), info.weight_class, info.version].filter(Boolean).join(" · ")}</span>
+            </p>
+            {(maker || info.designer) && (
+                <p>{[maker && `by ${maker}`, info.designer && `designed by ${info.designer}`].filter(Boolean).join(" · ")}</p>
+            )}
+            {info.axes.length > 0 && (
+                <p className="font-mono">
+                    {info.axes.map(axis => `${axis.tag} ${axis.min}–${axis.max} (default ${axis.default})`).join(" · ")}
+                </p>
+            )}
+            {(info.license || info.license_url) && (
+                <p className="truncate" title={[info.license, info.copyright, info.trademark].filter(Boolean).join("\n")}>
+                    License:{" "}
+                    {info.license_url ? (
+                        <button onClick={() => openInBrowser(info.license_url)} className="text-cyan-400 hover:underline">
+                            {info.license || info.license_url}
+                        </button>
+                    ) : (
+                        info.license
+                    )}
+                </p>
+            )}
+        </div>
+    );
+}
+
+/* ── Typography Tab: the type scale per role and the families behind it ── */
+export function TypographyTab({ data, copyToClipboard, copiedId }: TypographyTabProps) {
+    const { styles, base_size_px, scale_ratio } = data.typography || { styles: [], base_size_px: null, scale_ratio: null };
+    const summary = [
+        base_size_px != null && `${base_size_px}px base`,
+        scale_ratio != null && `${scale_ratio.toFixed(3)} heading scale`,
+    ].filter(Boolean).join(" · ");
+    const families = data.font_families || [];
+
+    return (
+        <div className="p-8 space-y-10">
+            <section>
+                <SectionHeader icon={Heading} tone="blue" title="Type Scale" subtitle={summary || `${styles.length} text styles`} />
+                <div className="space-y-2">
+                    {styles.map((style, i) => (
+                        <motion.div
+                            key={style.role}
+                            initial={{ opacity: 0, y: 10 }}
+                            animate={{ opacity: 1, y: 0 }}
+                            transition={{ delay: 0.03 * i }}
+                            className="group flex items-center gap-6 bg-white/[0.03] border border-white/5 rounded-xl px-5 py-4"
+                        >
+                            <span className="w-16 shrink-0 text-xs font-mono uppercase text-gray-500">{style.role}</span>
+                            <p
+                                className="flex-1 min-w-0 text-white truncate"
+                                style={{
+                                    fontFamily: style.stack.join(", "),
+                                    // Cap huge display sizes so the row stays readable
+                                    fontSize: style.size_px != null ? `${Math.min(style.size_px, 48)}px` : undefined,
+                                    fontWeight: style.weight ?? undefined,
+                                    letterSpacing: style.letter_spacing || undefined,
+                                    textTransform: (style.text_transform || undefined) as "none" | "uppercase" | "lowercase" | "capitalize" | undefined,
+                                }}
+                            >
+                                {style.family || "Default font"}
+                            </p>
+                            <div className="text-right text-[11px] font-mono text-gray-500 shrink-0">
+                                <p>{[style.size, style.weight, style.line_height && `/ ${style.line_height}`].filter(Boolean).join(" ")}</p>
+                                <p className="text-gray-600">
+                                    {[style.letter_spacing && `tracking ${style.letter_spacing}`, style.text_transform !== "none" && style.text_transform, style.source === "css" && "from CSS"].filter(Boolean).join(" · ")}
+                                </p>
+                            </div>
+                            <button
+                                onClick={() => copyToClipboard(styleCss(style), `style-${style.role}`)}
+                                className="opacity-0 group-hover:opacity-100 text-gray-500 hover:text-white transition-all"
+                                title="Copy CSS"
+                            >
+                                {copiedId === `style-${style.role}` ? <Check className="w-4 h-4 text-green-400" /> : <Copy className="w-4 h-4" />}
+                            </button>
+                        </motion.div>
+                    ))}
+                </div>
+                {styles.length === 0 && (
+                    <div className="text-center py-10 text-gray-600">No text styles detected.</div>
+                )}
+            </section>
+
+            {families.length > 0 && (
+                <section>
+                    <SectionHeader icon={Type} tone="emerald" title="Font Families" subtitle={`${families.filter(f => f.role === "primary").length} primary, ${families.filter(f => f.role !== "primary").length} fallbacks and generics`} />
+                    <div className="space-y-2">
+                        {families.map(family => (
+                            <div key={family.name} className="bg-white/[0.03] border border-white/5 rounded-xl px-5 py-3">
+                                <div className="flex items-center gap-4">
+                                    <div className="flex-1 min-w-0">
+                                        <p className="text-base text-white truncate" style={{ fontFamily: family.name }}>{family.name}</p>
+                                        <p className="text-xs text-gray-500 mt-0.5">
+                                            {[
+                                                family.role,
+                                                family.system ? "system font" : PROVIDERS[family.provider] || family.provider,
+                                                family.files.length > 0 && `${family.files.length} files`,
+                                                `used by ${family.usage} elements`,
+                                            ].filter(Boolean).join(" · ")}
+                                        </p>
+                                    </div>
+                                    {family.stylesheets[0] && (
+                                        <button
+                                            onClick={() => openInBrowser(family.stylesheets[0])}
+                                            className="flex items-center gap-1.5 bg-white/5 hover:bg-white/15 text-gray-300 text-xs px-3 py-1.5 rounded-lg transition-all"
+                                        >
+                                            <ExternalLink className="w-3 h-3" /> Stylesheet
+                                        </button>
+                                    )}
+                                </div>
+                                {family.file_info?.map(info => <FontFileRow key={info.url} info={info} />)}
+                            </div>
+                        ))}
+                    </div>
+                </section>
+            )}
+        </div>
+    );
+}
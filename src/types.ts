@@ -0,0 +1,432 @@
+/* ── Shapes returned by the Tauri commands (mirrors the serde structs in src-tauri) ── */
+
+export interface Rgb {
+    r: number;
+    g: number;
+    b: number;
+}
+
+/** One candidate of a responsive image (`srcset` entry or `<picture><source>`) */
+export interface ImageVariant {
+    src: string;
+    /** `w` descriptor (0 when the candidate has none) */
+    width: number;
+    /** `x` descriptor (0 when the candidate has none) */
+    density: number;
+    media: string;
+    type: string;
+}
+
+export interface DominantColor {
+    hex: string;
+    rgb: Rgb;
+    /** Share of the image's opaque pixels, 0–1 */
+    share: number;
+}
+
+export interface ImageInfo {
+    src: string;
+    alt: string;
+    width: number;
+    height: number;
+    /** Where the image was declared when it isn't a plain page element (e.g. "manifest") */
+    source: string;
+    /** "logo", "icon", "hero", "product", "social-preview" or "background" */
+    kind: string;
+    /** Logo likelihood, 0–100 */
+    score: number;
+    /** "png", "jpeg", "webp", "svg", ... read from the file header */
+    format: string;
+    content_type: string;
+    /** Size in bytes, when the server reports it */
+    file_size: number | null;
+    /** Every responsive candidate; `src` is the largest of them */
+    variants: ImageVariant[];
+    /** Most common colors of logos and hero images, largest share first */
+    dominant_colors: DominantColor[];
+}
+
+export interface TextBlock {
+    tag: string;
+    text: string;
+}
+
+export interface SocialProfile {
+    platform: string;
+    handle: string;
+    url: string;
+    /** "json-ld", "twitter-meta" or "link" */
+    source: string;
+}
+
+export interface EmailContact {
+    address: string;
+    source: string;
+}
+
+export interface PhoneContact {
+    /** As written on the page */
+    display: string;
+    /** Normalized `+15551234567`, when the number could be parsed */
+    e164: string | null;
+    source: string;
+}
+
+export interface PostalAddress {
+    street: string;
+    locality: string;
+    region: string;
+    postal_code: string;
+    country: string;
+    formatted: string;
+    source: string;
+}
+
+export interface ContactDetails {
+    emails: EmailContact[];
+    phones: PhoneContact[];
+    addresses: PostalAddress[];
+}
+
+export interface ManifestIcon {
+    src: string;
+    sizes: string;
+    width: number;
+    height: number;
+    type: string;
+    purpose: string;
+}
+
+export interface WebAppManifest {
+    url: string;
+    name: string;
+    short_name: string;
+    theme_color: string;
+    background_color: string;
+    icons: ManifestIcon[];
+}
+
+export interface IconInfo {
+    src: string;
+    /** "icon", "shortcut icon", "apple-touch-icon", "mask-icon", "manifest" or "favicon.ico" */
+    rel: string;
+    sizes: string;
+    declared_width: number;
+    declared_height: number;
+    /** Real dimensions read from the file header (0 when unknown or scalable) */
+    width: number;
+    height: number;
+    format: string;
+    /** `color` of a Safari mask icon */
+    color: string | null;
+    score: number;
+}
+
+export interface SvgColor {
+    hex: string;
+    rgb: Rgb;
+    /** Share of the painted area, 0–1 */
+    share: number;
+    /** File URL, or the inline SVG's id */
+    asset: string;
+}
+
+export interface InlineSvg {
+    /** Element id, accessible label, or a positional fallback (`inline-svg-3`) */
+    id: string;
+    /** `aria-label` or `<title>` text */
+    label: string;
+    view_box: string;
+    width: number;
+    height: number;
+    /** Sanitized, self-contained markup */
+    markup: string;
+    /** Fill, stroke and gradient colors, largest painted area first */
+    colors: SvgColor[];
+    /** Marked as the site logo */
+    logo: boolean;
+}
+
+export interface SpriteSymbol {
+    id: string;
+    view_box: string;
+}
+
+export interface SpriteSheet {
+    /** Sprite file URL, or the page URL for a sprite embedded in the document */
+    url: string;
+    inline: boolean;
+    symbols: SpriteSymbol[];
+    /** Symbol ids the page actually uses */
+    referenced: string[];
+}
+
+export interface IconFont {
+    /** Library name ("Font Awesome", "Bootstrap Icons") or "Custom icon font" */
+    name: string;
+    /** Class prefix, e.g. "fa" for `.fa-home::before` */
+    prefix: string;
+    glyph_count: number;
+    font_family: string;
+    stylesheet: string;
+}
+
+export interface IconInventory {
+    sprites: SpriteSheet[];
+    icon_fonts: IconFont[];
+}
+
+/** A brand color spelled out in page text */
+export interface ColorSpec {
+    /** Nearby heading or label; empty when none was found */
+    name: string;
+    hex: string;
+    rgb: Rgb;
+    /** CMYK percentages as published */
+    cmyk: [number, number, number, number] | null;
+    /** Pantone reference as published, e.g. "PMS 2955 C" */
+    pantone: string | null;
+    /** Every notation as written on the page */
+    notations: string[];
+}
+
+/** The closest entry of a named-color table */
+export interface ColorMatch {
+    name: string;
+    hex: string;
+    /** CIEDE2000 distance from the palette color */
+    delta_e: number;
+}
+
+export interface PaletteColor {
+    hex: string;
+    rgb: Rgb;
+    /** e.g. "manifest-theme", "theme-color", "text", "svg", "logo", "computed", "css-dark" */
+    source: string;
+    /** Declared by the site as a brand color rather than inferred from styles */
+    declared: boolean;
+    /** Image URL or inline SVG id the color was extracted from; empty for page-level sources */
+    asset: string;
+    /** Name the site gives the color ("Midnight Blue"), when it spells one out */
+    name: string;
+    /** Naive CMYK percentages (no ICC profile) */
+    cmyk: [number, number, number, number];
+    /** Closest CSS named color */
+    nearest_css: ColorMatch | null;
+    /** Closest entry of the user's spot-color table, when one is loaded */
+    nearest_spot: ColorMatch | null;
+    /** "primary", "secondary" or "accent" for the colors a scale is built from; empty otherwise */
+    role: string;
+}
+
+export interface ScaleStep {
+    /** 50–950 */
+    step: number;
+    hex: string;
+    rgb: Rgb;
+    /** WCAG contrast ratios, 1–21 */
+    contrast_white: number;
+    contrast_black: number;
+}
+
+/** A 50–950 tint/shade ramp generated from one brand color */
+export interface ColorScale {
+    /** "primary", "secondary" or "accent" */
+    role: string;
+    base: string;
+    /** Step the brand color sits at unchanged */
+    base_step: number;
+    steps: ScaleStep[];
+}
+
+/** The ramps as ready-to-paste design tokens, each step commented with CMYK, nearest CSS name and nearest spot color */
+export interface ScaleTokens {
+    css: string;
+    tailwind: string;
+}
+
+/** Two palette colors that become hard to tell apart with a color vision deficiency */
+export interface CvdConflict {
+    condition: "protanopia" | "deuteranopia" | "tritanopia" | "achromatopsia";
+    /** The two palette colors, in palette order */
+    colors: [string, string];
+    /** How they appear with the condition */
+    simulated: [string, string];
+    /** CIEDE2000 difference with typical vision */
+    delta_e: number;
+    /** CIEDE2000 difference with the condition */
+    simulated_delta_e: number;
+}
+
+/** A color custom property as one color scheme declares it */
+export interface ColorToken {
+    /** e.g. "--background" */
+    name: string;
+    /** As written, e.g. "var(--slate-900)" or "222 47% 11%" */
+    value: string;
+    hex: string;
+}
+
+/** Dark only lists the properties the dark theme overrides */
+export interface ColorTokens {
+    light: ColorToken[];
+    dark: ColorToken[];
+}
+
+/** Resolved text style of one role */
+export interface TextStyle {
+    /** "body", "h1"–"h6", "button" or "link" */
+    role: string;
+    /** First family in the stack that names a typeface */
+    family: string;
+    stack: string[];
+    /** As computed ("32px") or declared ("2rem") */
+    size: string;
+    size_px: number | null;
+    weight: number | null;
+    line_height: string;
+    letter_spacing: string;
+    text_transform: string;
+    /** "browser" (computed styles) or "css" (stylesheet rules) */
+    source: string;
+}
+
+export interface Typography {
+    styles: TextStyle[];
+    /** Body size in px */
+    base_size_px: number | null;
+    /** Median ratio between consecutive heading sizes (1.25 = major third) */
+    scale_ratio: number | null;
+}
+
+export interface VariationAxis {
+    /** e.g. "wght", "wdth", "opsz" */
+    tag: string;
+    name: string;
+    min: number;
+    default: number;
+    max: number;
+}
+
+/** What a font file says about itself in its `name`, `OS/2` and `fvar` tables */
+export interface FontFileInfo {
+    url: string;
+    /** "woff2", "woff", "ttf" or "otf" */
+    format: string;
+    family: string;
+    subfamily: string;
+    full_name: string;
+    version: string;
+    designer: string;
+    /** Manufacturer, usually the foundry */
+    foundry: string;
+    /** Four-letter foundry code (`OS/2` achVendID) */
+    vendor_id: string;
+    copyright: string;
+    trademark: string;
+    license: string;
+    license_url: string;
+    vendor_url: string;
+    /** 1–1000 */
+    weight_class: number | null;
+    /** Empty for static fonts */
+    axes: VariationAxis[];
+}
+
+/** One family the page names, classified by its place in the stacks and linked to its source */
+export interface FontFamily {
+    name: string;
+    /** "primary", "fallback" or "generic" */
+    role: string;
+    /** Preinstalled on common operating systems and not loaded as a web font */
+    system: boolean;
+    /** "google", "adobe", "bunny", "fontshare" or "self-hosted"; empty when not a web font */
+    provider: string;
+    stylesheets: string[];
+    /** Font files from its `@font-face` rules */
+    files: string[];
+    /** Elements whose stack includes the family */
+    usage: number;
+    /** What the downloaded font files say about themselves */
+    file_info: FontFileInfo[];
+}
+
+export interface AnalysisResult {
+    colors: string[];
+    /** Declared brand colors followed by computed styles, each tagged with its source */
+    palette: PaletteColor[];
+    fonts: string[];
+    images: ImageInfo[];
+    text_content: TextBlock[];
+    metadata: {
+        title: string;
+        description: string;
+        favicon: string;
+    };
+    social_profiles: SocialProfile[];
+    contacts: ContactDetails;
+    manifest: WebAppManifest | null;
+    /** Every site icon, best first; the first one is also `metadata.favicon` */
+    icons: IconInfo[];
+    /** Inline `<svg>` marks with no URL of their own */
+    inline_svgs: InlineSvg[];
+    /** SVG sprite sheets and icon fonts the site draws its UI icons from */
+    icon_sets: IconInventory;
+    /** Colors spelled out in page text (hex, RGB, CMYK, Pantone) */
+    color_specs: ColorSpec[];
+    /** 50–950 ramps for the palette's primary, secondary and accent colors */
+    color_scales: ColorScale[];
+    /** The ramps as CSS custom properties and a Tailwind colors object */
+    scale_tokens: ScaleTokens;
+    /** Palette pairs that become hard to tell apart with a color vision deficiency */
+    cvd_conflicts: CvdConflict[];
+    /** Dark-scheme colors: dark theme-color metas, then colors painted by dark-mode rules */
+    dark_palette: PaletteColor[];
+    /** Color custom properties for the light and dark schemes */
+    color_tokens: ColorTokens;
+    /** Family, size, weight, line height, letter spacing and case of body, headings, buttons and links */
+    typography: Typography;
+    /** Every family in the page's font stacks, with its provider */
+    font_families: FontFamily[];
+}
+
+/** Conversion applied to a download; omitted fields keep the file as served */
+export interface ConvertOptions {
+    format?: "png" | "jpeg" | "webp";
+    /** Favicon set, 1200×630 social card or square avatar */
+    preset?: "favicon" | "og" | "avatar";
+    /** With both set the image is cropped to fill; with one the other follows the aspect ratio */
+    width?: number;
+    height?: number;
+    /** JPEG quality, 1–100 */
+    quality?: number;
+}
+
+/** Payload of the `download-progress` event */
+export interface DownloadProgress {
+    /** The URL passed to `download_image` */
+    url: string;
+    received: number;
+    /** From `Content-Length`, when the server sends it */
+    total: number | null;
+    done: boolean;
+}
+
+/** One asset to include in a `download_assets` bundle */
+export interface AssetRequest {
+    kind: "image" | "icon" | "svg" | "font";
+    /** Remote URL; empty for captured inline SVGs */
+    url: string;
+    /** Inline SVG markup */
+    markup: string;
+    /** Preferred file name without directory */
+    name: string;
+}
+
+/** Optional switches for `analyze_page` */
+export interface AnalyzeOptions {
+    /** Group images by perceptual hash; downloads a sample of images, so off by default */
+    content_dedupe: boolean;
+}
+
+export type CopyFn = (text: string, id?: string) => void;
@@ -0,0 +1,30 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import type { DownloadProgress } from "./types";
+
+/* ── Latest `download-progress` event per requested URL, dropped once the download finishes ── */
+export function useDownloadProgress() {
+    const [progress, setProgress] = useState<Record<string, DownloadProgress>>({});
+
+    useEffect(() => {
+        const unlisten = listen<DownloadProgress>("download-progress", ({ payload }) => {
+            setProgress(current => {
+                const next = { ...current };
+                if (payload.done) delete next[payload.url];
+                else next[payload.url] = payload;
+                return next;
+            });
+        });
+        return () => {
+            unlisten.then(stop => stop());
+        };
+    }, []);
+
+    return progress;
+}
+
+/** "42%" when the size is known, else the bytes received so far */
+export function progressLabel({ received, total }: DownloadProgress) {
+    if (total) return `${Math.round((received / total) * 100)}%`;
+    return received < 1024 * 1024 ? `${Math.round(received / 1024)} KB` : `${(received / (1024 * 1024)).toFixed(1)} MB`;
+}